@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use clap::ValueHint;
+
+use aoc_solver::solution::Solution;
+use aoc_solver::y2020::day08::{find_fix, parse_program, Day08, Flag, GameConsole, Step};
+
+const HELP: &str = "Commands:
+  s, step [n]       execute the next n instructions, an empty line steps once
+  c, continue       run until a breakpoint or until the program stops
+  b, break <addr>   toggle a breakpoint at the address
+  l, list [addr]    list the instructions around the address
+  t, trace          print every executed instruction while running
+  f, fix            find the jmp or nop that terminates the program when flipped
+  r, reset          restart the program, keeping the breakpoints
+  p, print          print the program counter and the accumulator
+  q, quit           exit the debugger";
+
+#[derive(clap::Args, Debug)]
+pub struct ConsoleArgs {
+    /// Path to the program, the default input of 2020 day 8 is used without it
+    #[clap(short, long, value_hint = ValueHint::FilePath)]
+    file: Option<PathBuf>,
+
+    /// Print the trace of the whole program and exit instead of starting the debugger
+    #[clap(short, long, action)]
+    trace: bool,
+
+    /// Addresses to break at
+    #[clap(short, long)]
+    breakpoint: Vec<usize>,
+}
+
+pub fn debug(args: ConsoleArgs) -> Result<(), Box<dyn Error>> {
+    let input = match args.file {
+        Some(path) => fs::read_to_string(path)?,
+        None => Day08.default_input().to_string(),
+    };
+
+    let mut console = GameConsole::new(parse_program(&input)?);
+    console.breakpoints.extend(args.breakpoint);
+
+    if args.trace {
+        let flag = console.run(print_step);
+        print_state(&console, &flag);
+        return Ok(());
+    }
+
+    println!(
+        "{} instructions loaded, type \"help\" for commands",
+        console.mem.len()
+    );
+
+    let mut tracing = false;
+    let mut stdin = io::stdin().lock();
+    let mut line = String::new();
+
+    loop {
+        print!("{:04}> ", console.program_counter);
+        io::stdout().flush()?;
+
+        line.clear();
+        if stdin.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("step");
+        let arg = words.next().map(|word| word.parse::<usize>());
+
+        match (command, arg) {
+            ("s" | "step", arg) => {
+                let count = match arg {
+                    Some(Ok(count)) => count,
+                    Some(Err(err)) => {
+                        println!("Invalid count: {err}");
+                        continue;
+                    }
+                    None => 1,
+                };
+
+                for _ in 0..count {
+                    let program_counter = console.program_counter;
+                    let flag = console.step();
+
+                    if flag != Flag::Running {
+                        print_state(&console, &flag);
+                        break;
+                    }
+
+                    print_step(&Step {
+                        program_counter,
+                        instruction: console.mem[program_counter].clone(),
+                        accumulator: console.accumulator,
+                    });
+                }
+            }
+            ("c" | "continue", _) => {
+                let flag = console.run(|step| {
+                    if tracing {
+                        print_step(step)
+                    }
+                });
+                print_state(&console, &flag);
+            }
+            ("b" | "break", Some(Ok(address))) => {
+                if console.breakpoints.remove(&address) {
+                    println!("Removed the breakpoint at {address:04}");
+                } else {
+                    console.breakpoints.insert(address);
+                    println!("Added a breakpoint at {address:04}");
+                }
+            }
+            ("l" | "list", arg) => {
+                let address = match arg {
+                    Some(Ok(address)) => address,
+                    _ => console.program_counter,
+                };
+
+                for (index, instruction) in console
+                    .mem
+                    .iter()
+                    .enumerate()
+                    .skip(address.saturating_sub(5))
+                    .take(11)
+                {
+                    let marker = match (
+                        index == console.program_counter,
+                        console.breakpoints.contains(&index),
+                    ) {
+                        (true, _) => "->",
+                        (false, true) => " *",
+                        (false, false) => "  ",
+                    };
+                    println!("{marker} {index:04}  {instruction}");
+                }
+            }
+            ("t" | "trace", _) => {
+                tracing = !tracing;
+                println!("Tracing {}", if tracing { "on" } else { "off" });
+            }
+            ("f" | "fix", _) => match find_fix(&console.mem) {
+                Some(address) => println!(
+                    "Flipping \"{}\" at {address:04} terminates the program",
+                    console.mem[address]
+                ),
+                None => println!("No single flip terminates the program"),
+            },
+            ("r" | "reset", _) => {
+                console.reset();
+                print_state(&console, &Flag::Running);
+            }
+            ("p" | "print", _) => print_state(&console, &Flag::Running),
+            ("q" | "quit", _) => return Ok(()),
+            _ => println!("{HELP}"),
+        }
+    }
+}
+
+fn print_step(step: &Step) {
+    println!(
+        "{:04}  {:<8}  acc = {}",
+        step.program_counter,
+        step.instruction.to_string(),
+        step.accumulator
+    );
+}
+
+fn print_state(console: &GameConsole, flag: &Flag) {
+    println!(
+        "{flag:?}: pc = {:04}, acc = {}",
+        console.program_counter, console.accumulator
+    );
+}
@@ -1,19 +1,29 @@
-use clap::{Parser, ValueHint};
+use clap::{Parser, Subcommand, ValueHint};
 use env_logger::Env;
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use aoc_solver::solution::Solver;
+use log::info;
+
+use aoc_solver::registry;
+use aoc_solver::solution::{AocError, Part, Solver};
 use aoc_solver::y2020::Y2020;
 use aoc_solver::y2021::Y2021;
 use aoc_solver::y2022::Y2022;
 use aoc_solver::y2023::Y2023;
 
+mod console;
+mod render;
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Day of the Solution
     #[clap(short, long, value_name = "<1-25>")]
     day: Option<u8>,
@@ -31,27 +41,44 @@ struct Args {
     verbose: bool,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Render the frames of a grid visualization as an animated GIF or PNG images
+    Render(render::RenderArgs),
+    /// Trace and debug 2020 day 8 game console programs
+    Console(console::ConsoleArgs),
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = Args::parse();
     init_logger(cli.verbose);
 
+    match cli.command {
+        Some(Command::Render(args)) => return render::render(args),
+        Some(Command::Console(args)) => return console::debug(args),
+        None => {}
+    }
+
     match cli.day {
         None => {
-            match cli.year {
-                Some(2020) => {
-                    Y2020::run_all();
-                }
-                Some(2021) => {
-                    Y2021::run_all();
-                }
-                Some(2022) => {
-                    Y2022::run_all();
-                }
-                Some(2023) | None => {
-                    Y2023::run_all();
-                }
-                _ => unimplemented!(),
-            };
+            let year = cli.year.unwrap_or(2023);
+            let days = registry::days(year);
+            if days.is_empty() {
+                return Err(AocError::logic(format!("No solutions for {year}")).into());
+            }
+
+            // Timed per day to compare against the web dashboard
+            let mut total = Duration::ZERO;
+            for info in days {
+                let start = Instant::now();
+                registry::run_parts(year, info.day, None, &Part::ALL);
+                let duration = start.elapsed();
+                total += duration;
+
+                info!("[{year}/{:0>2}] {} ms", info.day, duration.as_millis());
+            }
+
+            info!("[{year}] Total {} ms", total.as_millis());
         }
         Some(day) => {
             let input = match cli.file {
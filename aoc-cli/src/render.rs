@@ -0,0 +1,207 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use clap::{ValueEnum, ValueHint};
+use log::info;
+
+use aoc_solver::registry;
+use aoc_solver::solution::AocError;
+use aoc_solver::visualization::Content;
+
+/// Colors of the tiles, the first one is the background
+const PALETTE: [[u8; 3]; 8] = [
+    [0x0f, 0x0f, 0x23],
+    [0xcc, 0xcc, 0xcc],
+    [0xff, 0xff, 0x66],
+    [0x00, 0x99, 0x00],
+    [0xff, 0x66, 0x66],
+    [0x66, 0x66, 0xff],
+    [0x66, 0xcc, 0xff],
+    [0xff, 0x66, 0xff],
+];
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Single animated GIF
+    Gif,
+    /// Numbered PNG image for every frame
+    Png,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RenderArgs {
+    /// Day of the Solution
+    #[clap(short, long, value_name = "<1-25>")]
+    day: u8,
+
+    /// Year of the Solution
+    #[clap(short, long, value_name = "<2020-2023>")]
+    year: u32,
+
+    /// Name of the visualization, as listed in the web app
+    #[clap(short, long)]
+    name: String,
+
+    /// Path to the input file, the default input of the day is used without it
+    #[clap(short, long, value_hint = ValueHint::FilePath)]
+    file: Option<PathBuf>,
+
+    /// Path of the GIF, or the directory of the PNG frames
+    #[clap(short, long, value_hint = ValueHint::AnyPath)]
+    output: PathBuf,
+
+    #[clap(long, value_enum, default_value_t = Format::Gif)]
+    format: Format,
+
+    /// Size of a single tile of the grid in pixels
+    #[clap(long, default_value_t = 4)]
+    scale: usize,
+
+    /// Delay between the frames of the GIF in milliseconds
+    #[clap(long, default_value_t = 50)]
+    delay: u16,
+
+    /// Frames after this are left out
+    #[clap(long, default_value_t = 2000)]
+    max_frames: usize,
+}
+
+/// Frame drawn as indices to the `PALETTE`
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+pub fn render(args: RenderArgs) -> Result<(), Box<dyn Error>> {
+    let input = args.file.map(fs::read_to_string).transpose()?;
+
+    let grids: Vec<Vec<String>> = registry::visualize(args.year, args.day, &args.name, input)?
+        .take(args.max_frames)
+        .map(|frame| match frame.content {
+            Content::Grid(rows) => Ok(rows),
+            _ => Err(AocError::logic("Only grid visualizations can be rendered")),
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Grids can change size between frames, so they're all drawn on the largest canvas
+    let width = grids
+        .iter()
+        .flat_map(|rows| rows.iter().map(|row| row.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let height = grids.iter().map(|rows| rows.len()).max().unwrap_or(0);
+
+    if width == 0 || height == 0 {
+        return Err(AocError::logic("Nothing to render").into());
+    }
+
+    let images = grids
+        .iter()
+        .map(|rows| rasterize(rows, width, height, args.scale.max(1)));
+
+    match args.format {
+        Format::Gif => write_gif(&args.output, images, args.delay)?,
+        Format::Png => write_pngs(&args.output, images)?,
+    }
+
+    info!(
+        "[{}/{:0>2}] Rendered {} frames of \"{}\" to {}",
+        args.year,
+        args.day,
+        grids.len(),
+        args.name,
+        args.output.display()
+    );
+
+    Ok(())
+}
+
+fn color(tile: char) -> u8 {
+    match tile {
+        '.' | ' ' => 0,
+        '#' => 1,
+        'o' | 'O' | '@' | 'E' => 2,
+        '+' => 3,
+        _ => 4 + (tile as u32 % 4) as u8,
+    }
+}
+
+fn rasterize(rows: &[String], width: usize, height: usize, scale: usize) -> Image {
+    let (width, height) = (width * scale, height * scale);
+    let mut pixels = vec![0; width * height];
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, tile) in row.chars().enumerate() {
+            let color = color(tile);
+
+            for dy in 0..scale {
+                let start = (y * scale + dy) * width + x * scale;
+                pixels[start..start + scale].fill(color);
+            }
+        }
+    }
+
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+fn dimension(size: usize) -> Result<u16, AocError> {
+    u16::try_from(size).map_err(|_| AocError::logic("The image is too large, try a smaller scale"))
+}
+
+fn write_gif(
+    path: &Path,
+    images: impl Iterator<Item = Image>,
+    delay: u16,
+) -> Result<(), Box<dyn Error>> {
+    let mut encoder = None;
+
+    for image in images {
+        let (width, height) = (dimension(image.width)?, dimension(image.height)?);
+
+        let encoder = match &mut encoder {
+            Some(encoder) => encoder,
+            None => {
+                let file = BufWriter::new(File::create(path)?);
+                let mut created = gif::Encoder::new(file, width, height, &PALETTE.concat())?;
+                created.set_repeat(gif::Repeat::Infinite)?;
+                encoder.insert(created)
+            }
+        };
+
+        encoder.write_frame(&gif::Frame {
+            width,
+            height,
+            // The delay of a GIF frame is in hundredths of a second
+            delay: delay / 10,
+            buffer: Cow::Owned(image.pixels),
+            ..gif::Frame::default()
+        })?;
+    }
+
+    Ok(())
+}
+
+fn write_pngs(directory: &Path, images: impl Iterator<Item = Image>) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(directory)?;
+
+    for (index, image) in images.enumerate() {
+        let file = BufWriter::new(File::create(directory.join(format!("{index:05}.png")))?);
+
+        let mut encoder = png::Encoder::new(file, image.width as u32, image.height as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(PALETTE.concat());
+
+        encoder.write_header()?.write_image_data(&image.pixels)?;
+    }
+
+    Ok(())
+}
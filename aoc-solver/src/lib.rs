@@ -1,5 +1,7 @@
+pub mod registry;
 pub mod solution;
 pub mod utils;
+pub mod visualization;
 
 pub mod y2020;
 pub mod y2021;
@@ -0,0 +1,139 @@
+use crate::solution::{AocError, DayInfo, Part, Solver};
+use crate::visualization::{unknown_visualization, Frames, Visualize};
+use crate::{y2020, y2020::Y2020, y2021, y2021::Y2021, y2022, y2022::Y2022, y2023, y2023::Y2023};
+
+/// Years with solutions, newest first.
+pub const YEARS: &[u32] = &[2023, 2022, 2021, 2020];
+
+/// Implemented days of the year, in order.
+pub fn days(year: u32) -> &'static [DayInfo] {
+    match year {
+        2020 => Y2020::days(),
+        2021 => Y2021::days(),
+        2022 => Y2022::days(),
+        2023 => Y2023::days(),
+        _ => &[],
+    }
+}
+
+pub fn find_day(year: u32, day: u8) -> Option<&'static DayInfo> {
+    days(year).iter().find(|info| info.day == day)
+}
+
+pub fn run_parts(year: u32, day: u8, input: Option<String>, parts: &[Part]) -> Vec<String> {
+    match year {
+        2020 => Y2020::run_parts(day, input, parts),
+        2021 => Y2021::run_parts(day, input, parts),
+        2022 => Y2022::run_parts(day, input, parts),
+        2023 => Y2023::run_parts(day, input, parts),
+        _ => vec![String::from("Missing year")],
+    }
+}
+
+pub fn get_source(year: u32, day: u8) -> &'static str {
+    match year {
+        2020 => Y2020::get_source(day),
+        2021 => Y2021::get_source(day),
+        2022 => Y2022::get_source(day),
+        2023 => Y2023::get_source(day),
+        _ => "",
+    }
+}
+
+/// Frames of a visualization, using the default input of the day when no input is given.
+pub fn visualize(
+    year: u32,
+    day: u8,
+    name: &str,
+    input: Option<String>,
+) -> Result<Frames, AocError> {
+    match (year, day) {
+        (2020, 11) => y2020::day11::Day11.visualize_default(name, input),
+        (2021, 15) => y2021::day15::Day15.visualize_default(name, input),
+        (2022, 9) => y2022::day09::Day09.visualize_default(name, input),
+        (2022, 12) => y2022::day12::Day12.visualize_default(name, input),
+        (2022, 14) => y2022::day14::Day14.visualize_default(name, input),
+        (2022, 17) => y2022::day17::Day17.visualize_default(name, input),
+        (2022, 18) => y2022::day18::Day18.visualize_default(name, input),
+        (2022, 22) => y2022::day22::Day22.visualize_default(name, input),
+        (2022, 24) => y2022::day24::Day24.visualize_default(name, input),
+        (2023, 10) => y2023::day10::Day10.visualize_default(name, input),
+        (2023, 14) => y2023::day14::Day14.visualize_default(name, input),
+        (2023, 16) => y2023::day16::Day16.visualize_default(name, input),
+        (2023, 17) => y2023::day17::Day17.visualize_default(name, input),
+        (2023, 20) => y2023::day20::Day20.visualize_default(name, input),
+        (2023, 21) => y2023::day21::Day21.visualize_default(name, input),
+        (2023, 25) => y2023::day25::Day25.visualize_default(name, input),
+        _ => Err(unknown_visualization(name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lists_days_in_order() {
+        for year in YEARS {
+            let days = days(*year);
+            assert!(!days.is_empty());
+            assert!(days.windows(2).all(|pair| pair[0].day < pair[1].day));
+            assert!(days.iter().all(|info| (1..=25).contains(&info.day)));
+        }
+    }
+
+    #[test]
+    fn it_dispatches_exactly_the_listed_days() {
+        for year in YEARS {
+            for day in 1..=25 {
+                // Without parts nothing is solved, only the dispatch is checked
+                let dispatched = run_parts(*year, day, Some(String::new()), &[]).is_empty();
+                assert_eq!(
+                    dispatched,
+                    find_day(*year, day).is_some(),
+                    "{year} day {day} is listed but not dispatched, or the other way around"
+                );
+
+                if dispatched {
+                    assert!(!get_source(*year, day).is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_days_and_visualizations() {
+        assert_eq!(
+            find_day(2022, 9).map(|info| info.visualizations),
+            Some(&["rope"][..])
+        );
+        assert_eq!(find_day(2021, 19), None);
+        assert_eq!(find_day(2019, 1), None);
+    }
+
+    #[test]
+    fn it_runs_single_parts() {
+        assert_eq!(
+            run_parts(2023, 1, Some(String::from("1abc2")), &[Part::One]),
+            vec![String::from("[2023/01][Part 1] 12")]
+        );
+    }
+
+    #[test]
+    fn it_visualizes_with_default_input() {
+        assert!(visualize(2022, 18, "lava", None).unwrap().count() > 0);
+        assert!(visualize(2022, 18, "rope", None).is_err());
+        assert!(visualize(2023, 1, "lava", None).is_err());
+    }
+
+    #[test]
+    fn it_resolves_listed_visualizations() {
+        for year in YEARS {
+            for info in days(*year) {
+                for name in info.visualizations {
+                    assert!(visualize(*year, info.day, name, None).is_ok());
+                }
+            }
+        }
+    }
+}
@@ -31,6 +31,25 @@ impl std::fmt::Display for AocError {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "Part 1"),
+            Part::Two => write!(f, "Part 2"),
+        }
+    }
+}
+
 pub trait Solution {
     type A: fmt::Display;
     type B: fmt::Display;
@@ -40,42 +59,72 @@ pub trait Solution {
     fn part_1(&self, input: &str) -> Result<Self::A, AocError>;
     fn part_2(&self, input: &str) -> Result<Self::B, AocError>;
 
-    fn run(&self, input: Option<String>, day: u32, year: u32) -> Vec<String> {
-        let input = input.unwrap_or_else(|| self.default_input().to_owned());
-        let mut output = Vec::new();
-
-        match self.part_1(&input) {
-            Ok(result) => {
-                let logline = format!("[{year}/{day:0>2}][Part 1] {result}");
-                info!("{logline}");
-                output.push(logline);
-            }
-            Err(err) => {
-                let logline = format!("[{year}/{day:0>2}][Part 1] Error: {err}");
-                error!("{logline}");
-                output.push(logline);
-            }
+    fn run_part(&self, part: Part, input: &str, day: u32, year: u32) -> String {
+        let result = match part {
+            Part::One => self.part_1(input).map(|result| result.to_string()),
+            Part::Two => self.part_2(input).map(|result| result.to_string()),
         };
 
-        match self.part_2(&input) {
+        match result {
             Ok(result) => {
-                let logline = format!("[{year}/{day:0>2}][Part 2] {result}");
+                let logline = format!("[{year}/{day:0>2}][{part}] {result}");
                 info!("{logline}");
-                output.push(logline);
+                logline
             }
             Err(err) => {
-                let logline = format!("[{year}/{day:0>2}][Part 2] Error: {err}");
+                let logline = format!("[{year}/{day:0>2}][{part}] Error: {err}");
                 error!("{logline}");
-                output.push(logline);
+                logline
             }
-        };
+        }
+    }
+
+    fn run_parts(&self, input: Option<String>, day: u32, year: u32, parts: &[Part]) -> Vec<String> {
+        let input = input.unwrap_or_else(|| self.default_input().to_owned());
+
+        parts
+            .iter()
+            .map(|part| self.run_part(*part, &input, day, year))
+            .collect()
+    }
+
+    fn run(&self, input: Option<String>, day: u32, year: u32) -> Vec<String> {
+        self.run_parts(input, day, year, &Part::ALL)
+    }
+}
+
+/// Metadata of an implemented day.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DayInfo {
+    pub day: u8,
+    /// Names of the extra visualizations the web app has for this day
+    pub visualizations: &'static [&'static str],
+}
+
+impl DayInfo {
+    pub const fn new(day: u8) -> Self {
+        DayInfo {
+            day,
+            visualizations: &[],
+        }
+    }
 
-        output
+    pub const fn with_visualizations(day: u8, visualizations: &'static [&'static str]) -> Self {
+        DayInfo {
+            day,
+            visualizations,
+        }
     }
 }
 
 pub trait Solver {
-    fn run_solution(day: u8, input: Option<String>) -> Vec<String>;
+    fn run_parts(day: u8, input: Option<String>, parts: &[Part]) -> Vec<String>;
+
+    fn run_solution(day: u8, input: Option<String>) -> Vec<String> {
+        Self::run_parts(day, input, &Part::ALL)
+    }
+
     fn run_all() -> Vec<String>;
     fn get_source(day: u8) -> &'static str;
+    fn days() -> &'static [DayInfo];
 }
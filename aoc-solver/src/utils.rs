@@ -1,3 +1,12 @@
+pub mod automaton;
+pub mod balanced;
+pub mod geometry;
+pub mod grammar;
+pub mod hex;
+pub mod linalg;
+pub mod memo;
+pub mod search;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Coords<T> {
     pub x: T,
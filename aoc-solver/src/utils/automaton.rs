@@ -0,0 +1,490 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::utils::hex::Hex;
+use crate::utils::Coords;
+
+/// Describes which cells are considered neighbours of each other.
+pub trait Topology {
+    type Cell: Copy + Eq + Hash;
+
+    fn neighbours(&self, cell: &Self::Cell) -> Vec<Self::Cell>;
+}
+
+/// Flat square grid, with or without the diagonal neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Square {
+    pub diagonals: bool,
+}
+
+impl Square {
+    pub const ORTHOGONAL: Square = Square { diagonals: false };
+    pub const WITH_DIAGONALS: Square = Square { diagonals: true };
+
+    const ORTHOGONAL_OFFSETS: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+    #[rustfmt::skip]
+    const DIAGONAL_OFFSETS: [(i32, i32); 8] = [
+        (-1, -1), (0, -1), (1, -1),
+        (-1,  0),          (1,  0),
+        (-1,  1), (0,  1), (1,  1),
+    ];
+
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        if self.diagonals {
+            &Self::DIAGONAL_OFFSETS
+        } else {
+            &Self::ORTHOGONAL_OFFSETS
+        }
+    }
+}
+
+impl Topology for Square {
+    type Cell = Coords<i32>;
+
+    fn neighbours(&self, cell: &Coords<i32>) -> Vec<Coords<i32>> {
+        self.offsets()
+            .iter()
+            .map(|(x, y)| Coords {
+                x: cell.x + x,
+                y: cell.y + y,
+            })
+            .collect()
+    }
+}
+
+/// Cube, hypercube or any other `N` dimensional grid where every cell
+/// within the distance of one on each axis is a neighbour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hypercube<const N: usize> {
+    offsets: Vec<[i32; N]>,
+}
+
+impl<const N: usize> Hypercube<N> {
+    pub fn new() -> Self {
+        let mut offsets = vec![[0; N]];
+
+        for axis in 0..N {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|offset| {
+                    [-1, 0, 1].into_iter().map(move |delta| {
+                        let mut next = offset;
+                        next[axis] = delta;
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        offsets.retain(|offset| offset.iter().any(|delta| *delta != 0));
+
+        Hypercube { offsets }
+    }
+}
+
+impl<const N: usize> Default for Hypercube<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Topology for Hypercube<N> {
+    type Cell = [i32; N];
+
+    fn neighbours(&self, cell: &[i32; N]) -> Vec<[i32; N]> {
+        self.offsets
+            .iter()
+            .map(|offset| {
+                let mut neighbour = *cell;
+                for (value, delta) in neighbour.iter_mut().zip(offset) {
+                    *value += delta;
+                }
+                neighbour
+            })
+            .collect()
+    }
+}
+
+/// Hexagonal grid where each [`Hex`] has six neighbours.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hexagonal;
+
+impl Topology for Hexagonal {
+    type Cell = Hex;
+
+    fn neighbours(&self, cell: &Hex) -> Vec<Hex> {
+        cell.neighbours().to_vec()
+    }
+}
+
+/// Summary of a single simulated generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Generation {
+    pub generation: u32,
+    pub changed: usize,
+}
+
+impl Generation {
+    pub fn is_stable(&self) -> bool {
+        self.changed == 0
+    }
+}
+
+pub trait Automaton {
+    fn generation(&self) -> u32;
+    fn step(&mut self) -> Generation;
+
+    fn run(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation no longer changes any cells, at most `max_generations` times.
+    /// Returns the summary of that last, unchanged generation, or `None` if the automaton
+    /// didn't settle in time, eg. because the rule makes it oscillate.
+    fn run_until_stable(&mut self, max_generations: u32) -> Option<Generation> {
+        (0..max_generations)
+            .map(|_| self.step())
+            .find(Generation::is_stable)
+    }
+
+    /// Lazily steps the automaton, yielding each generation as it gets simulated.
+    /// The current state can be inspected between the steps.
+    fn generations(&mut self) -> Generations<'_, Self>
+    where
+        Self: Sized,
+    {
+        Generations { automaton: self }
+    }
+}
+
+pub struct Generations<'a, A: Automaton> {
+    automaton: &'a mut A,
+}
+
+impl<'a, A: Automaton> Iterator for Generations<'a, A> {
+    type Item = Generation;
+
+    fn next(&mut self) -> Option<Generation> {
+        Some(self.automaton.step())
+    }
+}
+
+/// Two state automaton storing only the live cells, growing without bounds.
+/// The rule receives whether the cell is alive and the count of its live neighbours.
+pub struct SparseAutomaton<T, F>
+where
+    T: Topology,
+    F: Fn(bool, usize) -> bool,
+{
+    topology: T,
+    rule: F,
+    cells: HashSet<T::Cell>,
+    generation: u32,
+}
+
+impl<T, F> SparseAutomaton<T, F>
+where
+    T: Topology,
+    F: Fn(bool, usize) -> bool,
+{
+    pub fn new(topology: T, cells: impl IntoIterator<Item = T::Cell>, rule: F) -> Self {
+        SparseAutomaton {
+            topology,
+            rule,
+            cells: cells.into_iter().collect(),
+            generation: 0,
+        }
+    }
+
+    pub fn cells(&self) -> &HashSet<T::Cell> {
+        &self.cells
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_alive(&self, cell: &T::Cell) -> bool {
+        self.cells.contains(cell)
+    }
+
+    pub fn live_neighbours(&self, cell: &T::Cell) -> usize {
+        self.topology
+            .neighbours(cell)
+            .iter()
+            .filter(|neighbour| self.cells.contains(neighbour))
+            .count()
+    }
+}
+
+impl<T, F> Automaton for SparseAutomaton<T, F>
+where
+    T: Topology,
+    F: Fn(bool, usize) -> bool,
+{
+    fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    fn step(&mut self) -> Generation {
+        // The only cells that can change their state are the live cells
+        // and the cells within the distance of one from them.
+        let mut live_neighbours: HashMap<T::Cell, usize> =
+            self.cells.iter().map(|cell| (*cell, 0)).collect();
+
+        for cell in self.cells.iter() {
+            for neighbour in self.topology.neighbours(cell) {
+                *live_neighbours.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        let next: HashSet<T::Cell> = live_neighbours
+            .into_iter()
+            .filter(|(cell, count)| (self.rule)(self.cells.contains(cell), *count))
+            .map(|(cell, _)| cell)
+            .collect();
+
+        let changed = next.symmetric_difference(&self.cells).count();
+
+        self.cells = next;
+        self.generation += 1;
+
+        Generation {
+            generation: self.generation,
+            changed,
+        }
+    }
+}
+
+/// Fixed size square grid storing every cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<S> {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<S>,
+}
+
+impl<S> Grid<S> {
+    pub fn parse(input: &str, parse_cell: impl Fn(char) -> S) -> Self {
+        let height = input.lines().count();
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        let cells = input
+            .lines()
+            .flat_map(|line| line.chars())
+            .map(parse_cell)
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn get(&self, coords: Coords<i32>) -> Option<&S> {
+        if coords.x < 0
+            || coords.y < 0
+            || coords.x as usize >= self.width
+            || coords.y as usize >= self.height
+        {
+            return None;
+        }
+
+        self.cells
+            .get(coords.y as usize * self.width + coords.x as usize)
+    }
+
+    pub fn neighbours(&self, topology: &Square, coords: Coords<i32>) -> impl Iterator<Item = &S> {
+        topology
+            .neighbours(&coords)
+            .into_iter()
+            .filter_map(|neighbour| self.get(neighbour))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coords<i32>, &S)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(index, cell)| {
+            let coords = Coords {
+                x: (index % width) as i32,
+                y: (index / width) as i32,
+            };
+            (coords, cell)
+        })
+    }
+}
+
+/// Automaton over a bounded [`Grid`] with any number of cell states.
+/// The rule receives the current grid, the coordinates of the cell and its state,
+/// letting it look further than the immediate neighbours when needed.
+pub struct DenseAutomaton<S, F>
+where
+    S: Clone + PartialEq,
+    F: Fn(&Grid<S>, Coords<i32>, &S) -> S,
+{
+    rule: F,
+    grid: Grid<S>,
+    generation: u32,
+}
+
+impl<S, F> DenseAutomaton<S, F>
+where
+    S: Clone + PartialEq,
+    F: Fn(&Grid<S>, Coords<i32>, &S) -> S,
+{
+    pub fn new(grid: Grid<S>, rule: F) -> Self {
+        DenseAutomaton {
+            rule,
+            grid,
+            generation: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<S> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<S> {
+        self.grid
+    }
+}
+
+impl<S, F> Automaton for DenseAutomaton<S, F>
+where
+    S: Clone + PartialEq,
+    F: Fn(&Grid<S>, Coords<i32>, &S) -> S,
+{
+    fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    fn step(&mut self) -> Generation {
+        let cells: Vec<S> = self
+            .grid
+            .iter()
+            .map(|(coords, state)| (self.rule)(&self.grid, coords, state))
+            .collect();
+
+        let changed = cells
+            .iter()
+            .zip(self.grid.cells.iter())
+            .filter(|(next, current)| next != current)
+            .count();
+
+        self.grid.cells = cells;
+        self.generation += 1;
+
+        Generation {
+            generation: self.generation,
+            changed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: bool, neighbours: usize) -> bool {
+        neighbours == 3 || alive && neighbours == 2
+    }
+
+    #[test]
+    fn it_builds_hypercube_neighbourhoods() {
+        assert_eq!(Hypercube::<2>::new().neighbours(&[0, 0]).len(), 8);
+        assert_eq!(Hypercube::<3>::new().neighbours(&[0, 0, 0]).len(), 26);
+        assert_eq!(Hypercube::<4>::new().neighbours(&[0, 0, 0, 0]).len(), 80);
+        assert!(!Hypercube::<3>::new()
+            .neighbours(&[1, 2, 3])
+            .contains(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn it_oscillates_sparse_blinker() {
+        let mut automaton = SparseAutomaton::new(
+            Square::WITH_DIAGONALS,
+            [(0, -1), (0, 0), (0, 1)].map(|(x, y)| Coords { x, y }),
+            life,
+        );
+
+        assert_eq!(
+            automaton.step(),
+            Generation {
+                generation: 1,
+                changed: 4
+            }
+        );
+        assert!(automaton.is_alive(&Coords { x: -1, y: 0 }));
+        assert!(automaton.is_alive(&Coords { x: 1, y: 0 }));
+        assert_eq!(automaton.population(), 3);
+
+        automaton.step();
+        assert!(automaton.is_alive(&Coords { x: 0, y: -1 }));
+        assert_eq!(automaton.generation(), 2);
+
+        assert_eq!(automaton.run_until_stable(10), None);
+        assert_eq!(automaton.generation(), 12);
+    }
+
+    #[test]
+    fn it_streams_generations_until_stable() {
+        // A block is a still life, a lonely cell dies out on the first generation
+        let mut automaton = SparseAutomaton::new(
+            Hypercube::<2>::new(),
+            [[0, 0], [0, 1], [1, 0], [1, 1], [5, 5]],
+            life,
+        );
+
+        let changes: Vec<usize> = automaton
+            .generations()
+            .take_while(|generation| !generation.is_stable())
+            .map(|generation| generation.changed)
+            .collect();
+
+        assert_eq!(changes, vec![1]);
+        assert_eq!(automaton.population(), 4);
+
+        assert_eq!(
+            automaton.run_until_stable(10),
+            Some(Generation {
+                generation: 3,
+                changed: 0
+            })
+        );
+    }
+
+    #[test]
+    fn it_runs_dense_automaton() {
+        let grid = Grid::parse(
+            ".#.\n\
+             .#.\n\
+             .#.",
+            |c| c == '#',
+        );
+
+        let mut automaton = DenseAutomaton::new(grid, |grid, coords, alive| {
+            let neighbours = grid
+                .neighbours(&Square::WITH_DIAGONALS, coords)
+                .filter(|neighbour| **neighbour)
+                .count();
+            life(*alive, neighbours)
+        });
+
+        automaton.run(1);
+        assert_eq!(
+            automaton.grid(),
+            &Grid::parse(
+                "...\n\
+                 ###\n\
+                 ...",
+                |c| c == '#'
+            )
+        );
+
+        let generation = automaton.step();
+        assert_eq!(generation.generation, 2);
+        assert_eq!(generation.changed, 4);
+    }
+}
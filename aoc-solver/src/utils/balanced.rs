@@ -0,0 +1,365 @@
+use std::fmt;
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use num::{BigInt, Integer, Signed, ToPrimitive, Zero};
+
+use crate::solution::AocError;
+
+/// Digit symbols of a base, starting from the smallest digit `MIN`. The base is the
+/// number of symbols, and `MIN` has to be negative while the largest digit is positive,
+/// which is checked at compile time.
+pub trait Digits {
+    const SYMBOLS: &'static [u8];
+    const MIN: i8;
+
+    fn base() -> i8 {
+        Self::SYMBOLS.len() as i8
+    }
+}
+
+/// Special Numeral-Analogue Fuel Units, base 5 with digits from -2 to 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Snafu;
+
+impl Digits for Snafu {
+    const SYMBOLS: &'static [u8] = b"=-012";
+    const MIN: i8 = -2;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Ternary;
+
+impl Digits for Ternary {
+    const SYMBOLS: &'static [u8] = b"-0+";
+    const MIN: i8 = -1;
+}
+
+/// Integer in a balanced base. The digits are unbounded, so arithmetic never overflows.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BalancedBase<D: Digits> {
+    /// Least significant digit first, without leading zeros
+    digits: Vec<i8>,
+    base: PhantomData<D>,
+}
+
+impl<D: Digits> BalancedBase<D> {
+    /// Checked when the base is used, so that a base whose columns can't carry both ways
+    /// fails to compile instead of never finishing normalization
+    const BASE: i128 = {
+        assert!(
+            D::MIN < 0 && D::MIN as isize + D::SYMBOLS.len() as isize - 1 > 0,
+            "digits must range from a negative to a positive digit"
+        );
+        D::SYMBOLS.len() as i128
+    };
+
+    pub fn zero() -> Self {
+        BalancedBase {
+            digits: Vec::new(),
+            base: PhantomData,
+        }
+    }
+
+    /// Carries the column sums over to the next columns until every digit is valid
+    fn normalize(columns: impl IntoIterator<Item = i128>) -> Self {
+        let base = Self::BASE;
+        let min = D::MIN as i128;
+
+        let mut digits = Vec::new();
+        let mut columns = columns.into_iter();
+        let mut carry = 0;
+
+        loop {
+            let column = match columns.next() {
+                Some(column) => column + carry,
+                None if carry != 0 => carry,
+                None => break,
+            };
+
+            let digit = (column - min).rem_euclid(base) + min;
+            carry = (column - digit) / base;
+            digits.push(digit as i8);
+        }
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        BalancedBase {
+            digits,
+            base: PhantomData,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The most significant digit outweighs all of the others, so it decides the sign
+    pub fn signum(&self) -> i8 {
+        self.digits.last().map_or(0, |digit| digit.signum())
+    }
+
+    /// Digits from the least significant one
+    pub fn digits(&self) -> &[i8] {
+        &self.digits
+    }
+}
+
+impl<D: Digits> FromStr for BalancedBase<D> {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(AocError::parse(s, "empty number"));
+        }
+
+        let columns = s
+            .bytes()
+            .rev()
+            .map(|symbol| {
+                D::SYMBOLS
+                    .iter()
+                    .position(|digit| *digit == symbol)
+                    .map(|index| index as i128 + D::MIN as i128)
+                    .ok_or_else(|| AocError::parse(s, format!("unknown digit {}", symbol as char)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::normalize(columns))
+    }
+}
+
+impl<D: Digits> fmt::Display for BalancedBase<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "{}", D::SYMBOLS[-D::MIN as usize] as char);
+        }
+
+        for digit in self.digits.iter().rev() {
+            write!(f, "{}", D::SYMBOLS[(digit - D::MIN) as usize] as char)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D: Digits> From<i64> for BalancedBase<D> {
+    fn from(value: i64) -> Self {
+        Self::normalize([value as i128])
+    }
+}
+
+impl<D: Digits> From<&BigInt> for BalancedBase<D> {
+    fn from(value: &BigInt) -> Self {
+        let base = BigInt::from(D::base());
+        let mut columns = Vec::new();
+        let mut value = value.clone();
+
+        // Each remainder is carried over to the next columns by normalizing
+        while !value.is_zero() {
+            let (quotient, remainder) = value.div_rem(&base);
+            columns.push(remainder.to_i128().unwrap_or_default());
+            value = quotient;
+        }
+
+        Self::normalize(columns)
+    }
+}
+
+impl<D: Digits> From<&BalancedBase<D>> for BigInt {
+    fn from(value: &BalancedBase<D>) -> Self {
+        value
+            .digits
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, digit| acc * D::base() + digit)
+    }
+}
+
+impl<D: Digits> TryFrom<&BalancedBase<D>> for i64 {
+    type Error = AocError;
+
+    fn try_from(value: &BalancedBase<D>) -> Result<Self, Self::Error> {
+        // The partial sums can be out of the range even if the value itself isn't
+        value
+            .digits
+            .iter()
+            .rev()
+            .try_fold(0_i128, |acc, digit| {
+                acc.checked_mul(D::base() as i128)
+                    .and_then(|acc| acc.checked_add(*digit as i128))
+            })
+            .and_then(|value| i64::try_from(value).ok())
+            .ok_or_else(|| AocError::logic(format!("{value} does not fit in i64")))
+    }
+}
+
+impl<D: Digits> Add for &BalancedBase<D> {
+    type Output = BalancedBase<D>;
+
+    fn add(self, other: Self) -> BalancedBase<D> {
+        let len = self.digits.len().max(other.digits.len());
+        let digit = |digits: &[i8], index: usize| digits.get(index).copied().unwrap_or(0) as i128;
+
+        BalancedBase::normalize((0..len).map(|i| digit(&self.digits, i) + digit(&other.digits, i)))
+    }
+}
+
+impl<D: Digits> Neg for &BalancedBase<D> {
+    type Output = BalancedBase<D>;
+
+    fn neg(self) -> BalancedBase<D> {
+        BalancedBase::normalize(self.digits.iter().map(|digit| -(*digit as i128)))
+    }
+}
+
+impl<D: Digits> Sub for &BalancedBase<D> {
+    type Output = BalancedBase<D>;
+
+    fn sub(self, other: Self) -> BalancedBase<D> {
+        self + &-other
+    }
+}
+
+impl<D: Digits> Mul for &BalancedBase<D> {
+    type Output = BalancedBase<D>;
+
+    fn mul(self, other: Self) -> BalancedBase<D> {
+        if self.is_zero() || other.is_zero() {
+            return BalancedBase::zero();
+        }
+
+        let mut columns = vec![0_i128; self.digits.len() + other.digits.len() - 1];
+        for (i, a) in self.digits.iter().enumerate() {
+            for (j, b) in other.digits.iter().enumerate() {
+                columns[i + j] += *a as i128 * *b as i128;
+            }
+        }
+
+        BalancedBase::normalize(columns)
+    }
+}
+
+macro_rules! forward_owned {
+    ($($trait:ident $method:ident),*) => {
+        $(
+            impl<D: Digits> $trait for BalancedBase<D> {
+                type Output = BalancedBase<D>;
+
+                fn $method(self, other: Self) -> BalancedBase<D> {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned!(Add add, Sub sub, Mul mul);
+
+impl<D: Digits> Neg for BalancedBase<D> {
+    type Output = BalancedBase<D>;
+
+    fn neg(self) -> BalancedBase<D> {
+        -&self
+    }
+}
+
+impl<D: Digits> AddAssign<&BalancedBase<D>> for BalancedBase<D> {
+    fn add_assign(&mut self, other: &BalancedBase<D>) {
+        *self = &*self + other;
+    }
+}
+
+impl<D: Digits> Sum for BalancedBase<D> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, value| &acc + &value)
+    }
+}
+
+impl<'a, D: Digits + 'a> Sum<&'a BalancedBase<D>> for BalancedBase<D> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, value| &acc + value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Base 4 with digits from -1 to 2, which isn't symmetric
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    struct Quaternary;
+
+    impl Digits for Quaternary {
+        const SYMBOLS: &'static [u8] = b"-012";
+        const MIN: i8 = -1;
+    }
+
+    fn snafu(value: &str) -> BalancedBase<Snafu> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn it_parses_and_prints() {
+        assert_eq!(snafu("1=-0-2").to_string(), "1=-0-2");
+        assert_eq!(snafu("001=").to_string(), "1=");
+        assert_eq!(snafu("0").to_string(), "0");
+        assert_eq!(BalancedBase::<Ternary>::from(-5).to_string(), "-++");
+        assert_eq!(BalancedBase::<Quaternary>::from(-5).to_string(), "--");
+
+        assert!("".parse::<BalancedBase<Snafu>>().is_err());
+        assert!("12a".parse::<BalancedBase<Snafu>>().is_err());
+    }
+
+    #[test]
+    fn it_converts_integers() {
+        for value in -500..=500 {
+            let number = BalancedBase::<Snafu>::from(value);
+            assert_eq!(i64::try_from(&number), Ok(value));
+            assert_eq!(BigInt::from(&number), BigInt::from(value));
+            assert_eq!(number.signum() as i64, value.signum());
+
+            let number = BalancedBase::<Quaternary>::from(&BigInt::from(value));
+            assert_eq!(i64::try_from(&number), Ok(value));
+        }
+
+        for value in [i64::MIN, i64::MAX] {
+            assert_eq!(
+                i64::try_from(&BalancedBase::<Ternary>::from(value)),
+                Ok(value)
+            );
+        }
+    }
+
+    #[test]
+    fn it_calculates_natively() {
+        for a in -40..=40 {
+            for b in -40..=40 {
+                let (x, y) = (BalancedBase::<Snafu>::from(a), BalancedBase::from(b));
+                assert_eq!(&x + &y, BalancedBase::from(a + b));
+                assert_eq!(&x - &y, BalancedBase::from(a - b));
+                assert_eq!(&x * &y, BalancedBase::from(a * b));
+
+                let (x, y) = (BalancedBase::<Quaternary>::from(a), BalancedBase::from(b));
+                assert_eq!(x.clone() + y.clone(), BalancedBase::from(a + b));
+                assert_eq!(x.clone() - y.clone(), BalancedBase::from(a - b));
+                assert_eq!(x * y, BalancedBase::from(a * b));
+            }
+        }
+    }
+
+    #[test]
+    fn it_sums_beyond_i64() {
+        let numbers = vec![snafu("2222222222222222222222222222"); 1000];
+        let sum: BalancedBase<Snafu> = numbers.iter().sum();
+
+        let expected: BigInt = numbers.iter().map(BigInt::from).sum();
+        assert_eq!(BigInt::from(&sum), expected);
+        assert_eq!(BalancedBase::from(&expected), sum);
+        assert!(i64::try_from(&sum).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use num::Integer;
+
+use crate::utils::Coords;
+
+pub type Point = Coords<i64>;
+pub type Segment = (Point, Point);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (&Point, &Point)> {
+    vertices.iter().zip(vertices.iter().cycle().skip(1))
+}
+
+/// Cross product of vectors `a -> b` and `a -> c`.
+/// Positive when `c` is counterclockwise from `b`, zero when the points are collinear.
+pub fn orientation(a: &Point, b: &Point, c: &Point) -> i64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Twice the area of a simple polygon, calculated using the Shoelace formula.
+/// Staying at twice the area keeps the result exact for lattice polygons.
+/// https://en.wikipedia.org/wiki/Shoelace_formula
+pub fn double_area(vertices: &[Point]) -> u64 {
+    edges(vertices)
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<i64>()
+        .unsigned_abs()
+}
+
+pub fn area(vertices: &[Point]) -> f64 {
+    double_area(vertices) as f64 / 2.0
+}
+
+pub fn perimeter(vertices: &[Point]) -> f64 {
+    edges(vertices)
+        .map(|(a, b)| ((b.x - a.x) as f64).hypot((b.y - a.y) as f64))
+        .sum()
+}
+
+/// Number of lattice points on the edges of the polygon.
+pub fn boundary_points(vertices: &[Point]) -> u64 {
+    edges(vertices)
+        .map(|(a, b)| (b.x - a.x).gcd(&(b.y - a.y)).unsigned_abs())
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon, solved with Pick's theorem
+/// using the area "A" from shoelace and the boundary points "b".
+/// https://en.wikipedia.org/wiki/Pick%27s_theorem
+/// A = i + b/2 - 1
+/// i = (2A - b + 2) / 2
+/// Degenerate polygons with no area have no interior points.
+pub fn interior_points(vertices: &[Point]) -> u64 {
+    let double_area = double_area(vertices);
+    if double_area == 0 {
+        return 0;
+    }
+
+    (double_area + 2).saturating_sub(boundary_points(vertices)) / 2
+}
+
+pub fn is_on_segment((a, b): &Segment, point: &Point) -> bool {
+    orientation(a, b, point) == 0
+        && point.x >= a.x.min(b.x)
+        && point.x <= a.x.max(b.x)
+        && point.y >= a.y.min(b.y)
+        && point.y <= a.y.max(b.y)
+}
+
+/// Locates the point by casting a ray towards positive x and counting the edge crossings.
+pub fn locate(vertices: &[Point], point: &Point) -> Location {
+    let mut is_inside = false;
+
+    for (a, b) in edges(vertices) {
+        if is_on_segment(&(*a, *b), point) {
+            return Location::Boundary;
+        }
+
+        if (a.y > point.y) != (b.y > point.y) {
+            let cross = orientation(a, b, point);
+            if (b.y > a.y) == (cross > 0) {
+                is_inside = !is_inside;
+            }
+        }
+    }
+
+    if is_inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Whether the two segments share at least one point, including touching endpoints.
+pub fn segments_intersect(first: &Segment, second: &Segment) -> bool {
+    let (a, b) = first;
+    let (c, d) = second;
+
+    let o1 = orientation(a, b, c).signum();
+    let o2 = orientation(a, b, d).signum();
+    let o3 = orientation(c, d, a).signum();
+    let o4 = orientation(c, d, b).signum();
+
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+
+    is_on_segment(first, c)
+        || is_on_segment(first, d)
+        || is_on_segment(second, a)
+        || is_on_segment(second, b)
+}
+
+/// Crossing point of the infinite lines through the segments,
+/// or `None` if the lines are parallel.
+pub fn line_intersection((a, b): &Segment, (c, d): &Segment) -> Option<(f64, f64)> {
+    let denominator = (b.x - a.x) * (d.y - c.y) - (b.y - a.y) * (d.x - c.x);
+    if denominator == 0 {
+        return None;
+    }
+
+    let numerator = (c.x - a.x) * (d.y - c.y) - (c.y - a.y) * (d.x - c.x);
+    let t = numerator as f64 / denominator as f64;
+
+    Some((
+        a.x as f64 + t * (b.x - a.x) as f64,
+        a.y as f64 + t * (b.y - a.y) as f64,
+    ))
+}
+
+/// Crossing point of the two segments, if they cross at a single point.
+pub fn segment_intersection(first: &Segment, second: &Segment) -> Option<(f64, f64)> {
+    if !segments_intersect(first, second) {
+        return None;
+    }
+
+    line_intersection(first, second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(i64, i64)]) -> Vec<Point> {
+        coords
+            .iter()
+            .map(|(x, y)| Coords { x: *x, y: *y })
+            .collect()
+    }
+
+    #[test]
+    fn it_calculates_polygon_area_and_points() {
+        let square = points(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(area(&square), 16.0);
+        assert_eq!(perimeter(&square), 16.0);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+
+        // Winding order doesn't matter
+        let reversed: Vec<Point> = square.iter().rev().copied().collect();
+        assert_eq!(double_area(&reversed), 32);
+
+        let triangle = points(&[(0, 0), (3, 0), (0, 3)]);
+        assert_eq!(area(&triangle), 4.5);
+        assert_eq!(boundary_points(&triangle), 9);
+        assert_eq!(interior_points(&triangle), 1);
+
+        let segment = points(&[(0, 0), (4, 0)]);
+        assert_eq!(boundary_points(&segment), 8);
+        assert_eq!(interior_points(&segment), 0);
+
+        let collinear = points(&[(0, 0), (2, 2), (5, 5)]);
+        assert_eq!(interior_points(&collinear), 0);
+        assert_eq!(interior_points(&points(&[(1, 1)])), 0);
+    }
+
+    #[test]
+    fn it_locates_points() {
+        // U-shaped polygon, opening upwards
+        let polygon = points(&[
+            (0, 0),
+            (6, 0),
+            (6, 6),
+            (4, 6),
+            (4, 2),
+            (2, 2),
+            (2, 6),
+            (0, 6),
+        ]);
+
+        assert_eq!(locate(&polygon, &Coords { x: 1, y: 1 }), Location::Inside);
+        assert_eq!(locate(&polygon, &Coords { x: 5, y: 4 }), Location::Inside);
+        assert_eq!(locate(&polygon, &Coords { x: 3, y: 4 }), Location::Outside);
+        assert_eq!(locate(&polygon, &Coords { x: 7, y: 0 }), Location::Outside);
+        assert_eq!(locate(&polygon, &Coords { x: 3, y: 2 }), Location::Boundary);
+        assert_eq!(locate(&polygon, &Coords { x: 6, y: 6 }), Location::Boundary);
+    }
+
+    #[test]
+    fn it_intersects_segments() {
+        let segment = |x1, y1, x2, y2| (Coords { x: x1, y: y1 }, Coords { x: x2, y: y2 });
+
+        assert!(segments_intersect(
+            &segment(0, 0, 4, 4),
+            &segment(0, 4, 4, 0)
+        ));
+        assert!(segments_intersect(
+            &segment(0, 0, 4, 4),
+            &segment(4, 4, 5, 0)
+        ));
+        assert!(segments_intersect(
+            &segment(0, 0, 4, 0),
+            &segment(2, 0, 6, 0)
+        ));
+        assert!(!segments_intersect(
+            &segment(0, 0, 4, 0),
+            &segment(5, 0, 6, 0)
+        ));
+        assert!(!segments_intersect(
+            &segment(0, 0, 1, 1),
+            &segment(0, 4, 4, 0)
+        ));
+
+        assert_eq!(
+            segment_intersection(&segment(0, 0, 4, 4), &segment(0, 3, 3, 0)),
+            Some((1.5, 1.5))
+        );
+        assert_eq!(
+            segment_intersection(&segment(0, 0, 1, 1), &segment(0, 4, 4, 0)),
+            None
+        );
+        assert_eq!(
+            line_intersection(&segment(0, 0, 1, 1), &segment(0, 4, 4, 0)),
+            Some((2.0, 2.0))
+        );
+        assert_eq!(
+            line_intersection(&segment(0, 0, 1, 1), &segment(0, 1, 1, 2)),
+            None
+        );
+    }
+}
@@ -0,0 +1,727 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::solution::AocError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    Terminal(char),
+    NonTerminal(String),
+}
+
+impl Symbol {
+    pub fn non_terminal(name: &str) -> Self {
+        Symbol::NonTerminal(name.to_owned())
+    }
+}
+
+/// Context-free grammar, where every rule has alternative sequences of symbols.
+/// An empty alternative matches the empty string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    pub start: String,
+    rules: BTreeMap<String, Vec<Vec<Symbol>>>,
+}
+
+fn terminals(literal: &str) -> impl Iterator<Item = Symbol> + '_ {
+    literal.chars().map(Symbol::Terminal)
+}
+
+impl Grammar {
+    pub fn new(start: &str) -> Self {
+        Grammar {
+            start: start.to_owned(),
+            rules: BTreeMap::new(),
+        }
+    }
+
+    pub fn rule(&self, name: &str) -> Option<&[Vec<Symbol>]> {
+        self.rules
+            .get(name)
+            .map(|alternatives| alternatives.as_slice())
+    }
+
+    /// Replaces the alternatives of the rule, or adds a new rule
+    pub fn set_rule(&mut self, name: &str, alternatives: Vec<Vec<Symbol>>) {
+        self.rules.insert(name.to_owned(), alternatives);
+    }
+
+    pub fn remove_rule(&mut self, name: &str) -> Option<Vec<Vec<Symbol>>> {
+        self.rules.remove(name)
+    }
+
+    /// Parses rules in the format of 2020 day 19, like `0: 1 2 | 2 "a"`.
+    /// The rule `0` is the start symbol.
+    pub fn parse_rules(input: &str) -> Result<Self, AocError> {
+        let mut grammar = Grammar::new("0");
+
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, rhs) = line
+                .split_once(':')
+                .ok_or(AocError::parse(line, "Missing colon"))?;
+
+            let alternatives = rhs
+                .split('|')
+                .map(|alternative| {
+                    let mut symbols = Vec::new();
+
+                    for word in alternative.split_whitespace() {
+                        match word.strip_prefix('"').and_then(|w| w.strip_suffix('"')) {
+                            Some(literal) => symbols.extend(terminals(literal)),
+                            None if word.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                                symbols.push(Symbol::non_terminal(word))
+                            }
+                            None => return Err(AocError::parse(line, "Invalid symbol")),
+                        }
+                    }
+
+                    Ok(symbols)
+                })
+                .collect::<Result<_, _>>()?;
+
+            grammar.set_rule(name.trim(), alternatives);
+        }
+
+        Ok(grammar)
+    }
+
+    /// Parses rules in BNF, like `<expr> ::= <term> "+" <expr> | <term>`.
+    /// The first rule is the start symbol.
+    pub fn parse_bnf(input: &str) -> Result<Self, AocError> {
+        let mut grammar: Option<Grammar> = None;
+
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, rhs) = line
+                .split_once("::=")
+                .ok_or(AocError::parse(line, "Missing ::="))?;
+
+            let name = name
+                .trim()
+                .strip_prefix('<')
+                .and_then(|name| name.strip_suffix('>'))
+                .ok_or(AocError::parse(line, "Invalid rule name"))?;
+
+            let mut alternatives = vec![Vec::new()];
+            let mut chars = rhs.chars();
+
+            while let Some(c) = chars.next() {
+                let delimiter = match c {
+                    '|' => {
+                        alternatives.push(Vec::new());
+                        continue;
+                    }
+                    '<' => '>',
+                    '"' | '\'' => c,
+                    c if c.is_whitespace() => continue,
+                    _ => return Err(AocError::parse(line, format!("Unexpected {c:?}"))),
+                };
+
+                let mut literal = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == delimiter => break,
+                        Some(next) => literal.push(next),
+                        None => return Err(AocError::parse(line, "Unterminated symbol")),
+                    }
+                }
+
+                let symbols = alternatives.last_mut().unwrap();
+                if c == '<' {
+                    symbols.push(Symbol::NonTerminal(literal));
+                } else {
+                    symbols.extend(terminals(&literal));
+                }
+            }
+
+            grammar
+                .get_or_insert_with(|| Grammar::new(name))
+                .set_rule(name, alternatives);
+        }
+
+        grammar.ok_or(AocError::parse(input, "No rules"))
+    }
+
+    /// Converts the grammar to Chomsky normal form, where every rule produces either
+    /// a single terminal or two non-terminals.
+    pub fn to_cnf(&self) -> Result<Cnf, AocError> {
+        let mut builder = CnfBuilder::default();
+
+        for name in self.rules.keys() {
+            builder.non_terminal(name);
+        }
+
+        let start = *builder
+            .indices
+            .get(&self.start)
+            .ok_or(AocError::logic(format!(
+                "Undefined start rule {}",
+                self.start
+            )))?;
+
+        for (name, alternatives) in self.rules.iter() {
+            let lhs = builder.indices[name];
+
+            for alternative in alternatives {
+                let items = alternative
+                    .iter()
+                    .map(|symbol| match symbol {
+                        Symbol::Terminal(c) => Ok(Item::Terminal(*c)),
+                        Symbol::NonTerminal(name) => match builder.indices.get(name) {
+                            Some(index) => Ok(Item::NonTerminal(*index)),
+                            None => Err(AocError::logic(format!("Undefined rule {name}"))),
+                        },
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                builder.add(lhs, items);
+            }
+        }
+
+        Ok(builder.build(start))
+    }
+}
+
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|(name, _)| **name == self.start)
+            .chain(self.rules.iter().filter(|(name, _)| **name != self.start));
+
+        for (name, alternatives) in rules {
+            write!(f, "<{name}> ::=")?;
+
+            for (index, alternative) in alternatives.iter().enumerate() {
+                if index > 0 {
+                    write!(f, " |")?;
+                }
+
+                if alternative.is_empty() {
+                    write!(f, " \"\"")?;
+                }
+
+                let mut symbols = alternative.iter().peekable();
+                while let Some(symbol) = symbols.next() {
+                    match symbol {
+                        Symbol::NonTerminal(name) => write!(f, " <{name}>")?,
+                        Symbol::Terminal(c) => {
+                            // Consecutive terminals are joined to a single literal
+                            let mut literal = c.to_string();
+                            while let Some(Symbol::Terminal(c)) = symbols.peek() {
+                                literal.push(*c);
+                                symbols.next();
+                            }
+
+                            let quote = if literal.contains('"') { '\'' } else { '"' };
+                            write!(f, " {quote}{literal}{quote}")?;
+                        }
+                    }
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Item {
+    Terminal(char),
+    NonTerminal(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Production {
+    Terminal(char),
+    Pair(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    lhs: usize,
+    /// Non-terminals between the left hand side and the production, left out by unit rules
+    via: Vec<usize>,
+    production: Production,
+}
+
+#[derive(Default)]
+struct CnfBuilder {
+    names: Vec<String>,
+    /// Non-terminals created by the conversion, left out of the parse trees
+    generated: Vec<bool>,
+    indices: HashMap<String, usize>,
+    rules: Vec<(usize, Vec<Item>)>,
+    terminals: HashMap<char, usize>,
+}
+
+impl CnfBuilder {
+    fn non_terminal(&mut self, name: &str) -> usize {
+        self.names.push(name.to_owned());
+        self.generated.push(false);
+        self.indices.insert(name.to_owned(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    fn generated(&mut self, name: String) -> usize {
+        let index = self.names.len();
+        self.names.push(name);
+        self.generated.push(true);
+        index
+    }
+
+    /// Replaces the terminals of long rules with non-terminals producing them,
+    /// and splits the rules into rules of at most two symbols.
+    fn add(&mut self, lhs: usize, mut items: Vec<Item>) {
+        if items.len() >= 2 {
+            for item in items.iter_mut() {
+                if let Item::Terminal(c) = *item {
+                    let index = match self.terminals.get(&c) {
+                        Some(index) => *index,
+                        None => {
+                            let index = self.generated(format!("{c:?}"));
+                            self.terminals.insert(c, index);
+                            self.rules.push((index, vec![Item::Terminal(c)]));
+                            index
+                        }
+                    };
+
+                    *item = Item::NonTerminal(index);
+                }
+            }
+        }
+
+        let mut lhs = lhs;
+        while items.len() > 2 {
+            let rest = self.generated(format!("{}'", self.names[lhs]));
+            self.rules
+                .push((lhs, vec![items.remove(0), Item::NonTerminal(rest)]));
+            lhs = rest;
+        }
+
+        self.rules.push((lhs, items));
+    }
+
+    fn build(self, start: usize) -> Cnf {
+        let count = self.names.len();
+
+        // Non-terminals that can produce the empty string
+        let mut nullable = vec![false; count];
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (lhs, items) in self.rules.iter() {
+                let is_nullable = items
+                    .iter()
+                    .all(|item| matches!(item, Item::NonTerminal(index) if nullable[*index]));
+
+                if is_nullable && !nullable[*lhs] {
+                    nullable[*lhs] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        // Empty productions are removed by adding the rules that skip the nullable symbols
+        let mut units: Vec<Vec<usize>> = vec![Vec::new(); count];
+        let mut productions: Vec<Vec<Production>> = vec![Vec::new(); count];
+
+        for (lhs, items) in self.rules.iter() {
+            match items.as_slice() {
+                [] => {}
+                [Item::Terminal(c)] => productions[*lhs].push(Production::Terminal(*c)),
+                [Item::NonTerminal(a)] => units[*lhs].push(*a),
+                [Item::NonTerminal(a), Item::NonTerminal(b)] => {
+                    productions[*lhs].push(Production::Pair(*a, *b));
+
+                    if nullable[*b] {
+                        units[*lhs].push(*a);
+                    }
+                    if nullable[*a] {
+                        units[*lhs].push(*b);
+                    }
+                }
+                _ => unreachable!("Rules are split before building"),
+            }
+        }
+
+        // Unit rules are removed by copying the productions of every non-terminal reachable
+        // through them, remembering the path for the parse trees.
+        let mut rules = Vec::new();
+
+        for lhs in 0..count {
+            let mut paths: HashMap<usize, Vec<usize>> = HashMap::from([(lhs, Vec::new())]);
+            let mut queue = VecDeque::from([lhs]);
+            let mut added: HashSet<Production> = HashSet::new();
+
+            while let Some(current) = queue.pop_front() {
+                let via = paths[&current].clone();
+
+                for production in productions[current].iter() {
+                    if added.insert(*production) {
+                        rules.push(Rule {
+                            lhs,
+                            via: via.clone(),
+                            production: *production,
+                        });
+                    }
+                }
+
+                for next in units[current].iter() {
+                    if !paths.contains_key(next) {
+                        let mut path = via.clone();
+                        path.push(*next);
+                        paths.insert(*next, path);
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+
+        let mut pairs = vec![Vec::new(); count];
+        for (index, rule) in rules.iter().enumerate() {
+            if let Production::Pair(a, _) = rule.production {
+                pairs[a].push(index);
+            }
+        }
+
+        Cnf {
+            start,
+            names: self.names,
+            generated: self.generated,
+            rules,
+            pairs,
+            accepts_empty: nullable[start],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTree {
+    Leaf(char),
+    Node(String, Vec<ParseTree>),
+}
+
+impl fmt::Display for ParseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseTree::Leaf(c) => write!(f, "{c}"),
+            ParseTree::Node(name, children) => {
+                write!(f, "({name}")?;
+                for child in children {
+                    write!(f, " {child}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Back {
+    rule: usize,
+    split: usize,
+}
+
+/// Grammar in Chomsky normal form. Nullable symbols matching the empty string
+/// are left out of the parse trees.
+#[derive(Debug, Clone)]
+pub struct Cnf {
+    start: usize,
+    names: Vec<String>,
+    generated: Vec<bool>,
+    rules: Vec<Rule>,
+    /// Indices of the rules producing two non-terminals, by the first non-terminal
+    pairs: Vec<Vec<usize>>,
+    accepts_empty: bool,
+}
+
+impl Cnf {
+    /// Fills the CYK table, where `table[len - 1][start]` has every non-terminal
+    /// producing the substring of length `len` at `start`, with a way to produce it.
+    fn cyk(&self, word: &[char]) -> Vec<Vec<HashMap<usize, Back>>> {
+        let n = word.len();
+        let mut table: Vec<Vec<HashMap<usize, Back>>> = Vec::with_capacity(n);
+
+        table.push(
+            word.iter()
+                .map(|c| {
+                    self.rules
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, rule)| rule.production == Production::Terminal(*c))
+                        .map(|(index, rule)| {
+                            (
+                                rule.lhs,
+                                Back {
+                                    rule: index,
+                                    split: 0,
+                                },
+                            )
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+
+        // This runs in O(|G| * n^3)
+        for len in 2..=n {
+            let mut row = Vec::with_capacity(n - len + 1);
+
+            for start in 0..=(n - len) {
+                let mut cell: HashMap<usize, Back> = HashMap::new();
+
+                for split in 1..len {
+                    let left = &table[split - 1][start];
+                    let right = &table[len - split - 1][start + split];
+
+                    for a in left.keys() {
+                        for index in self.pairs[*a].iter() {
+                            let rule = &self.rules[*index];
+
+                            if let Production::Pair(_, b) = rule.production {
+                                if right.contains_key(&b) {
+                                    // The first split and rule are kept, so the trees are stable
+                                    let back = cell.entry(rule.lhs).or_insert(Back {
+                                        rule: *index,
+                                        split,
+                                    });
+                                    if back.split == split && back.rule > *index {
+                                        back.rule = *index;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                row.push(cell);
+            }
+
+            table.push(row);
+        }
+
+        table
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        self.parse(word).is_some()
+    }
+
+    /// Finds a parse tree of the word with the CYK algorithm
+    pub fn parse(&self, word: &str) -> Option<ParseTree> {
+        let word: Vec<char> = word.chars().collect();
+
+        if word.is_empty() {
+            let name = self.names[self.start].clone();
+            return self
+                .accepts_empty
+                .then_some(ParseTree::Node(name, Vec::new()));
+        }
+
+        let table = self.cyk(&word);
+        let back = table[word.len() - 1][0].get(&self.start)?;
+
+        self.tree(&table, word.len(), 0, self.start, *back)
+            .into_iter()
+            .next()
+    }
+
+    fn tree(
+        &self,
+        table: &[Vec<HashMap<usize, Back>>],
+        len: usize,
+        start: usize,
+        symbol: usize,
+        back: Back,
+    ) -> Vec<ParseTree> {
+        let rule = &self.rules[back.rule];
+
+        let mut children = match rule.production {
+            Production::Terminal(c) => vec![ParseTree::Leaf(c)],
+            Production::Pair(a, b) => {
+                let (left, right) = (back.split, len - back.split);
+                let left_back = table[left - 1][start][&a];
+                let right_back = table[right - 1][start + left][&b];
+
+                let mut children = self.tree(table, left, start, a, left_back);
+                children.extend(self.tree(table, right, start + left, b, right_back));
+                children
+            }
+        };
+
+        for via in rule.via.iter().rev().chain([&symbol]) {
+            if !self.generated[*via] {
+                children = vec![ParseTree::Node(self.names[*via].clone(), children)];
+            }
+        }
+
+        children
+    }
+
+    /// Every string matching the grammar up to the length, shortest first
+    pub fn enumerate(&self, max_len: usize) -> Vec<String> {
+        // Strings produced by every non-terminal, by their length
+        let mut languages: Vec<Vec<BTreeSet<String>>> =
+            vec![vec![BTreeSet::new(); max_len + 1]; self.names.len()];
+
+        for len in 1..=max_len {
+            for rule in self.rules.iter() {
+                let strings: Vec<String> = match rule.production {
+                    Production::Terminal(c) if len == 1 => vec![c.to_string()],
+                    Production::Terminal(_) => continue,
+                    Production::Pair(a, b) => (1..len)
+                        .flat_map(|split| {
+                            let rights = &languages[b][len - split];
+                            languages[a][split].iter().flat_map(move |left| {
+                                rights.iter().map(move |right| left.clone() + right)
+                            })
+                        })
+                        .collect(),
+                };
+
+                languages[rule.lhs][len].extend(strings);
+            }
+        }
+
+        let empty = self.accepts_empty.then(String::new);
+
+        empty
+            .into_iter()
+            .chain(languages.swap_remove(self.start).into_iter().flatten())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_puzzle_rules() {
+        let grammar = Grammar::parse_rules(
+            "0: 1 2\n\
+             1: \"a\"\n\
+             2: 1 3 | 3 1\n\
+             3: \"b\"",
+        )
+        .unwrap();
+
+        assert_eq!(grammar.start, "0");
+        assert_eq!(
+            grammar.rule("2"),
+            Some(
+                [
+                    vec![Symbol::non_terminal("1"), Symbol::non_terminal("3")],
+                    vec![Symbol::non_terminal("3"), Symbol::non_terminal("1")]
+                ]
+                .as_slice()
+            )
+        );
+        assert_eq!(
+            grammar.rule("3"),
+            Some([vec![Symbol::Terminal('b')]].as_slice())
+        );
+
+        assert!(Grammar::parse_rules("0 1 2").is_err());
+        assert!(Grammar::parse_rules("0: 1 +").is_err());
+    }
+
+    #[test]
+    fn it_prints_and_parses_bnf() {
+        let grammar = Grammar::parse_bnf(
+            "<expr> ::= <term> \"+\" <expr> | <term>\n\
+             <term> ::= \"(\" <expr> ')' | <digit>\n\
+             <digit> ::= \"0\" | \"1\" | \"\"",
+        )
+        .unwrap();
+
+        assert_eq!(grammar.start, "expr");
+        assert_eq!(
+            grammar.to_string(),
+            "<expr> ::= <term> \"+\" <expr> | <term>\n\
+             <digit> ::= \"0\" | \"1\" | \"\"\n\
+             <term> ::= \"(\" <expr> \")\" | <digit>\n"
+        );
+        assert_eq!(Grammar::parse_bnf(&grammar.to_string()), Ok(grammar));
+
+        assert!(Grammar::parse_bnf("<a> = \"a\"").is_err());
+        assert!(Grammar::parse_bnf("<a> ::= <b").is_err());
+        assert!(Grammar::parse_bnf("").is_err());
+    }
+
+    #[test]
+    fn it_checks_membership() {
+        let cnf = Grammar::parse_bnf(
+            "<s> ::= \"a\" <s> \"b\" | <c>\n\
+             <c> ::= \"c\" <c> | \"\"",
+        )
+        .unwrap()
+        .to_cnf()
+        .unwrap();
+
+        for word in ["", "c", "ab", "acb", "aacccbb", "ccc"] {
+            assert!(cnf.matches(word), "{word}");
+        }
+        for word in ["a", "abb", "ba", "acbb", "cab"] {
+            assert!(!cnf.matches(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn it_rejects_undefined_rules() {
+        let grammar = Grammar::parse_rules("0: 1 2\n1: \"a\"").unwrap();
+        assert!(grammar.to_cnf().is_err());
+
+        let grammar = Grammar::parse_rules("1: \"a\"").unwrap();
+        assert!(grammar.to_cnf().is_err());
+    }
+
+    #[test]
+    fn it_extracts_parse_trees() {
+        let cnf = Grammar::parse_rules(
+            "0: 4 1 5\n\
+             1: 2 3 | 3 2\n\
+             2: 4 4 | 5 5\n\
+             3: 4 5 | 5 4\n\
+             4: \"a\"\n\
+             5: \"b\"",
+        )
+        .unwrap()
+        .to_cnf()
+        .unwrap();
+
+        assert_eq!(
+            cnf.parse("ababbb").unwrap().to_string(),
+            "(0 (4 a) (1 (3 (5 b) (4 a)) (2 (5 b) (5 b))) (5 b))"
+        );
+        assert_eq!(cnf.parse("bababa"), None);
+
+        let cnf = Grammar::parse_bnf(
+            "<s> ::= <x> \"!\"\n\
+             <x> ::= <y>\n\
+             <y> ::= \"hi\"",
+        )
+        .unwrap()
+        .to_cnf()
+        .unwrap();
+
+        assert_eq!(cnf.parse("hi!").unwrap().to_string(), "(s (x (y h i)) !)");
+    }
+
+    #[test]
+    fn it_enumerates_matching_strings() {
+        let cnf = Grammar::parse_bnf("<s> ::= \"a\" <s> \"b\" | \"\" | \"c\"")
+            .unwrap()
+            .to_cnf()
+            .unwrap();
+
+        assert_eq!(
+            cnf.enumerate(5),
+            vec!["", "c", "ab", "acb", "aabb", "aacbb"]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+use crate::solution::AocError;
+
+/// Pointy-top hexagon in cube coordinates, where `q + r + s == 0`.
+/// Moving east increases `q`, moving south-east increases `r`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Direction {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+/// Offset coordinate layouts for pointy-top hexagons, shoving either
+/// the odd or the even rows half a hexagon to the right.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OffsetLayout {
+    OddRows,
+    EvenRows,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0, s: 0 };
+
+    /// Creates a hexagon from its axial coordinates.
+    pub fn new(q: i32, r: i32) -> Self {
+        Hex { q, r, s: -q - r }
+    }
+
+    pub fn axial(&self) -> (i32, i32) {
+        (self.q, self.r)
+    }
+
+    pub fn from_offset(column: i32, row: i32, layout: OffsetLayout) -> Self {
+        let q = match layout {
+            OffsetLayout::OddRows => column - (row - (row & 1)) / 2,
+            OffsetLayout::EvenRows => column - (row + (row & 1)) / 2,
+        };
+
+        Hex::new(q, row)
+    }
+
+    pub fn offset(&self, layout: OffsetLayout) -> (i32, i32) {
+        let column = match layout {
+            OffsetLayout::OddRows => self.q + (self.r - (self.r & 1)) / 2,
+            OffsetLayout::EvenRows => self.q + (self.r + (self.r & 1)) / 2,
+        };
+
+        (column, self.r)
+    }
+
+    pub fn neighbour(&self, direction: Direction) -> Hex {
+        *self + direction.offset()
+    }
+
+    pub fn neighbours(&self) -> [Hex; 6] {
+        Direction::ALL.map(|direction| self.neighbour(direction))
+    }
+
+    pub fn distance(&self, other: &Hex) -> u32 {
+        let diff = *self - *other;
+        diff.q
+            .unsigned_abs()
+            .max(diff.r.unsigned_abs())
+            .max(diff.s.unsigned_abs())
+    }
+
+    /// All hexagons at exactly `radius` steps away, walking the ring clockwise
+    /// starting from the south-west corner.
+    pub fn ring(&self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let mut hex = *self + Direction::SouthWest.offset() * radius as i32;
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+
+        for direction in [
+            Direction::NorthWest,
+            Direction::NorthEast,
+            Direction::East,
+            Direction::SouthEast,
+            Direction::SouthWest,
+            Direction::West,
+        ] {
+            for _ in 0..radius {
+                hex = hex.neighbour(direction);
+                ring.push(hex);
+            }
+        }
+
+        ring
+    }
+
+    /// Follows a path of directions without separators, such as `esenee`.
+    pub fn walk(&self, path: &str) -> Result<Hex, AocError> {
+        Ok(Direction::parse_path(path)?
+            .into_iter()
+            .fold(*self, |hex, direction| hex.neighbour(direction)))
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex {
+            q: self.q + other.q,
+            r: self.r + other.r,
+            s: self.s + other.s,
+        }
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex {
+            q: self.q - other.q,
+            r: self.r - other.r,
+            s: self.s - other.s,
+        }
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, scale: i32) -> Hex {
+        Hex {
+            q: self.q * scale,
+            r: self.r * scale,
+            s: self.s * scale,
+        }
+    }
+}
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthEast,
+    ];
+
+    pub fn offset(&self) -> Hex {
+        match self {
+            Direction::East => Hex { q: 1, r: 0, s: -1 },
+            Direction::SouthEast => Hex { q: 0, r: 1, s: -1 },
+            Direction::SouthWest => Hex { q: -1, r: 1, s: 0 },
+            Direction::West => Hex { q: -1, r: 0, s: 1 },
+            Direction::NorthWest => Hex { q: 0, r: -1, s: 1 },
+            Direction::NorthEast => Hex { q: 1, r: -1, s: 0 },
+        }
+    }
+
+    /// Parses directions `e, se, sw, w, nw, ne` written one after another.
+    pub fn parse_path(path: &str) -> Result<Vec<Direction>, AocError> {
+        let mut directions = Vec::new();
+        let mut chars = path.trim().chars();
+
+        while let Some(c) = chars.next() {
+            let second = match c {
+                'n' | 's' => chars.next(),
+                _ => None,
+            };
+
+            let direction = match (c, second) {
+                ('e', None) => Direction::East,
+                ('w', None) => Direction::West,
+                ('s', Some('e')) => Direction::SouthEast,
+                ('s', Some('w')) => Direction::SouthWest,
+                ('n', Some('w')) => Direction::NorthWest,
+                ('n', Some('e')) => Direction::NorthEast,
+                ('n' | 's', None) => return Err(AocError::parse(path, "Unexpected end of path")),
+                (c, next) => {
+                    let direction: String = std::iter::once(c).chain(next).collect();
+                    return Err(AocError::parse(direction, "Unknown direction"));
+                }
+            };
+
+            directions.push(direction);
+        }
+
+        Ok(directions)
+    }
+}
+
+impl FromStr for Direction {
+    type Err = AocError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "e" => Ok(Direction::East),
+            "se" => Ok(Direction::SouthEast),
+            "sw" => Ok(Direction::SouthWest),
+            "w" => Ok(Direction::West),
+            "nw" => Ok(Direction::NorthWest),
+            "ne" => Ok(Direction::NorthEast),
+            _ => Err(AocError::parse(input, "Unknown direction")),
+        }
+    }
+}
+
+/// Draws the hexagons with every other row shifted by half a hexagon.
+/// Positions between the given hexagons are filled with `empty`.
+pub fn render(hexes: &HashMap<Hex, char>, empty: char) -> String {
+    // Every hexagon is two characters wide, and each row shifts by half a hexagon
+    let column = |hex: &Hex| 2 * hex.q + hex.r;
+
+    let (Some(min_r), Some(max_r)) = (
+        hexes.keys().map(|hex| hex.r).min(),
+        hexes.keys().map(|hex| hex.r).max(),
+    ) else {
+        return String::new();
+    };
+
+    let min_column = hexes.keys().map(column).min().unwrap_or_default();
+    let max_column = hexes.keys().map(column).max().unwrap_or_default();
+
+    let mut output = String::new();
+
+    for r in min_r..=max_r {
+        let mut line = String::new();
+
+        for x in min_column..=max_column {
+            if (x - r).rem_euclid(2) != 0 {
+                line.push(' ');
+                continue;
+            }
+
+            let hex = Hex::new((x - r) / 2, r);
+            line.push(*hexes.get(&hex).unwrap_or(&empty));
+        }
+
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_walks_paths() {
+        assert_eq!(Hex::ORIGIN.walk("esenee"), Ok(Hex::new(3, 0)));
+        assert_eq!(Hex::ORIGIN.walk("esew"), Ok(Hex::new(0, 1)));
+        assert_eq!(Hex::ORIGIN.walk("nwwswee"), Ok(Hex::ORIGIN));
+        assert!(Hex::ORIGIN.walk("enx").is_err());
+        assert!(Hex::ORIGIN.walk("en").is_err());
+        assert!(Hex::ORIGIN.walk("nä").is_err());
+        assert!(Hex::ORIGIN.walk("äe").is_err());
+        assert_eq!(
+            Direction::parse_path("nesww"),
+            Ok(vec![
+                Direction::NorthEast,
+                Direction::SouthWest,
+                Direction::West
+            ])
+        );
+    }
+
+    #[test]
+    fn it_converts_offset_coordinates() {
+        for layout in [OffsetLayout::OddRows, OffsetLayout::EvenRows] {
+            for row in -3..=3 {
+                for column in -3..=3 {
+                    let hex = Hex::from_offset(column, row, layout);
+                    assert_eq!(hex.q + hex.r + hex.s, 0);
+                    assert_eq!(hex.offset(layout), (column, row));
+                }
+            }
+        }
+
+        assert_eq!(
+            Hex::new(0, 1).offset(OffsetLayout::OddRows),
+            Hex::new(-1, 1).offset(OffsetLayout::EvenRows)
+        );
+    }
+
+    #[test]
+    fn it_calculates_distances_and_rings() {
+        let center = Hex::new(2, -1);
+
+        assert_eq!(center.distance(&center.walk("eeene").unwrap()), 4);
+        assert_eq!(center.ring(0), vec![center]);
+
+        for radius in 1..=4 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|hex| hex.distance(&center) == radius));
+        }
+
+        assert_eq!(center.ring(1).len(), center.neighbours().len());
+        assert!(center
+            .ring(1)
+            .iter()
+            .all(|hex| center.neighbours().contains(hex)));
+    }
+
+    #[test]
+    fn it_renders_hex_maps() {
+        let hexes: HashMap<Hex, char> = [Hex::ORIGIN]
+            .into_iter()
+            .chain(Hex::ORIGIN.neighbours())
+            .map(|hex| (hex, if hex == Hex::ORIGIN { '#' } else { 'o' }))
+            .collect();
+
+        assert_eq!(render(&hexes, '.'), " o o\no # o\n o o\n");
+    }
+}
@@ -0,0 +1,388 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+
+use num::{BigInt, BigRational, One, Signed, ToPrimitive, Zero};
+
+use crate::solution::AocError;
+
+pub type Matrix = Vec<Vec<BigRational>>;
+
+pub fn rational(value: i64) -> BigRational {
+    BigRational::from_integer(BigInt::from(value))
+}
+
+pub fn from_integers(matrix: &[Vec<i64>]) -> Matrix {
+    matrix
+        .iter()
+        .map(|row| row.iter().map(|value| rational(*value)).collect())
+        .collect()
+}
+
+pub fn to_integer(value: &BigRational) -> Result<i64, AocError> {
+    if !value.is_integer() {
+        return Err(AocError::logic(format!("{value} is not an integer")));
+    }
+
+    value
+        .to_integer()
+        .to_i64()
+        .ok_or_else(|| AocError::logic(format!("{value} does not fit in i64")))
+}
+
+/// Gaussian elimination into reduced row echelon form, considering only the first `columns`
+/// columns for pivots. Returns the pivot column of each row that got one.
+fn eliminate(matrix: &mut Matrix, columns: usize) -> Vec<usize> {
+    let mut pivots = Vec::new();
+
+    for column in 0..columns {
+        let row = pivots.len();
+        if row == matrix.len() {
+            break;
+        }
+
+        let Some(pivot) = (row..matrix.len()).find(|r| !matrix[*r][column].is_zero()) else {
+            continue;
+        };
+
+        matrix.swap(pivot, row);
+
+        let divisor = matrix[row][column].clone();
+        for value in matrix[row].iter_mut() {
+            *value /= divisor.clone();
+        }
+
+        for other in 0..matrix.len() {
+            if other == row || matrix[other][column].is_zero() {
+                continue;
+            }
+
+            let factor = matrix[other][column].clone();
+            for c in 0..matrix[other].len() {
+                let delta = matrix[row][c].clone() * factor.clone();
+                matrix[other][c] -= delta;
+            }
+        }
+
+        pivots.push(column);
+    }
+
+    pivots
+}
+
+pub fn determinant(matrix: &[Vec<BigRational>]) -> BigRational {
+    let size = matrix.len();
+    let mut reduced = matrix.to_vec();
+    let mut determinant = BigRational::one();
+
+    // Forward elimination only, the determinant is the product of the diagonal
+    for column in 0..size {
+        let Some(pivot) = (column..size).find(|row| !reduced[*row][column].is_zero()) else {
+            return BigRational::zero();
+        };
+
+        if pivot != column {
+            reduced.swap(pivot, column);
+            determinant = -determinant;
+        }
+
+        for row in column + 1..size {
+            let factor = reduced[row][column].clone() / reduced[column][column].clone();
+            for c in column..size {
+                let delta = reduced[column][c].clone() * factor.clone();
+                reduced[row][c] -= delta;
+            }
+        }
+
+        determinant *= reduced[column][column].clone();
+    }
+
+    determinant
+}
+
+pub fn inverse(matrix: &[Vec<BigRational>]) -> Option<Matrix> {
+    let size = matrix.len();
+
+    let mut augmented: Matrix = matrix
+        .iter()
+        .enumerate()
+        .map(|(row, values)| {
+            let identity = (0..size).map(|column| {
+                if row == column {
+                    BigRational::one()
+                } else {
+                    BigRational::zero()
+                }
+            });
+            values.iter().cloned().chain(identity).collect()
+        })
+        .collect();
+
+    let pivots = eliminate(&mut augmented, size);
+    if pivots.len() < size {
+        return None;
+    }
+
+    Some(
+        augmented
+            .into_iter()
+            .map(|row| row[size..].to_vec())
+            .collect(),
+    )
+}
+
+/// Solves the system of linear equations from an augmented matrix,
+/// where the last column contains the constants.
+/// a*i + b*j + c*k + d*l = e
+/// ^-------------------^   ^
+///     coefficients        constant
+pub fn solve(augmented: &[Vec<BigRational>]) -> Result<Vec<BigRational>, AocError> {
+    let unknowns = augmented
+        .first()
+        .map(|row| row.len().saturating_sub(1))
+        .ok_or_else(|| AocError::logic("Empty system of equations"))?;
+
+    let mut reduced = augmented.to_vec();
+    let pivots = eliminate(&mut reduced, unknowns);
+
+    // Any remaining row without a pivot must have reduced into 0 = 0
+    if reduced[pivots.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        return Err(AocError::logic("Inconsistent system of equations"));
+    }
+
+    if pivots.len() < unknowns {
+        return Err(AocError::logic(
+            "System of equations has no unique solution",
+        ));
+    }
+
+    Ok(reduced
+        .into_iter()
+        .take(unknowns)
+        .map(|row| row[unknowns].clone())
+        .collect())
+}
+
+/// Expression of form `coefficient * x + constant` in terms of a single unknown `x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearExpr {
+    pub coefficient: BigRational,
+    pub constant: BigRational,
+}
+
+impl LinearExpr {
+    pub fn constant(value: BigRational) -> Self {
+        LinearExpr {
+            coefficient: BigRational::zero(),
+            constant: value,
+        }
+    }
+
+    pub fn unknown() -> Self {
+        LinearExpr {
+            coefficient: BigRational::one(),
+            constant: BigRational::zero(),
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    pub fn mul(&self, other: &LinearExpr) -> Result<LinearExpr, AocError> {
+        if !self.is_constant() && !other.is_constant() {
+            return Err(AocError::logic(format!(
+                "({self}) * ({other}) is not linear"
+            )));
+        }
+
+        Ok(LinearExpr {
+            coefficient: &self.coefficient * &other.constant + &other.coefficient * &self.constant,
+            constant: &self.constant * &other.constant,
+        })
+    }
+
+    pub fn div(&self, other: &LinearExpr) -> Result<LinearExpr, AocError> {
+        if !other.is_constant() {
+            return Err(AocError::logic(format!(
+                "({self}) / ({other}) is not linear"
+            )));
+        }
+
+        if other.constant.is_zero() {
+            return Err(AocError::logic(format!("({self}) / 0")));
+        }
+
+        Ok(LinearExpr {
+            coefficient: &self.coefficient / &other.constant,
+            constant: &self.constant / &other.constant,
+        })
+    }
+
+    /// Solves `x` from the equation `self = other`.
+    pub fn solve(&self, other: &LinearExpr) -> Result<BigRational, AocError> {
+        let coefficient = &self.coefficient - &other.coefficient;
+
+        if coefficient.is_zero() {
+            return Err(AocError::logic(format!(
+                "{self} = {other} has no unique solution"
+            )));
+        }
+
+        Ok((&other.constant - &self.constant) / coefficient)
+    }
+}
+
+impl Add for &LinearExpr {
+    type Output = LinearExpr;
+
+    fn add(self, other: &LinearExpr) -> LinearExpr {
+        LinearExpr {
+            coefficient: &self.coefficient + &other.coefficient,
+            constant: &self.constant + &other.constant,
+        }
+    }
+}
+
+impl Sub for &LinearExpr {
+    type Output = LinearExpr;
+
+    fn sub(self, other: &LinearExpr) -> LinearExpr {
+        LinearExpr {
+            coefficient: &self.coefficient - &other.coefficient,
+            constant: &self.constant - &other.constant,
+        }
+    }
+}
+
+impl fmt::Display for LinearExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.coefficient.is_zero(), self.constant.is_zero()) {
+            (true, _) => write!(f, "{}", self.constant),
+            (false, true) => write!(f, "{}x", self.coefficient),
+            (false, false) if self.constant.is_negative() => {
+                write!(f, "{}x - {}", self.coefficient, -self.constant.clone())
+            }
+            (false, false) => write!(f, "{}x + {}", self.coefficient, self.constant),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fraction(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn it_calculates_determinant() {
+        assert_eq!(
+            determinant(&from_integers(&[
+                vec![3, 7],
+                vec![1,-4]])),
+            rational(-19)
+        );
+
+        assert_eq!(
+            determinant(&from_integers(&[
+                vec![-2,-1, 2],
+                vec![ 2, 1, 4],
+                vec![-3, 3,-1]])),
+            rational(54)
+        );
+
+        assert_eq!(
+            determinant(&from_integers(&[
+                vec![-1, 1, 4, 2],
+                vec![ 2,-1, 2, 5],
+                vec![ 1, 2, 3, 4],
+                vec![ 3, 4,-1, 2]])),
+            rational(-26)
+        );
+
+        assert_eq!(
+            determinant(&from_integers(&[
+                vec![1, 2],
+                vec![2, 4]])),
+            rational(0)
+        );
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn it_inverts_matrices() {
+        let matrix = from_integers(&[
+            vec![2, 1],
+            vec![7, 4]]);
+
+        assert_eq!(
+            inverse(&matrix),
+            Some(from_integers(&[
+                vec![ 4,-1],
+                vec![-7, 2]]))
+        );
+
+        assert_eq!(
+            inverse(&from_integers(&[
+                vec![1, 2],
+                vec![2, 4]])),
+            None
+        );
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn it_solves_systems_of_equations() {
+        // x + y = 3, x - y = 0
+        assert_eq!(
+            solve(&from_integers(&[
+                vec![1, 1, 3],
+                vec![1,-1, 0]])),
+            Ok(vec![fraction(3, 2), fraction(3, 2)])
+        );
+
+        // Redundant equations are fine as long as they agree
+        assert_eq!(
+            solve(&from_integers(&[
+                vec![0, 2, 4],
+                vec![1, 0, 1],
+                vec![2, 2, 6]])),
+            Ok(vec![rational(1), rational(2)])
+        );
+
+        assert!(solve(&from_integers(&[
+            vec![1, 1, 3],
+            vec![2, 2, 7]])).is_err());
+
+        assert!(solve(&from_integers(&[
+            vec![1, 1, 3],
+            vec![2, 2, 6]])).is_err());
+    }
+
+    #[test]
+    fn it_solves_linear_expressions() {
+        // (4 + 2 * (x - 3)) / 4 = 150 / 2
+        let left = LinearExpr::constant(rational(2))
+            .mul(&(&LinearExpr::unknown() - &LinearExpr::constant(rational(3))))
+            .map(|expr| &expr + &LinearExpr::constant(rational(4)))
+            .and_then(|expr| expr.div(&LinearExpr::constant(rational(4))))
+            .unwrap();
+        let right = LinearExpr::constant(rational(150))
+            .div(&LinearExpr::constant(rational(2)))
+            .unwrap();
+
+        assert_eq!(left.to_string(), "1/2x - 1/2");
+        assert_eq!(left.solve(&right), Ok(rational(151)));
+
+        assert!(LinearExpr::unknown().mul(&LinearExpr::unknown()).is_err());
+        assert!(LinearExpr::unknown()
+            .div(&LinearExpr::constant(rational(0)))
+            .is_err());
+        assert!(LinearExpr::unknown().solve(&LinearExpr::unknown()).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl CacheStats {
+    pub fn lookups(&self) -> usize {
+        self.hits + self.misses
+    }
+}
+
+/// Cache of previously computed values, keeping count of the cache hits and misses.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: CacheStats,
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Hash + Eq,
+{
+    pub fn new() -> Self {
+        Memo {
+            cache: HashMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        match self.cache.get(key) {
+            Some(value) => {
+                self.stats.hits += 1;
+                Some(value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.cache.insert(key, value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = CacheStats::default();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+impl<K, V> Default for Memo<K, V>
+where
+    K: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recursive function with its results cached by the arguments.
+/// The function receives a callback for recursing back into itself through the cache.
+pub struct Memoized<K, V, F>
+where
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    memo: Memo<K, V>,
+    function: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(function: F) -> Self {
+        Memoized {
+            memo: Memo::new(),
+            function,
+        }
+    }
+
+    pub fn call(&mut self, key: K) -> V {
+        Self::call_cached(&mut self.memo, &self.function, key)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.memo.stats()
+    }
+
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+
+    fn call_cached(memo: &mut Memo<K, V>, function: &F, key: K) -> V {
+        if let Some(value) = memo.get(&key) {
+            return value.clone();
+        }
+
+        let value = function(
+            &mut |next| Self::call_cached(memo, function, next),
+            key.clone(),
+        );
+        memo.insert(key, value.clone());
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_caches_recursive_calls() {
+        let mut fibonacci = Memoized::new(|fibonacci: &mut dyn FnMut(u64) -> u64, n: u64| {
+            if n < 2 {
+                n
+            } else {
+                fibonacci(n - 1) + fibonacci(n - 2)
+            }
+        });
+
+        assert_eq!(fibonacci.call(10), 55);
+        // Every value from 0 to 10 is computed exactly once,
+        // the second branch of each recursion hits the cache
+        assert_eq!(
+            fibonacci.stats(),
+            CacheStats {
+                hits: 8,
+                misses: 11
+            }
+        );
+        assert_eq!(fibonacci.memo().len(), 11);
+
+        assert_eq!(fibonacci.call(10), 55);
+        assert_eq!(fibonacci.stats().hits, 9);
+    }
+
+    #[test]
+    fn it_counts_lookups() {
+        let mut memo: Memo<&str, u32> = Memo::new();
+
+        assert_eq!(memo.get(&"a"), None);
+        memo.insert("a", 1);
+        assert_eq!(memo.get(&"a"), Some(&1));
+        assert_eq!(memo.stats().lookups(), 2);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), CacheStats::default());
+    }
+}
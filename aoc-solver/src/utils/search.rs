@@ -0,0 +1,196 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Progress of a search, reported to the observer of the search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchEvent<N> {
+    /// A cheaper way to the node was found and it was added to the frontier
+    Discover(N, u32),
+    /// The node was taken from the frontier and its neighbours were expanded
+    Visit(N, u32),
+}
+
+impl<N> SearchEvent<N> {
+    pub fn map<M, F: FnOnce(N) -> M>(self, f: F) -> SearchEvent<M> {
+        match self {
+            SearchEvent::Discover(node, cost) => SearchEvent::Discover(f(node), cost),
+            SearchEvent::Visit(node, cost) => SearchEvent::Visit(f(node), cost),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: u32,
+    /// Nodes of the path from the source to the goal, both included
+    pub nodes: Vec<N>,
+}
+
+/// Nodes are kept in an arena, so the heap doesn't need them to be ordered.
+struct Frontier<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    costs: Vec<u32>,
+    parents: Vec<Option<usize>>,
+    heap: BinaryHeap<(Reverse<u32>, Reverse<usize>)>,
+}
+
+impl<N: Clone + Eq + Hash> Frontier<N> {
+    /// Returns the index of the node, unless a cheaper way to it is already known.
+    fn push(&mut self, node: N, cost: u32, parent: Option<usize>) -> Option<usize> {
+        let index = match self.indices.get(&node) {
+            Some(index) if self.costs[*index] <= cost => return None,
+            Some(index) => {
+                self.costs[*index] = cost;
+                self.parents[*index] = parent;
+                *index
+            }
+            None => {
+                self.indices.insert(node.clone(), self.nodes.len());
+                self.nodes.push(node);
+                self.costs.push(cost);
+                self.parents.push(parent);
+                self.nodes.len() - 1
+            }
+        };
+
+        self.heap.push((Reverse(cost), Reverse(index)));
+
+        Some(index)
+    }
+
+    fn path(&self, mut index: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[index].clone()];
+
+        while let Some(parent) = self.parents[index] {
+            path.push(self.nodes[parent].clone());
+            index = parent;
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Dijkstra's algorithm from any of the `sources` to the first node accepted by `is_goal`.
+/// The `neighbours` of a node get the cost of reaching it, and every change to the frontier
+/// is reported to `on_event`, which lets the search be replayed afterwards.
+pub fn dijkstra<N, S, FN, NI, FG, FE>(
+    sources: S,
+    mut neighbours: FN,
+    mut is_goal: FG,
+    mut on_event: FE,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    S: IntoIterator<Item = N>,
+    FN: FnMut(&N, u32) -> NI,
+    NI: IntoIterator<Item = (N, u32)>,
+    FG: FnMut(&N) -> bool,
+    FE: FnMut(SearchEvent<&N>),
+{
+    let mut frontier = Frontier {
+        nodes: Vec::new(),
+        indices: HashMap::new(),
+        costs: Vec::new(),
+        parents: Vec::new(),
+        heap: BinaryHeap::new(),
+    };
+
+    for source in sources {
+        if let Some(index) = frontier.push(source, 0, None) {
+            on_event(SearchEvent::Discover(&frontier.nodes[index], 0));
+        }
+    }
+
+    while let Some((Reverse(cost), Reverse(index))) = frontier.heap.pop() {
+        // We've already found a better way
+        if cost > frontier.costs[index] {
+            continue;
+        }
+
+        let node = frontier.nodes[index].clone();
+        on_event(SearchEvent::Visit(&node, cost));
+
+        if is_goal(&node) {
+            return Some(Path {
+                cost,
+                nodes: frontier.path(index),
+            });
+        }
+
+        for (neighbour, step_cost) in neighbours(&node, cost) {
+            let next_cost = cost + step_cost;
+            if let Some(next) = frontier.push(neighbour, next_cost, Some(index)) {
+                on_event(SearchEvent::Discover(&frontier.nodes[next], next_cost));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(node: &i32, _: u32) -> Vec<(i32, u32)> {
+        vec![(node - 1, 1), (node + 1, 1), (node + 3, 5)]
+    }
+
+    #[test]
+    fn it_finds_the_cheapest_path() {
+        let path = dijkstra([0], line, |node| *node == 3, |_| {});
+
+        assert_eq!(
+            path,
+            Some(Path {
+                cost: 3,
+                nodes: vec![0, 1, 2, 3]
+            })
+        );
+    }
+
+    #[test]
+    fn it_starts_from_the_closest_source() {
+        let path = dijkstra([0, 5, 10], line, |node| *node == 7, |_| {});
+
+        assert_eq!(path.map(|path| path.nodes), Some(vec![5, 6, 7]));
+    }
+
+    #[test]
+    fn it_reports_the_search_events() {
+        let mut events = Vec::new();
+        dijkstra(
+            [0],
+            |node, _| (*node < 2).then_some((node + 1, 1)),
+            |node| *node == 2,
+            |event| events.push(event.map(|node| *node)),
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                SearchEvent::Discover(0, 0),
+                SearchEvent::Visit(0, 0),
+                SearchEvent::Discover(1, 1),
+                SearchEvent::Visit(1, 1),
+                SearchEvent::Discover(2, 2),
+                SearchEvent::Visit(2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_gives_up_when_the_goal_is_unreachable() {
+        let path = dijkstra(
+            [0],
+            |node, _| (*node < 5).then_some((node + 1, 1)),
+            |node| *node == 10,
+            |_| {},
+        );
+
+        assert_eq!(path, None);
+    }
+}
@@ -0,0 +1,341 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::solution::{AocError, Solution};
+use crate::utils::search::{Path, SearchEvent};
+
+pub type Frames = Box<dyn Iterator<Item = Frame>>;
+
+/// Position of a node of a graph
+pub type Position = (f64, f64);
+
+/// Roughly how many frames a replayed search is split into
+const SEARCH_FRAMES: usize = 200;
+
+/// A single step of a visualization.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Frame {
+    pub caption: String,
+    pub content: Content,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Content {
+    /// Rows of characters
+    Grid(Vec<String>),
+    /// Unit cubes in 3D space
+    Voxels(Vec<Voxel>),
+    Graph(Graph),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Voxel {
+    pub position: (i32, i32, i32),
+    /// CSS color of the cube
+    pub color: Cow<'static, str>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    /// Positions of the `nodes`, filled in by `lay_out_graphs`
+    pub positions: Vec<Position>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Node {
+    pub label: String,
+    /// CSS color of the node
+    pub color: Cow<'static, str>,
+}
+
+/// Directed edge between indices to the `nodes` of the graph
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// CSS color of the edge
+    pub color: Cow<'static, str>,
+}
+
+impl Frame {
+    pub fn grid<C: Into<String>>(caption: C, rows: Vec<String>) -> Self {
+        Frame {
+            caption: caption.into(),
+            content: Content::Grid(rows),
+        }
+    }
+
+    pub fn voxels<C: Into<String>>(caption: C, voxels: Vec<Voxel>) -> Self {
+        Frame {
+            caption: caption.into(),
+            content: Content::Voxels(voxels),
+        }
+    }
+
+    pub fn graph<C: Into<String>>(caption: C, graph: Graph) -> Self {
+        Frame {
+            caption: caption.into(),
+            content: Content::Graph(graph),
+        }
+    }
+}
+
+/// Solutions that can be stepped through in the web app, one frame at a time.
+/// The names of the visualizations are listed in the `DayInfo` of the day.
+pub trait Visualize: Solution {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError>;
+
+    fn visualize_default(&self, name: &str, input: Option<String>) -> Result<Frames, AocError> {
+        match input {
+            Some(input) => self.visualize(name, &input),
+            None => self.visualize(name, self.default_input()),
+        }
+    }
+}
+
+pub fn unknown_visualization(name: &str) -> AocError {
+    AocError::logic(format!("Unknown visualization \"{name}\""))
+}
+
+/// Replays a search over the `tiles` of a grid, drawing the frontier as `+` and the visited
+/// tiles as `.`. The last frame shows the found path as `#` on top of the original tiles.
+pub fn search_frames(
+    tiles: Vec<Vec<char>>,
+    events: Vec<SearchEvent<(usize, usize)>>,
+    path: Option<Path<(usize, usize)>>,
+) -> Frames {
+    let chunk_size = events.len().div_ceil(SEARCH_FRAMES).max(1);
+    let chunks: Vec<Vec<SearchEvent<(usize, usize)>>> = events
+        .chunks(chunk_size)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+
+    let rows = |grid: &[Vec<char>]| -> Vec<String> {
+        grid.iter().map(|row| row.iter().collect()).collect()
+    };
+
+    let mut result = tiles.clone();
+    let result = match path {
+        Some(path) => {
+            for (x, y) in path.nodes {
+                result[y][x] = '#';
+            }
+            Frame::grid(format!("Lowest cost: {}", path.cost), rows(&result))
+        }
+        None => Frame::grid("No path found", rows(&result)),
+    };
+
+    let mut grid = tiles;
+    let (mut visited, mut cost) = (0, 0);
+
+    let search = chunks.into_iter().map(move |chunk| {
+        for event in chunk {
+            match event {
+                SearchEvent::Discover((x, y), _) => {
+                    if grid[y][x] != '.' {
+                        grid[y][x] = '+';
+                    }
+                }
+                SearchEvent::Visit((x, y), visit_cost) => {
+                    grid[y][x] = '.';
+                    cost = visit_cost;
+                    visited += 1;
+                }
+            }
+        }
+
+        Frame::grid(format!("Visited: {visited}, cost: {cost}"), rows(&grid))
+    });
+
+    Box::new(search.chain(std::iter::once(result)))
+}
+
+/// Places the nodes of the graph frames. Frames with the same connections share the layout,
+/// so the nodes stay put between frames.
+pub fn lay_out_graphs(frames: &mut [Frame]) {
+    let mut laid_out: Vec<(usize, usize)> = Vec::new();
+    let mut positions: Vec<Position> = Vec::new();
+
+    for frame in frames {
+        let Content::Graph(graph) = &mut frame.content else {
+            continue;
+        };
+
+        let connections: Vec<(usize, usize)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to))
+            .collect();
+        if connections != laid_out || positions.len() != graph.nodes.len() {
+            positions = layout(graph.nodes.len(), &connections);
+            laid_out = connections;
+        }
+
+        graph.positions = positions.clone();
+    }
+}
+
+/// Fruchterman-Reingold layout: connected nodes pull each other closer, while nearby nodes
+/// push each other away. Starts from the nodes evenly on a circle. Only the nodes in the
+/// neighbouring cells of a grid push each other, which keeps large graphs fast.
+pub fn layout(count: usize, edges: &[(usize, usize)]) -> Vec<Position> {
+    const SIZE: f64 = 800.0;
+    const ITERATIONS: usize = 100;
+
+    let mut positions: Vec<Position> = (0..count)
+        .map(|index| {
+            let angle = std::f64::consts::TAU * index as f64 / count as f64;
+            (SIZE / 2.0 * angle.cos(), SIZE / 2.0 * angle.sin())
+        })
+        .collect();
+
+    let k = (SIZE * SIZE / count.max(1) as f64).sqrt();
+    let cell_size = 2.0 * k;
+    let cell = |(x, y): Position| {
+        (
+            (x / cell_size).floor() as i64,
+            (y / cell_size).floor() as i64,
+        )
+    };
+
+    for iteration in 0..ITERATIONS {
+        let temperature = SIZE / 10.0 * (1.0 - iteration as f64 / ITERATIONS as f64);
+        let mut displacements = vec![(0.0, 0.0); count];
+
+        let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (index, position) in positions.iter().enumerate() {
+            grid.entry(cell(*position)).or_default().push(index);
+        }
+
+        for a in 0..count {
+            let (x, y) = cell(positions[a]);
+            let nearby = (x - 1..=x + 1)
+                .cartesian_product(y - 1..=y + 1)
+                .filter_map(|cell| grid.get(&cell))
+                .flatten();
+
+            for b in nearby.filter(|b| **b != a) {
+                let (dx, dy) = (
+                    positions[a].0 - positions[*b].0,
+                    positions[a].1 - positions[*b].1,
+                );
+                let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                if distance > cell_size {
+                    continue;
+                }
+
+                let force = k * k / distance;
+                displacements[a].0 += dx / distance * force;
+                displacements[a].1 += dy / distance * force;
+            }
+        }
+
+        for (a, b) in edges {
+            let (dx, dy) = (
+                positions[*a].0 - positions[*b].0,
+                positions[*a].1 - positions[*b].1,
+            );
+            let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+            let force = distance * distance / k;
+
+            displacements[*a].0 -= dx / distance * force;
+            displacements[*a].1 -= dy / distance * force;
+            displacements[*b].0 += dx / distance * force;
+            displacements[*b].1 += dy / distance * force;
+        }
+
+        for (position, (dx, dy)) in positions.iter_mut().zip(displacements) {
+            let length = (dx * dx + dy * dy).sqrt().max(0.01);
+            let step = length.min(temperature);
+
+            position.0 += dx / length * step;
+            position.1 += dy / length * step;
+        }
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_replays_a_search_on_a_grid() {
+        let tiles = vec![vec!['1', '2'], vec!['3', '4']];
+        let events = vec![
+            SearchEvent::Discover((0, 0), 0),
+            SearchEvent::Visit((0, 0), 0),
+            SearchEvent::Discover((1, 0), 2),
+            SearchEvent::Discover((0, 1), 3),
+        ];
+        let path = Path {
+            cost: 6,
+            nodes: vec![(0, 0), (1, 0), (1, 1)],
+        };
+
+        let frames: Vec<Frame> = search_frames(tiles, events, Some(path)).collect();
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            frames[3..].to_vec(),
+            vec![
+                Frame::grid("Visited: 1, cost: 0", vec![".+".into(), "+4".into()]),
+                Frame::grid("Lowest cost: 6", vec!["##".into(), "3#".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_lays_out_graphs_once_per_connections() {
+        let distance = |a: Position, b: Position| (a.0 - b.0).hypot(a.1 - b.1);
+
+        let positions = layout(3, &[(0, 1)]);
+        assert_eq!(positions.len(), 3);
+        assert!(distance(positions[0], positions[1]) < distance(positions[0], positions[2]));
+
+        let node = |label: &str| Node {
+            label: label.to_string(),
+            color: "#cccccc".into(),
+        };
+        let edge = |from, to| Edge {
+            from,
+            to,
+            color: "#333340".into(),
+        };
+        let graph = Graph {
+            nodes: vec![node("a"), node("b"), node("c")],
+            edges: vec![edge(0, 1)],
+            ..Graph::default()
+        };
+
+        let mut frames = vec![
+            Frame::graph("first", graph.clone()),
+            Frame::grid("between", vec![]),
+            Frame::graph("second", graph),
+        ];
+        lay_out_graphs(&mut frames);
+
+        let positions: Vec<&Vec<Position>> = frames
+            .iter()
+            .filter_map(|frame| match &frame.content {
+                Content::Graph(graph) => Some(&graph.positions),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(positions, vec![&layout(3, &[(0, 1)]); 2]);
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
 
 use nom::{branch::alt, bytes::complete::tag, character::complete::space0, IResult};
 
@@ -12,12 +13,27 @@ pub enum Flag {
     Running,
     InfiniteLoop,
     Exception,
+    Breakpoint,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Instruction {
-    operation: String,
-    arg: i32,
+    pub operation: String,
+    pub arg: i32,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.operation, self.arg)
+    }
+}
+
+/// Single executed instruction, with the accumulator after executing it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub program_counter: usize,
+    pub instruction: Instruction,
+    pub accumulator: i32,
 }
 
 #[derive(Debug, Default)]
@@ -25,9 +41,18 @@ pub struct GameConsole {
     pub mem: Vec<Instruction>,
     pub program_counter: usize,
     pub accumulator: i32,
+    pub breakpoints: HashSet<usize>,
+    executed: HashSet<usize>,
 }
 
 impl GameConsole {
+    pub fn new(mem: Vec<Instruction>) -> GameConsole {
+        GameConsole {
+            mem,
+            ..Default::default()
+        }
+    }
+
     // `acc` increases or decreases a single global value called the accumulator by the value given in the argument.
     #[inline]
     fn acc(&mut self) -> Flag {
@@ -40,9 +65,13 @@ impl GameConsole {
     // The next instruction to execute is found using the argument as an offset from the jmp instruction.
     #[inline]
     fn jmp(&mut self) -> Flag {
-        self.program_counter =
-            (self.program_counter as i32 + self.mem[self.program_counter].arg) as usize;
-        Flag::Running
+        match jump_target(self.program_counter, self.mem[self.program_counter].arg) {
+            Some(target) => {
+                self.program_counter = target;
+                Flag::Running
+            }
+            None => Flag::Exception,
+        }
     }
 
     // `nop` stands for No OPeration - it does nothing. The instruction immediately below it is executed next.
@@ -52,38 +81,169 @@ impl GameConsole {
         Flag::Running
     }
 
-    pub fn run_program(&mut self) -> Flag {
-        let mut executed: HashSet<usize> = HashSet::new();
+    /// Executes the next instruction, unless the program has already stopped
+    pub fn step(&mut self) -> Flag {
+        if self.program_counter == self.mem.len() {
+            return Flag::Halted;
+        }
+
+        if self.program_counter > self.mem.len() {
+            return Flag::Exception;
+        }
+
+        if !self.executed.insert(self.program_counter) {
+            return Flag::InfiniteLoop;
+        }
+
+        match self.mem[self.program_counter].operation.as_str() {
+            "acc" => self.acc(),
+            "jmp" => self.jmp(),
+            "nop" => self.nop(),
+            _ => Flag::Exception,
+        }
+    }
+
+    /// Runs the program until it stops, calling `on_step` after every executed instruction.
+    /// Stops at breakpoints, unless the program is already paused at one.
+    pub fn run<F>(&mut self, mut on_step: F) -> Flag
+    where
+        F: FnMut(&Step),
+    {
+        let mut is_first = true;
+
         loop {
-            if !executed.insert(self.program_counter) {
-                return Flag::InfiniteLoop;
+            if !is_first && self.breakpoints.contains(&self.program_counter) {
+                return Flag::Breakpoint;
             }
+            is_first = false;
 
-            if self.program_counter >= self.mem.len() {
-                return Flag::Halted;
+            let program_counter = self.program_counter;
+            let flag = self.step();
+
+            if flag != Flag::Running {
+                return flag;
             }
 
-            match self.mem[self.program_counter].operation.as_str() {
-                "acc" => self.acc(),
-                "jmp" => self.jmp(),
-                "nop" => self.nop(),
-                _ => panic!(),
-            };
+            on_step(&Step {
+                program_counter,
+                instruction: self.mem[program_counter].clone(),
+                accumulator: self.accumulator,
+            });
         }
     }
+
+    pub fn run_program(&mut self) -> Flag {
+        self.run(|_| {})
+    }
+
+    /// Runs the program, recording every executed instruction
+    pub fn trace(&mut self) -> (Flag, Vec<Step>) {
+        let mut steps = Vec::new();
+        let flag = self.run(|step| steps.push(step.clone()));
+
+        (flag, steps)
+    }
+
+    /// Restarts the program from the first instruction, keeping the breakpoints
+    pub fn reset(&mut self) {
+        self.program_counter = 0;
+        self.accumulator = 0;
+        self.executed.clear();
+    }
+}
+
+fn jump_target(program_counter: usize, offset: i32) -> Option<usize> {
+    program_counter.checked_add_signed(offset as isize)
+}
+
+fn next_instruction(mem: &[Instruction], program_counter: usize, operation: &str) -> Option<usize> {
+    match operation {
+        "jmp" => jump_target(program_counter, mem[program_counter].arg),
+        _ => Some(program_counter + 1),
+    }
+}
+
+fn flipped(operation: &str) -> Option<&'static str> {
+    match operation {
+        "jmp" => Some("nop"),
+        "nop" => Some("jmp"),
+        _ => None,
+    }
+}
+
+/// Finds the single `jmp` or `nop` that makes the program terminate when flipped.
+/// Walks backwards from the end of the program to find every instruction that
+/// terminates, then picks the flip that leads from the executed loop into one of them.
+pub fn find_fix(mem: &[Instruction]) -> Option<usize> {
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); mem.len() + 1];
+    for (program_counter, instruction) in mem.iter().enumerate() {
+        if let Some(next) = next_instruction(mem, program_counter, &instruction.operation) {
+            if next <= mem.len() {
+                predecessors[next].push(program_counter);
+            }
+        }
+    }
+
+    let mut terminates = vec![false; mem.len() + 1];
+    terminates[mem.len()] = true;
+
+    let mut queue = VecDeque::from([mem.len()]);
+    while let Some(current) = queue.pop_front() {
+        for previous in predecessors[current].iter() {
+            if !terminates[*previous] {
+                terminates[*previous] = true;
+                queue.push_back(*previous);
+            }
+        }
+    }
+
+    let mut executed = HashSet::new();
+    let mut program_counter = 0;
+
+    while program_counter < mem.len() && executed.insert(program_counter) {
+        let operation = mem[program_counter].operation.as_str();
+
+        if let Some(fixed) = flipped(operation) {
+            if next_instruction(mem, program_counter, fixed)
+                .is_some_and(|next| next <= mem.len() && terminates[next])
+            {
+                return Some(program_counter);
+            }
+        }
+
+        program_counter = next_instruction(mem, program_counter, operation)?;
+    }
+
+    None
 }
 
 fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
     let (i, operation) = alt((tag("acc"), tag("jmp"), tag("nop")))(input)?;
     let (i, _ws) = space0(i)?;
-    let arg = i.parse::<i32>().unwrap();
 
     let instruction = Instruction {
         operation: operation.to_string(),
-        arg,
+        arg: 0,
     };
 
-    Ok(("", instruction))
+    Ok((i, instruction))
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, AocError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (arg, instruction) =
+                parse_instruction(line.trim()).map_err(|err| AocError::parse(line, err))?;
+
+            let arg = arg
+                .parse::<i32>()
+                .map_err(|err| AocError::parse(line, err))?;
+
+            Ok(Instruction { arg, ..instruction })
+        })
+        .collect()
 }
 
 impl Solution for Day08 {
@@ -95,16 +255,7 @@ impl Solution for Day08 {
     }
 
     fn part_1(&self, input: &str) -> Result<i32, AocError> {
-        let instructions: Vec<Instruction> = input
-            .lines()
-            .filter_map(|line| parse_instruction(line).ok())
-            .map(|(_, instruction)| instruction)
-            .collect();
-
-        let mut console = GameConsole {
-            mem: instructions,
-            ..Default::default()
-        };
+        let mut console = GameConsole::new(parse_program(input)?);
 
         let _flag = console.run_program();
 
@@ -112,38 +263,19 @@ impl Solution for Day08 {
     }
 
     fn part_2(&self, input: &str) -> Result<i32, AocError> {
-        let corrupted_instructions: Vec<Instruction> = input
-            .lines()
-            .filter_map(|line| parse_instruction(line).ok())
-            .map(|(_, instruction)| instruction)
-            .collect();
-
-        for (i, instruction) in corrupted_instructions.iter().enumerate() {
-            if instruction.operation == "acc" {
-                continue;
-            }
-
-            let mut fixed_instructions = corrupted_instructions.clone();
-
-            match instruction.operation.as_str() {
-                "nop" => fixed_instructions[i].operation = String::from("jmp"),
-                "jmp" => fixed_instructions[i].operation = String::from("nop"),
-                _ => continue,
-            }
+        let mut instructions = parse_program(input)?;
 
-            let mut console = GameConsole {
-                mem: fixed_instructions,
-                ..Default::default()
-            };
+        let fix = find_fix(&instructions).ok_or(AocError::logic("No solution"))?;
+        if let Some(operation) = flipped(&instructions[fix].operation) {
+            instructions[fix].operation = operation.to_string();
+        }
 
-            let flag = console.run_program();
+        let mut console = GameConsole::new(instructions);
 
-            if flag == Flag::Halted {
-                return Ok(console.accumulator);
-            }
+        match console.run_program() {
+            Flag::Halted => Ok(console.accumulator),
+            _ => Err(AocError::logic("No solution")),
         }
-
-        Err(AocError::logic("No solution"))
     }
 }
 
@@ -151,6 +283,16 @@ impl Solution for Day08 {
 mod tests {
     use super::*;
 
+    const EXAMPLE_INPUT: &str = "nop +0\n\
+                                 acc +1\n\
+                                 jmp +4\n\
+                                 acc +3\n\
+                                 jmp -3\n\
+                                 acc -99\n\
+                                 acc +1\n\
+                                 jmp -4\n\
+                                 acc +6";
+
     #[test]
     fn it_solves_part1_example() {
         assert_eq!(
@@ -186,4 +328,68 @@ mod tests {
             Ok(8)
         );
     }
+
+    #[test]
+    fn it_traces_the_executed_instructions() {
+        let mut console = GameConsole::new(parse_program(EXAMPLE_INPUT).unwrap());
+        let (flag, steps) = console.trace();
+
+        assert_eq!(flag, Flag::InfiniteLoop);
+        assert_eq!(
+            steps
+                .iter()
+                .map(|step| (step.program_counter, step.accumulator))
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 1), (6, 2), (7, 2), (3, 5), (4, 5)]
+        );
+        assert_eq!(steps[2].instruction.to_string(), "jmp +4");
+    }
+
+    #[test]
+    fn it_stops_at_breakpoints() {
+        let mut console = GameConsole::new(parse_program(EXAMPLE_INPUT).unwrap());
+        console.breakpoints.insert(3);
+
+        assert_eq!(console.run_program(), Flag::Breakpoint);
+        assert_eq!((console.program_counter, console.accumulator), (3, 2));
+
+        assert_eq!(console.step(), Flag::Running);
+        assert_eq!(console.accumulator, 5);
+
+        assert_eq!(console.run_program(), Flag::InfiniteLoop);
+        assert_eq!(console.program_counter, 1);
+
+        console.reset();
+        assert_eq!(console.run_program(), Flag::Breakpoint);
+    }
+
+    #[test]
+    fn it_raises_exceptions() {
+        let mut console = GameConsole::new(parse_program("acc +1\njmp -2").unwrap());
+        assert_eq!(console.run_program(), Flag::Exception);
+
+        let mut console = GameConsole::new(parse_program("jmp +3\nnop +0").unwrap());
+        assert_eq!(console.run_program(), Flag::Exception);
+    }
+
+    #[test]
+    fn it_rejects_invalid_instructions() {
+        assert!(parse_program("mul +2").is_err());
+        assert!(parse_program("acc two").is_err());
+    }
+
+    #[test]
+    fn it_finds_the_instruction_to_fix() {
+        let mem = parse_program(EXAMPLE_INPUT).unwrap();
+        assert_eq!(find_fix(&mem), Some(7));
+
+        let mem = parse_program("jmp +0\nacc +1").unwrap();
+        assert_eq!(find_fix(&mem), Some(0));
+
+        let mem = parse_program("acc +1\njmp -1").unwrap();
+        assert_eq!(find_fix(&mem), Some(1));
+
+        let mem = parse_program("acc +1\njmp +0\njmp -2").unwrap();
+        assert_eq!(find_fix(&mem), None);
+    }
 }
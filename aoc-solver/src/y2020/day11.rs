@@ -1,161 +1,125 @@
-use std::collections::HashMap;
-
 use crate::solution::{AocError, Solution};
+use crate::utils::automaton::{Automaton, DenseAutomaton, Grid, Square};
+use crate::utils::Coords;
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 pub struct Day11;
 
-const ADJACENT_TILES: [(i32, i32); 8] = [
-    (-1, -1),
-    (0, -1),
-    (1, -1),
-    (-1, 0),
-    (1, 0),
-    (-1, 1),
-    (0, 1),
-    (1, 1),
-];
-
-#[derive(Debug, Default)]
+/// The seats settle in around a hundred rounds, a rule that never settles gives up here
+const MAX_ROUNDS: u32 = 10_000;
+
+#[derive(Debug)]
 pub struct GameOfSeats {
-    pub seats: HashMap<(i32, i32), char>,
+    pub seats: Grid<char>,
     pub step: u32,
-    pub dimensions: (usize, usize),
+}
+
+fn adjacent_occupied_count(seats: &Grid<char>, seat: Coords<i32>) -> usize {
+    seats
+        .neighbours(&Square::WITH_DIAGONALS, seat)
+        .filter(|seat| **seat == '#')
+        .count()
+}
+
+fn occupied_at_direction(seats: &Grid<char>, seat: Coords<i32>, direction: (i32, i32)) -> bool {
+    let current = Coords {
+        x: seat.x + direction.0,
+        y: seat.y + direction.1,
+    };
+
+    match seats.get(current) {
+        Some('#') => true,
+        Some('L') => false,
+        Some('.') => occupied_at_direction(seats, current, direction),
+        _ => false,
+    }
+}
+
+fn visible_occupied_count(seats: &Grid<char>, seat: Coords<i32>) -> usize {
+    Square::WITH_DIAGONALS
+        .offsets()
+        .iter()
+        .filter(|direction| occupied_at_direction(seats, seat, **direction))
+        .count()
 }
 
 impl GameOfSeats {
     #[inline]
     fn new(input: &str) -> GameOfSeats {
-        let seats: HashMap<(i32, i32), char> = input
-            .lines()
-            .enumerate()
-            .flat_map(|(row, columns)| -> HashMap<(i32, i32), char> {
-                columns
-                    .chars()
-                    .enumerate()
-                    .map(|(column, seat)| ((column as i32, row as i32), seat))
-                    .collect()
-            })
-            .collect();
-
-        let height = input.lines().count();
-        let width = input.lines().next().unwrap().chars().count();
-
         GameOfSeats {
-            seats,
+            seats: Grid::parse(input, |seat| seat),
             step: 0,
-            dimensions: (width, height),
-        }
-    }
-
-    #[allow(dead_code)]
-    fn print(&self) {
-        println!("Step {:?}:", self.step);
-        for y in 0..self.dimensions.1 {
-            for x in 0..self.dimensions.0 {
-                print!("{}", self.seats.get(&(x as i32, y as i32)).unwrap());
-            }
-            println!();
         }
-        println!();
     }
 
     fn occupied_seats_count(&self) -> usize {
         self.seats
             .iter()
-            .filter(|(_coords, seat)| *seat == &'#')
+            .filter(|(_coords, seat)| **seat == '#')
             .count()
     }
 
-    fn is_occupied(&self, seat: (i32, i32)) -> bool {
-        matches!(self.seats.get(&seat), Some('#'))
+    #[cfg(test)]
+    fn visible_occupied_count(&self, (x, y): (i32, i32)) -> usize {
+        visible_occupied_count(&self.seats, Coords { x, y })
     }
 
-    fn adjacent_occupied_count(&self, seat: (i32, i32)) -> usize {
-        ADJACENT_TILES
-            .iter()
-            .filter(|(x, y)| self.is_occupied((seat.0 + x, seat.1 + y)))
-            .count()
+    fn simulate<F>(&mut self, rule: F) -> Result<usize, AocError>
+    where
+        F: Fn(&Grid<char>, Coords<i32>, &char) -> char,
+    {
+        let mut automaton = DenseAutomaton::new(self.seats.clone(), rule);
+
+        let stable = automaton.run_until_stable(MAX_ROUNDS).ok_or_else(|| {
+            AocError::logic(format!(
+                "The seats didn't settle within {MAX_ROUNDS} rounds"
+            ))
+        })?;
+        self.step = stable.generation;
+        self.seats = automaton.into_grid();
+
+        Ok(self.occupied_seats_count())
     }
 
-    fn part_1(&mut self) -> usize {
-        // self.print();
-
-        let mut is_stabilized = false;
-
-        while !is_stabilized {
-            let mut new_seats = self.seats.clone();
-            self.step += 1;
-
-            for (seat, state) in self.seats.clone() {
-                // If a seat is empty (L) and there are no occupied seats adjacent to it, the seat becomes occupied.
-                if state == 'L' && self.adjacent_occupied_count(seat) == 0 {
-                    *new_seats.entry(seat).or_insert('#') = '#';
-                }
-                // If a seat is occupied (#) and four or more seats adjacent to it are also occupied, the seat becomes empty.
-                else if state == '#' && self.adjacent_occupied_count(seat) >= 4 {
-                    *new_seats.entry(seat).or_insert('L') = 'L';
-                }
-            }
-
-            is_stabilized = new_seats == self.seats;
-            self.seats = new_seats;
-
-            // self.print();
-        }
-
-        self.occupied_seats_count()
+    fn part_1(&mut self) -> Result<usize, AocError> {
+        self.simulate(adjacent_rule)
     }
 
-    fn occupied_at_direction(&self, seat: (i32, i32), direction: (i32, i32)) -> bool {
-        let current = (seat.0 + direction.0, seat.1 + direction.1);
-
-        match self.seats.get(&current) {
-            Some('#') => true,
-            Some('L') => false,
-            Some('.') => self.occupied_at_direction(current, direction),
-            _ => false,
-        }
+    fn part_2(&mut self) -> Result<usize, AocError> {
+        self.simulate(visible_rule)
     }
+}
 
-    fn visible_occupied_count(&self, seat: (i32, i32)) -> usize {
-        ADJACENT_TILES
-            .iter()
-            .filter(|direction| self.occupied_at_direction(seat, **direction))
-            .count()
+fn adjacent_rule(seats: &Grid<char>, coords: Coords<i32>, seat: &char) -> char {
+    match seat {
+        // If a seat is empty (L) and there are no occupied seats adjacent to it, the seat becomes occupied.
+        'L' if adjacent_occupied_count(seats, coords) == 0 => '#',
+        // If a seat is occupied (#) and four or more seats adjacent to it are also occupied, the seat becomes empty.
+        '#' if adjacent_occupied_count(seats, coords) >= 4 => 'L',
+        _ => *seat,
     }
+}
 
-    fn part_2(&mut self) -> usize {
-        // self.print();
-
-        let mut is_stabilized = false;
-
-        while !is_stabilized {
-            let mut new_seats = self.seats.clone();
-            self.step += 1;
-
-            for (coords, seat) in self.seats.clone() {
-                // If a seat is empty (L) and there are no occupied seats in any of the
-                // visible directions, the seat becomes occupied.
-                if seat == 'L' && self.visible_occupied_count(coords) == 0 {
-                    *new_seats.entry(coords).or_insert('#') = '#';
-                }
-                // If a seat is occupied (#) and five or more seats in any of the visible
-                // directions to it are also occupied, the seat becomes empty.
-                else if seat == '#' && self.visible_occupied_count(coords) >= 5 {
-                    *new_seats.entry(coords).or_insert('L') = 'L';
-                }
-            }
-
-            is_stabilized = new_seats == self.seats;
-            self.seats = new_seats;
-
-            // self.print();
-        }
-
-        self.occupied_seats_count()
+fn visible_rule(seats: &Grid<char>, coords: Coords<i32>, seat: &char) -> char {
+    match seat {
+        // If a seat is empty (L) and there are no occupied seats in any of the
+        // visible directions, the seat becomes occupied.
+        'L' if visible_occupied_count(seats, coords) == 0 => '#',
+        // If a seat is occupied (#) and five or more seats in any of the visible
+        // directions to it are also occupied, the seat becomes empty.
+        '#' if visible_occupied_count(seats, coords) >= 5 => 'L',
+        _ => *seat,
     }
 }
 
+fn render(seats: &Grid<char>) -> Vec<String> {
+    let cells: Vec<char> = seats.iter().map(|(_, seat)| *seat).collect();
+    cells
+        .chunks(seats.width.max(1))
+        .map(|row| row.iter().collect())
+        .collect()
+}
+
 impl Solution for Day11 {
     type A = usize;
     type B = usize;
@@ -165,15 +129,43 @@ impl Solution for Day11 {
     }
 
     fn part_1(&self, input: &str) -> Result<usize, AocError> {
-        let result = GameOfSeats::new(input).part_1();
-
-        Ok(result)
+        GameOfSeats::new(input).part_1()
     }
 
     fn part_2(&self, input: &str) -> Result<usize, AocError> {
-        let result = GameOfSeats::new(input).part_2();
+        GameOfSeats::new(input).part_2()
+    }
+}
+
+impl Visualize for Day11 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "seats" {
+            return Err(unknown_visualization(name));
+        }
+
+        let game = GameOfSeats::new(input);
+        let mut frames = vec![Frame::grid("Round 0", render(&game.seats))];
+
+        let mut automaton = DenseAutomaton::new(game.seats, adjacent_rule);
+        loop {
+            let generation = automaton.step();
+            let occupied = automaton
+                .grid()
+                .iter()
+                .filter(|(_, seat)| **seat == '#')
+                .count();
+
+            frames.push(Frame::grid(
+                format!("Round {}, {occupied} occupied seats", generation.generation),
+                render(automaton.grid()),
+            ));
+
+            if generation.is_stable() {
+                break;
+            }
+        }
 
-        Ok(result)
+        Ok(Box::new(frames.into_iter()))
     }
 }
 
@@ -197,7 +189,7 @@ mod tests {
                  L.LLLLL.LL"
             )
             .part_1(),
-            37
+            Ok(37)
         )
     }
 
@@ -217,7 +209,7 @@ mod tests {
                  L.LLLLL.LL"
             )
             .part_2(),
-            26
+            Ok(26)
         )
     }
 
@@ -263,4 +255,29 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn it_visualizes_the_seating_rounds() {
+        let frames: Vec<_> = Day11
+            .visualize(
+                "seats",
+                "L.LL.LL.LL\n\
+                 LLLLLLL.LL\n\
+                 L.L.L..L..\n\
+                 LLLL.LL.LL\n\
+                 L.LL.LL.LL\n\
+                 L.LLLLL.LL\n\
+                 ..L.L.....\n\
+                 LLLLLLLLLL\n\
+                 L.LLLLLL.L\n\
+                 L.LLLLL.LL",
+            )
+            .unwrap()
+            .collect();
+
+        // The seating stabilizes after five rounds, and the sixth round changes nothing
+        assert_eq!(frames.len(), 7);
+        assert_eq!(frames[1].caption, "Round 1, 71 occupied seats");
+        assert_eq!(frames[6].caption, "Round 6, 37 occupied seats");
+    }
 }
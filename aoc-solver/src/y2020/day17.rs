@@ -1,202 +1,72 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 use crate::solution::{AocError, Solution};
+use crate::utils::automaton::{Automaton, Hypercube, SparseAutomaton};
 
 pub struct Day17;
 
+type Rule = fn(bool, usize) -> bool;
+
+fn conway_cubes(active: bool, adjacent_count: usize) -> bool {
+    // If a cube is active and exactly 2 or 3 of its neighbors are also active,
+    // the cube remains active. Otherwise, the cube becomes inactive.
+    // If a cube is inactive but exactly 3 of its neighbors are active,
+    // the cube becomes active. Otherwise, the cube remains inactive.
+    adjacent_count == 3 || active && adjacent_count == 2
+}
+
 #[derive(Debug, Default)]
 pub struct GameOfCubes {
-    pub cubes: HashMap<(i32, i32, i32, i32), bool>,
-    pub step: u32,
+    pub cubes: HashSet<(i32, i32)>,
 }
 
 impl GameOfCubes {
     #[inline]
     fn new(input: &str) -> GameOfCubes {
-        let cubes: HashMap<(i32, i32, i32, i32), bool> = input
+        let cubes = input
             .lines()
             .enumerate()
-            .flat_map(|(y, xs)| -> HashMap<(i32, i32, i32, i32), bool> {
+            .flat_map(|(y, xs)| {
                 xs.chars()
                     .enumerate()
-                    .map(|(x, state)| ((x as i32, y as i32, 0, 0), state == '#'))
-                    .collect()
+                    .filter(|(_x, state)| *state == '#')
+                    .map(move |(x, _state)| (x as i32, y as i32))
             })
             .collect();
 
-        GameOfCubes { cubes, step: 0 }
-    }
-
-    #[allow(dead_code)]
-    fn print_3d(&self) {
-        println!("Step {:?}:", self.step);
-        let x_min = self.cubes.iter().min_by_key(|cube| cube.0 .0).unwrap().0 .0;
-        let x_max = self.cubes.iter().max_by_key(|cube| cube.0 .0).unwrap().0 .0;
-
-        let y_min = self.cubes.iter().min_by_key(|cube| cube.0 .1).unwrap().0 .1;
-        let y_max = self.cubes.iter().max_by_key(|cube| cube.0 .1).unwrap().0 .1;
-
-        let z_min = self.cubes.iter().min_by_key(|cube| cube.0 .2).unwrap().0 .2;
-        let z_max = self.cubes.iter().max_by_key(|cube| cube.0 .2).unwrap().0 .2;
-
-        for z in z_min..=z_max {
-            println!("z={}", z);
-            for y in y_min..=y_max {
-                for x in x_min..=x_max {
-                    let state = match self.is_active((x, y, z, 0)) {
-                        true => '#',
-                        false => '.',
-                    };
-
-                    print!("{}", state);
-                }
-                println!()
-            }
-            println!()
-        }
-    }
-
-    #[allow(dead_code)]
-    fn print_4d(&self) {
-        println!("Step {:?}:", self.step);
-        let x_min = self.cubes.iter().min_by_key(|cube| cube.0 .0).unwrap().0 .0;
-        let x_max = self.cubes.iter().max_by_key(|cube| cube.0 .0).unwrap().0 .0;
-
-        let y_min = self.cubes.iter().min_by_key(|cube| cube.0 .1).unwrap().0 .1;
-        let y_max = self.cubes.iter().max_by_key(|cube| cube.0 .1).unwrap().0 .1;
-
-        let z_min = self.cubes.iter().min_by_key(|cube| cube.0 .2).unwrap().0 .2;
-        let z_max = self.cubes.iter().max_by_key(|cube| cube.0 .2).unwrap().0 .2;
-
-        let w_min = self.cubes.iter().min_by_key(|cube| cube.0 .3).unwrap().0 .3;
-        let w_max = self.cubes.iter().max_by_key(|cube| cube.0 .3).unwrap().0 .3;
-
-        for w in w_min..=w_max {
-            for z in z_min..=z_max {
-                println!("z={}, w={}", z, w);
-                for y in y_min..=y_max {
-                    for x in x_min..=x_max {
-                        let state = match self.is_active((x, y, z, w)) {
-                            true => '#',
-                            false => '.',
-                        };
-                        print!("{}", state);
-                    }
-                    println!()
-                }
-                println!()
-            }
-        }
+        GameOfCubes { cubes }
     }
 
-    fn active_cubes_count(&self) -> usize {
-        self.cubes.iter().filter(|(_coords, state)| **state).count()
-    }
+    /// Lifts the initial flat slice of active cubes into `N` dimensions.
+    fn automaton<const N: usize>(&self) -> SparseAutomaton<Hypercube<N>, Rule> {
+        let cubes = self.cubes.iter().map(|(x, y)| {
+            let mut coords = [0; N];
+            coords[0] = *x;
+            coords[1] = *y;
+            coords
+        });
 
-    fn is_active(&self, coords: (i32, i32, i32, i32)) -> bool {
-        match self.cubes.get(&coords) {
-            Some(state) => *state,
-            _ => false,
-        }
+        SparseAutomaton::new(Hypercube::new(), cubes, conway_cubes)
     }
 
+    #[cfg(test)]
     fn adjacent_active_count(&self, coords: &(i32, i32, i32, i32)) -> usize {
-        let mut count = 0;
-        for w in [-1, 0, 1].iter() {
-            for z in [-1, 0, 1].iter() {
-                for y in [-1, 0, 1].iter() {
-                    for x in [-1, 0, 1].iter() {
-                        if *x == 0 && *y == 0 && *z == 0 && *w == 0 {
-                            continue;
-                        }
-                        if self.is_active((coords.0 + x, coords.1 + y, coords.2 + z, coords.3 + w))
-                        {
-                            count += 1;
-                        }
-                    }
-                }
-            }
-        }
-        count
+        self.automaton::<4>()
+            .live_neighbours(&[coords.0, coords.1, coords.2, coords.3])
     }
 
-    fn simulate_3d(&mut self, steps: u32) -> usize {
-        // self.print();
-
-        while self.step < steps {
-            let mut new_cubes = self.cubes.clone();
-            self.step += 1;
-
-            // The only meaningful cubes that can turn active are the ones within
-            // distance of max one from now active cubes
-            for (coords, _s) in self.cubes.iter() {
-                for z in [-1, 0, 1].iter() {
-                    for y in [-1, 0, 1].iter() {
-                        for x in [-1, 0, 1].iter() {
-                            let coords = (coords.0 + x, coords.1 + y, coords.2 + z, 0);
-                            let state = self.is_active(coords);
-                            let adjacent_count = self.adjacent_active_count(&coords);
-
-                            if state && adjacent_count != 2 && adjacent_count != 3 {
-                                new_cubes.insert(coords, false);
-                            }
-                            // If a cube is inactive but exactly 3 of its neighbors are active,
-                            // the cube becomes active. Otherwise, the cube remains inactive.
-                            else if !state && adjacent_count == 3 {
-                                new_cubes.insert(coords, true);
-                            }
-                        }
-                    }
-                }
-            }
-
-            self.cubes = new_cubes;
-
-            // self.print();
-        }
-
-        self.active_cubes_count()
+    fn simulate<const N: usize>(&self, steps: u32) -> usize {
+        let mut automaton = self.automaton::<N>();
+        automaton.run(steps);
+        automaton.population()
     }
 
-    fn simulate_4d(&mut self, steps: u32) -> usize {
-        // self.print();
-
-        while self.step < steps {
-            let mut new_cubes = self.cubes.clone();
-            self.step += 1;
-
-            // The only meaningful cubes that can turn active are the ones within
-            // distance of max one from now active cubes
-            for (coords, _s) in self.cubes.iter() {
-                for w in [-1, 0, 1].iter() {
-                    for z in [-1, 0, 1].iter() {
-                        for y in [-1, 0, 1].iter() {
-                            for x in [-1, 0, 1].iter() {
-                                let coords =
-                                    (coords.0 + x, coords.1 + y, coords.2 + z, coords.3 + w);
-                                let state = self.is_active(coords);
-                                let adjacent_count = self.adjacent_active_count(&coords);
-
-                                if state && adjacent_count != 2 && adjacent_count != 3 {
-                                    new_cubes.insert(coords, false);
-                                }
-                                // If a cube is inactive but exactly 3 of its neighbors are active,
-                                // the cube becomes active. Otherwise, the cube remains inactive.
-                                else if !state && adjacent_count == 3 {
-                                    new_cubes.insert(coords, true);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            self.cubes = new_cubes;
-
-            // self.print();
-        }
+    fn simulate_3d(&self, steps: u32) -> usize {
+        self.simulate::<3>(steps)
+    }
 
-        self.active_cubes_count()
+    fn simulate_4d(&self, steps: u32) -> usize {
+        self.simulate::<4>(steps)
     }
 }
 
@@ -1,84 +1,296 @@
-use std::collections::VecDeque;
+use std::collections::HashMap;
+use std::fmt;
 
 use crate::solution::{AocError, Solution};
 
 pub struct Day18;
 
-fn shunting_yard(input: &str, is_advanced: bool) -> VecDeque<char> {
-    let mut output_queue: VecDeque<char> = VecDeque::new();
-    let mut operator_stack: Vec<char> = Vec::new();
-
-    for token in input.chars().filter(|c| !c.is_whitespace()) {
-        if token.is_numeric() {
-            output_queue.push_back(token);
-        } else if token == '+' || token == '*' {
-            if is_advanced {
-                // In advanced mode addition is evaluated before multiplication
-                // while there is an operator at the top of the operator stack...
-                while let Some(operator) = operator_stack.last() {
-                    // ...AND the operator at the top of the operator stack is not a left parenthesis
-                    if *operator == '(' {
-                        break;
-                    }
-                    // ...AND the operator at the top of the operator stack has equal precedence OR
-                    // ...the operator at the top of the operator stack has greater precedence
-                    else if (token == *operator) || (token == '*' && *operator == '+') {
-                        output_queue.push_back(operator_stack.pop().unwrap());
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    fn apply(self, a: i64, b: i64) -> Result<i64, AocError> {
+        let result = match self {
+            Operator::Add => a.checked_add(b),
+            Operator::Sub => a.checked_sub(b),
+            Operator::Mul => a.checked_mul(b),
+            Operator::Div if b == 0 => return Err(AocError::logic("Division by zero")),
+            Operator::Div => a.checked_div(b),
+        };
+
+        result.ok_or(AocError::logic(format!("Overflow at {a} {self} {b}")))
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Operator::Add => '+',
+            Operator::Sub => '-',
+            Operator::Mul => '*',
+            Operator::Div => '/',
+        };
+
+        write!(f, "{symbol}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Number(i64),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{number}"),
+            Token::Operator(operator) => write!(f, "{operator}"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// Precedence and associativity of the operators, higher levels are evaluated first.
+/// Operators missing from the table are rejected.
+#[derive(Debug, Clone, Default)]
+pub struct Precedence {
+    levels: HashMap<Operator, (u8, Associativity)>,
+}
+
+impl Precedence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, operator: Operator, level: u8, associativity: Associativity) -> Self {
+        self.levels.insert(operator, (level, associativity));
+        self
+    }
+
+    /// Every operator has the same precedence, as in the homework of part 1
+    pub fn flat() -> Self {
+        Self::new()
+            .with(Operator::Add, 0, Associativity::Left)
+            .with(Operator::Sub, 0, Associativity::Left)
+            .with(Operator::Mul, 0, Associativity::Left)
+            .with(Operator::Div, 0, Associativity::Left)
+    }
+
+    /// Addition and subtraction are evaluated before multiplication and division, as in part 2
+    pub fn advanced() -> Self {
+        Self::new()
+            .with(Operator::Add, 1, Associativity::Left)
+            .with(Operator::Sub, 1, Associativity::Left)
+            .with(Operator::Mul, 0, Associativity::Left)
+            .with(Operator::Div, 0, Associativity::Left)
+    }
+
+    /// The usual rules of arithmetic
+    pub fn conventional() -> Self {
+        Self::new()
+            .with(Operator::Add, 0, Associativity::Left)
+            .with(Operator::Sub, 0, Associativity::Left)
+            .with(Operator::Mul, 1, Associativity::Left)
+            .with(Operator::Div, 1, Associativity::Left)
+    }
+
+    fn get(&self, operator: Operator) -> Result<(u8, Associativity), AocError> {
+        self.levels
+            .get(&operator)
+            .copied()
+            .ok_or(AocError::logic(format!("Unsupported operator {operator}")))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+impl Expr {
+    pub fn evaluate(&self) -> Result<i64, AocError> {
+        match self {
+            Expr::Number(number) => Ok(*number),
+            Expr::Binary(a, operator, b) => operator.apply(a.evaluate()?, b.evaluate()?),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter<'_>, expr: &Expr| match expr {
+            Expr::Number(number) => write!(f, "{number}"),
+            Expr::Binary(..) => write!(f, "({expr})"),
+        };
+
+        match self {
+            Expr::Number(number) => write!(f, "{number}"),
+            Expr::Binary(a, operator, b) => {
+                operand(f, a)?;
+                write!(f, " {operator} ")?;
+                operand(f, b)
+            }
+        }
+    }
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, AocError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '0'..='9' => {
+                let mut number = c.to_string();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                    number.push(digit);
+                }
+
+                Token::Number(
+                    number
+                        .parse()
+                        .map_err(|err| AocError::parse(&number, err))?,
+                )
+            }
+            '+' => Token::Operator(Operator::Add),
+            '-' => Token::Operator(Operator::Sub),
+            '*' => Token::Operator(Operator::Mul),
+            '/' => Token::Operator(Operator::Div),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_whitespace() => continue,
+            c => {
+                return Err(AocError::parse(
+                    input,
+                    format!("Unexpected character {c:?}"),
+                ))
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Converts the tokens to reverse polish notation
+pub fn shunting_yard(tokens: &[Token], precedence: &Precedence) -> Result<Vec<Token>, AocError> {
+    let mut output_queue: Vec<Token> = Vec::new();
+    let mut operator_stack: Vec<Token> = Vec::new();
+
+    // Operands and operators have to alternate, with parentheses only around operands
+    let mut expect_operand = true;
+
+    for token in tokens {
+        match (token, expect_operand) {
+            (Token::Number(_) | Token::Open, false) => {
+                return Err(AocError::logic(format!("Missing operator before {token}")))
+            }
+            (Token::Operator(_) | Token::Close, true) => {
+                return Err(AocError::logic(format!("Missing operand before {token}")))
+            }
+            _ => expect_operand = matches!(token, Token::Operator(_) | Token::Open),
+        }
+
+        match token {
+            Token::Number(_) => output_queue.push(*token),
+            Token::Operator(operator) => {
+                let (level, associativity) = precedence.get(*operator)?;
+
+                // While there is an operator at the top of the operator stack that has greater
+                // precedence, or equal precedence and the token is left associative, pop it
+                while let Some(Token::Operator(top)) = operator_stack.last() {
+                    let (top_level, _) = precedence.get(*top)?;
+
+                    if top_level > level
+                        || (top_level == level && associativity == Associativity::Left)
+                    {
+                        output_queue.extend(operator_stack.pop());
                     } else {
                         break;
                     }
                 }
-            } else {
-                // In basic mode all operators have the same precedence here
-                while operator_stack.last() != Some(&'(') && operator_stack.last().is_some() {
-                    output_queue.push_back(operator_stack.pop().unwrap());
-                }
-            }
-            operator_stack.push(token);
-        } else if token == '(' {
-            operator_stack.push(token);
-        } else if token == ')' {
-            while operator_stack.last() != Some(&'(') {
-                match operator_stack.pop() {
-                    Some(operator) => output_queue.push_back(operator),
-                    None => panic!("mismatched parenthesis"),
-                };
-            }
 
-            if operator_stack.last() == Some(&'(') {
-                operator_stack.pop();
+                operator_stack.push(*token);
             }
+            Token::Open => operator_stack.push(*token),
+            Token::Close => loop {
+                match operator_stack.pop() {
+                    Some(Token::Open) => break,
+                    Some(operator) => output_queue.push(operator),
+                    None => return Err(AocError::logic("Unbalanced closing parenthesis")),
+                }
+            },
         }
     }
 
     while let Some(operator) = operator_stack.pop() {
-        output_queue.push_back(operator);
+        if operator == Token::Open {
+            return Err(AocError::logic("Unbalanced opening parenthesis"));
+        }
+
+        output_queue.push(operator);
     }
 
-    output_queue
+    Ok(output_queue)
 }
 
-fn evaluate_rpn(mut rpn: VecDeque<char>) -> i64 {
-    let mut stack: Vec<i64> = Vec::new();
+fn build_ast(rpn: &[Token]) -> Result<Expr, AocError> {
+    let mut stack: Vec<Expr> = Vec::new();
 
-    while let Some(token) = rpn.pop_front() {
-        if token.is_numeric() {
-            stack.push(token.to_digit(10).unwrap() as i64);
-        } else {
-            let operand_a = stack.pop().unwrap();
-            let operand_b = stack.pop().unwrap();
+    for token in rpn {
+        match token {
+            Token::Number(number) => stack.push(Expr::Number(*number)),
+            Token::Operator(operator) => {
+                let missing = || AocError::logic(format!("Missing operand for {operator}"));
+                let b = stack.pop().ok_or_else(missing)?;
+                let a = stack.pop().ok_or_else(missing)?;
 
-            match token {
-                '+' => stack.push(operand_a + operand_b),
-                '*' => stack.push(operand_a * operand_b),
-                operator => panic!("Unknown operator {:?}", operator),
-            };
+                stack.push(Expr::Binary(Box::new(a), *operator, Box::new(b)));
+            }
+            Token::Open | Token::Close => {
+                return Err(AocError::logic("Parenthesis in reverse polish notation"))
+            }
         }
     }
 
-    assert_eq!(stack.len(), 1);
+    match (stack.pop(), stack.is_empty()) {
+        (Some(expr), true) => Ok(expr),
+        (None, _) => Err(AocError::logic("Empty expression")),
+        (Some(_), false) => Err(AocError::logic("Missing operator")),
+    }
+}
+
+pub fn parse(input: &str, precedence: &Precedence) -> Result<Expr, AocError> {
+    build_ast(&shunting_yard(&tokenize(input)?, precedence)?)
+}
+
+pub fn evaluate(input: &str, precedence: &Precedence) -> Result<i64, AocError> {
+    parse(input, precedence)?.evaluate()
+}
 
-    stack.pop().unwrap()
+fn sum(input: &str, precedence: &Precedence) -> Result<i64, AocError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| evaluate(line, precedence))
+        .sum()
 }
 
 impl Solution for Day18 {
@@ -90,21 +302,11 @@ impl Solution for Day18 {
     }
 
     fn part_1(&self, input: &str) -> Result<i64, AocError> {
-        let sum = input
-            .lines()
-            .map(|line| evaluate_rpn(shunting_yard(line, false)))
-            .sum();
-
-        Ok(sum)
+        sum(input, &Precedence::flat())
     }
 
     fn part_2(&self, input: &str) -> Result<i64, AocError> {
-        let sum = input
-            .lines()
-            .map(|line| evaluate_rpn(shunting_yard(line, true)))
-            .sum();
-
-        Ok(sum)
+        sum(input, &Precedence::advanced())
     }
 }
 
@@ -112,28 +314,105 @@ impl Solution for Day18 {
 mod tests {
     use super::*;
 
+    fn rpn(input: &str, precedence: &Precedence) -> String {
+        shunting_yard(&tokenize(input).unwrap(), precedence)
+            .unwrap()
+            .iter()
+            .map(|token| token.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     #[test]
     fn it_parses_rpn_basic_mode() {
         assert_eq!(
-            shunting_yard("1 + 2 * 3 + 4 * 5 + 6", false),
-            vec!['1', '2', '+', '3', '*', '4', '+', '5', '*', '6', '+']
+            rpn("1 + 2 * 3 + 4 * 5 + 6", &Precedence::flat()),
+            "1 2 + 3 * 4 + 5 * 6 +"
         );
         assert_eq!(
-            shunting_yard("1 + ((2 + 3) + 4 + (5 + 6))", false),
-            vec!['1', '2', '3', '+', '4', '+', '5', '6', '+', '+', '+']
+            rpn("1 + ((2 + 3) + 4 + (5 + 6))", &Precedence::flat()),
+            "1 2 3 + 4 + 5 6 + + +"
         );
+        assert_eq!(rpn("1 + (2 * 3)", &Precedence::flat()), "1 2 3 * +");
+    }
+
+    #[test]
+    fn it_parses_rpn_advanced_mode() {
         assert_eq!(
-            shunting_yard("1 + (2 * 3)", false),
-            vec!['1', '2', '3', '*', '+']
+            rpn("1 * ((2 * 3) + (4 * 5 + 6))", &Precedence::advanced()),
+            "1 2 3 * 4 5 6 + * + *"
         );
     }
 
     #[test]
-    fn it_parses_rpn_advanced_mode() {
+    fn it_tokenizes_multi_digit_numbers() {
+        assert_eq!(
+            tokenize("12*(340 -5)/6").unwrap(),
+            vec![
+                Token::Number(12),
+                Token::Operator(Operator::Mul),
+                Token::Open,
+                Token::Number(340),
+                Token::Operator(Operator::Sub),
+                Token::Number(5),
+                Token::Close,
+                Token::Operator(Operator::Div),
+                Token::Number(6),
+            ]
+        );
+        assert!(tokenize("1 % 2").is_err());
+    }
+
+    #[test]
+    fn it_evaluates_with_configurable_precedence() {
+        let conventional = Precedence::conventional();
+        assert_eq!(evaluate("10 - 4 - 3", &conventional), Ok(3));
+        assert_eq!(evaluate("100 / 10 / 5", &conventional), Ok(2));
+        assert_eq!(evaluate("2 + 30 * 4 - 8 / 2", &conventional), Ok(118));
+
+        let right = Precedence::conventional()
+            .with(Operator::Sub, 0, Associativity::Right)
+            .with(Operator::Div, 1, Associativity::Right);
+        assert_eq!(evaluate("10 - 4 - 3", &right), Ok(9));
+        assert_eq!(evaluate("100 / 10 / 5", &right), Ok(50));
+
+        let partial = Precedence::new().with(Operator::Add, 0, Associativity::Left);
+        assert!(evaluate("1 + 2 * 3", &partial).is_err());
+    }
+
+    #[test]
+    fn it_prints_the_ast() {
+        let expr = parse("1 + 2 * 3 + 4", &Precedence::advanced()).unwrap();
+        assert_eq!(expr.to_string(), "(1 + 2) * (3 + 4)");
+
+        let expr = parse("1 + 2 * 3 + 4", &Precedence::conventional()).unwrap();
+        assert_eq!(expr.to_string(), "(1 + (2 * 3)) + 4");
+        assert_eq!(expr.evaluate(), Ok(11));
+
+        assert_eq!(parse("(((7)))", &Precedence::flat()), Ok(Expr::Number(7)));
+    }
+
+    #[test]
+    fn it_reports_invalid_expressions() {
+        let flat = Precedence::flat();
+        assert!(evaluate("(1 + 2", &flat).is_err());
+        assert!(evaluate("1 + 2)", &flat).is_err());
+        assert!(evaluate("1 +", &flat).is_err());
+        assert!(evaluate("1 2", &flat).is_err());
+        assert!(evaluate("()", &flat).is_err());
+        assert!(evaluate("1 / (2 - 2)", &flat).is_err());
+        assert_eq!(
+            evaluate("1 2 +", &flat),
+            Err(AocError::logic("Missing operator before 2"))
+        );
         assert_eq!(
-            shunting_yard("1 * ((2 * 3) + (4 * 5 + 6))", true),
-            vec!['1', '2', '3', '*', '4', '5', '6', '+', '*', '+', '*']
+            evaluate("+ 1 2", &flat),
+            Err(AocError::logic("Missing operand before +"))
         );
+        assert!(evaluate("(1 2) +", &flat).is_err());
+        assert!(evaluate("1 + 2 3 *", &flat).is_err());
+        assert!(evaluate("1 + (* 2)", &flat).is_err());
+        assert!(evaluate("(1 +) * 2", &flat).is_err());
     }
 
     #[test]
@@ -1,192 +1,20 @@
-use std::collections::HashSet;
-use std::collections::VecDeque;
-
 use crate::solution::{AocError, Solution};
-
-#[derive(Debug, Clone)]
-struct TerminalRule {
-    pub lhs: u32,
-    pub rhs: char,
-}
-
-#[derive(Debug, Clone)]
-struct ProductionRule {
-    pub lhs: u32,
-    pub non_terminal_a: u32,
-    pub non_terminal_b: u32,
-}
+use crate::utils::grammar::{Grammar, Symbol};
 
 pub struct Day19;
 
-// Adapted from https://www.geeksforgeeks.org/converting-context-free-grammar-chomsky-normal-form/
-// A context free grammar (CFG) is in Chomsky Normal Form (CNF) if all production rules satisfy one of the following conditions:
-//  - A non-terminal generating a terminal (e.g.; X->x)
-//  - A non-terminal generating two non-terminals (e.g.; X->YZ)
-//  - Start symbol generating ε. (e.g.; S-> ε)
-fn convert_to_cnf(input: &str) -> (Vec<TerminalRule>, Vec<ProductionRule>) {
-    // At our example the only terminal rules are the characters like "a".
-    let mut terminal_rules: Vec<TerminalRule> = input
-        .lines()
-        .filter(|rule| rule.contains('"'))
-        .map(|rule| {
-            let mut iter = rule.split(": ");
-            let lhs = iter.next().unwrap().parse().unwrap();
-            let rhs = iter.next().unwrap().chars().nth(1).unwrap();
-
-            TerminalRule { lhs, rhs }
-        })
-        .collect();
-
-    let mut max_rule_id = input
-        .lines()
-        .map(|rule| rule.split(": ").next().unwrap().parse::<u32>().unwrap())
-        .max()
-        .unwrap();
-
-    let mut useless_unit_rules: Vec<(u32, u32)> = vec![];
-
-    let mut production_rules: Vec<ProductionRule> = input
-        .lines()
-        .filter(|rule| !rule.contains('"'))
-        .flat_map(|rule| {
-            let mut lhs_rhs = rule.split(": ");
-            let lhs = lhs_rhs.next().unwrap().parse().unwrap();
-            let rhs = lhs_rhs.next().unwrap();
-
-            rhs.split(" | ")
-                .flat_map(|production| {
-                    let mut p: VecDeque<u32> = production
-                        .split_whitespace()
-                        .map(|p| p.parse::<u32>().unwrap())
-                        .collect();
-
-                    // This is an useless unit production rule. Deal with these later
-                    if p.len() == 1 {
-                        useless_unit_rules.push((lhs, p[0]));
-                        return vec![];
-                    }
-
-                    let mut rules: Vec<ProductionRule> = vec![];
-
-                    // Eliminate RHS with more than two non-terminals.
-                    // e.g,; production rule X->XYZ can be decomposed as:
-                    // X->PZ
-                    // P->XY
-                    while p.len() > 2 {
-                        max_rule_id += 1;
-
-                        let a = p.pop_front().unwrap();
-                        let b = p.pop_front().unwrap();
-                        p.push_front(max_rule_id);
-
-                        rules.push(ProductionRule {
-                            lhs: max_rule_id,
-                            non_terminal_a: a,
-                            non_terminal_b: b,
-                        });
-                    }
-
-                    assert_eq!(p.len(), 2);
-
-                    rules.push(ProductionRule {
-                        lhs,
-                        non_terminal_a: p[0],
-                        non_terminal_b: p[1],
-                    });
+fn parse(input: &str) -> Result<(Grammar, &str), AocError> {
+    let (rules, messages) = input
+        .split_once("\n\n")
+        .ok_or(AocError::parse(input, "Missing messages"))?;
 
-                    rules
-                })
-                .collect::<Vec<ProductionRule>>()
-        })
-        .collect();
-
-    // We need to eliminate unit productions like 8: 42
-    for (lhs, rhs) in useless_unit_rules {
-        // Find an existing rule to replace the right hand sides of the unit rules we
-        // skipped earlier. In the example input we have useless rule
-        //  8: 42
-        // and a valid rules
-        //  42: 40 69 | 127 5
-        // and we want to replace the rule 8 with
-        //  8: 40 69
-        //  8: 127 5
-        let existing_production: Vec<ProductionRule> = production_rules
-            .iter()
-            .filter(|rule| rule.lhs == rhs)
-            .cloned()
-            .collect();
-
-        for rule in existing_production.iter() {
-            production_rules.push(ProductionRule {
-                lhs,
-                non_terminal_a: rule.non_terminal_a,
-                non_terminal_b: rule.non_terminal_b,
-            });
-        }
-
-        // We also need to do the same for terminal rules to handle situations like
-        //  93: 127 | 40
-        //  40: "a"
-        //  127: "b"
-        let existing_terminal: Vec<TerminalRule> = terminal_rules
-            .iter()
-            .filter(|rule| rule.lhs == rhs)
-            .cloned()
-            .collect();
-
-        for rule in existing_terminal.iter() {
-            terminal_rules.push(TerminalRule { lhs, rhs: rule.rhs });
-        }
-    }
-
-    (terminal_rules, production_rules)
+    Ok((Grammar::parse_rules(rules)?, messages))
 }
 
-fn cyk(w: &str, terminal_rules: &[TerminalRule], production_rules: &[ProductionRule]) -> bool {
-    // Adapted from https://www.geeksforgeeks.org/cyk-algorithm-for-context-free-grammar/
-    let n = w.len();
-
-    let mut dp: Vec<Vec<HashSet<u32>>> = vec![vec![HashSet::new(); production_rules.len()]; n];
-
-    for (s, c) in w.chars().enumerate() {
-        for terminal_rule in terminal_rules.iter() {
-            if terminal_rule.rhs == c {
-                dp[0][s].insert(terminal_rule.lhs);
-            }
-        }
-    }
+fn count_valid_messages(grammar: &Grammar, messages: &str) -> Result<usize, AocError> {
+    let cnf = grammar.to_cnf()?;
 
-    // This should run for O(|G|*n^3)
-    for i in 1..n {
-        for j in 0..(n - i) {
-            for k in 0..i {
-                for production_rule in production_rules.iter() {
-                    if dp[k][j].contains(&production_rule.non_terminal_a)
-                        && dp[i - k - 1][j + k + 1].contains(&production_rule.non_terminal_b)
-                    {
-                        dp[i][j].insert(production_rule.lhs);
-                    }
-                }
-            }
-        }
-    }
-
-    dp[n - 1][0].contains(&0)
-}
-
-fn count_valid_grammar(input: &str) -> usize {
-    let mut input_chunks = input.split("\n\n");
-
-    // Convert the input to Chomsky Normal Form (CNF)
-    let (terminal_rules, production_rules) = convert_to_cnf(input_chunks.next().unwrap());
-
-    // Check if grammar validates with CYK Algorithm
-    input_chunks
-        .next()
-        .unwrap()
-        .lines()
-        .filter(|line| cyk(line, &terminal_rules, &production_rules))
-        .count()
+    Ok(messages.lines().filter(|line| cnf.matches(line)).count())
 }
 
 impl Solution for Day19 {
@@ -198,19 +26,23 @@ impl Solution for Day19 {
     }
 
     fn part_1(&self, input: &str) -> Result<usize, AocError> {
-        let count = count_valid_grammar(input);
+        let (grammar, messages) = parse(input)?;
 
-        Ok(count)
+        count_valid_messages(&grammar, messages)
     }
 
     fn part_2(&self, input: &str) -> Result<usize, AocError> {
-        // Replace rules 8: 42 and 11: 42 31 with the following:
-        let mut i = input.replace("8: 42", "8: 42 | 42 8");
-        i = i.replace("11: 42 31", "11: 42 31 | 42 11 31");
-
-        let count = count_valid_grammar(i.as_str());
+        let (mut grammar, messages) = parse(input)?;
+
+        // Replace rules 8: 42 and 11: 42 31 with 8: 42 | 42 8 and 11: 42 31 | 42 11 31
+        let [r8, r11, r31, r42] = ["8", "11", "31", "42"].map(Symbol::non_terminal);
+        grammar.set_rule("8", vec![vec![r42.clone()], vec![r42.clone(), r8]]);
+        grammar.set_rule(
+            "11",
+            vec![vec![r42.clone(), r31.clone()], vec![r42, r11, r31]],
+        );
 
-        Ok(count)
+        count_valid_messages(&grammar, messages)
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::solution::{Solution, Solver};
+use crate::solution::{DayInfo, Part, Solution, Solver};
 
 pub mod day01;
 pub mod day02;
@@ -26,36 +26,64 @@ pub mod day23;
 pub mod day24;
 pub mod day25;
 
+const DAYS: &[DayInfo] = &[
+    DayInfo::new(1),
+    DayInfo::new(2),
+    DayInfo::new(3),
+    DayInfo::new(4),
+    DayInfo::new(5),
+    DayInfo::new(6),
+    DayInfo::new(7),
+    DayInfo::new(8),
+    DayInfo::new(9),
+    DayInfo::new(10),
+    DayInfo::with_visualizations(11, &["seats"]),
+    DayInfo::new(12),
+    DayInfo::new(13),
+    DayInfo::new(14),
+    DayInfo::new(15),
+    DayInfo::new(16),
+    DayInfo::new(17),
+    DayInfo::new(18),
+    DayInfo::new(19),
+    DayInfo::new(20),
+    DayInfo::new(21),
+    DayInfo::new(22),
+    DayInfo::new(23),
+    DayInfo::new(24),
+    DayInfo::new(25),
+];
+
 pub struct Y2020;
 
 impl Solver for Y2020 {
-    fn run_solution(day: u8, input: Option<String>) -> Vec<String> {
+    fn run_parts(day: u8, input: Option<String>, parts: &[Part]) -> Vec<String> {
         match day {
-            1 => day01::Day01.run(input, 1, 2020),
-            2 => day02::Day02.run(input, 2, 2020),
-            3 => day03::Day03.run(input, 3, 2020),
-            4 => day04::Day04.run(input, 4, 2020),
-            5 => day05::Day05.run(input, 5, 2020),
-            6 => day06::Day06.run(input, 6, 2020),
-            7 => day07::Day07.run(input, 7, 2020),
-            8 => day08::Day08.run(input, 8, 2020),
-            9 => day09::Day09.run(input, 9, 2020),
-            10 => day10::Day10.run(input, 10, 2020),
-            11 => day11::Day11.run(input, 11, 2020),
-            12 => day12::Day12.run(input, 12, 2020),
-            13 => day13::Day13.run(input, 13, 2020),
-            14 => day14::Day14.run(input, 14, 2020),
-            15 => day15::Day15.run(input, 15, 2020),
-            16 => day16::Day16.run(input, 16, 2020),
-            17 => day17::Day17.run(input, 17, 2020),
-            18 => day18::Day18.run(input, 18, 2020),
-            19 => day19::Day19.run(input, 19, 2020),
-            20 => day20::Day20.run(input, 20, 2020),
-            21 => day21::Day21.run(input, 21, 2020),
-            22 => day22::Day22.run(input, 22, 2020),
-            23 => day23::Day23.run(input, 23, 2020),
-            24 => day24::Day24.run(input, 24, 2020),
-            25 => day25::Day25.run(input, 25, 2020),
+            1 => day01::Day01.run_parts(input, 1, 2020, parts),
+            2 => day02::Day02.run_parts(input, 2, 2020, parts),
+            3 => day03::Day03.run_parts(input, 3, 2020, parts),
+            4 => day04::Day04.run_parts(input, 4, 2020, parts),
+            5 => day05::Day05.run_parts(input, 5, 2020, parts),
+            6 => day06::Day06.run_parts(input, 6, 2020, parts),
+            7 => day07::Day07.run_parts(input, 7, 2020, parts),
+            8 => day08::Day08.run_parts(input, 8, 2020, parts),
+            9 => day09::Day09.run_parts(input, 9, 2020, parts),
+            10 => day10::Day10.run_parts(input, 10, 2020, parts),
+            11 => day11::Day11.run_parts(input, 11, 2020, parts),
+            12 => day12::Day12.run_parts(input, 12, 2020, parts),
+            13 => day13::Day13.run_parts(input, 13, 2020, parts),
+            14 => day14::Day14.run_parts(input, 14, 2020, parts),
+            15 => day15::Day15.run_parts(input, 15, 2020, parts),
+            16 => day16::Day16.run_parts(input, 16, 2020, parts),
+            17 => day17::Day17.run_parts(input, 17, 2020, parts),
+            18 => day18::Day18.run_parts(input, 18, 2020, parts),
+            19 => day19::Day19.run_parts(input, 19, 2020, parts),
+            20 => day20::Day20.run_parts(input, 20, 2020, parts),
+            21 => day21::Day21.run_parts(input, 21, 2020, parts),
+            22 => day22::Day22.run_parts(input, 22, 2020, parts),
+            23 => day23::Day23.run_parts(input, 23, 2020, parts),
+            24 => day24::Day24.run_parts(input, 24, 2020, parts),
+            25 => day25::Day25.run_parts(input, 25, 2020, parts),
             _ => vec![String::from("Solution not implemented (yet?)")],
         }
     }
@@ -101,4 +129,8 @@ impl Solver for Y2020 {
             _ => unimplemented!(),
         }
     }
+
+    fn days() -> &'static [DayInfo] {
+        DAYS
+    }
 }
@@ -1,7 +1,6 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-
 use crate::solution::{AocError, Solution};
+use crate::utils::search::{self, Path, SearchEvent};
+use crate::visualization::{search_frames, unknown_visualization, Frames, Visualize};
 
 const NEIGHBOUR_OFFSETS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
 
@@ -46,77 +45,39 @@ fn parse_repeating(input: &str, repeats: usize) -> Vec<Vec<u32>> {
         .collect()
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct Search {
-    risk_level: u32,
-    coords: (usize, usize), // (x, y)
-}
-
-impl Ord for Search {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .risk_level
-            .cmp(&self.risk_level)
-            .then_with(|| self.coords.0.cmp(&other.coords.0))
-            .then_with(|| self.coords.1.cmp(&other.coords.1))
-    }
-}
-
-impl PartialOrd for Search {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-fn dijkstra(grid: Vec<Vec<u32>>, source: (usize, usize), target: (usize, usize)) -> Option<u32> {
-    let mut dist: Vec<Vec<u32>> = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
-    let mut heap: BinaryHeap<Search> = BinaryHeap::new();
-
+fn dijkstra<F>(
+    grid: &[Vec<u32>],
+    source: (usize, usize),
+    target: (usize, usize),
+    on_event: F,
+) -> Option<Path<(usize, usize)>>
+where
+    F: FnMut(SearchEvent<&(usize, usize)>),
+{
     let width = grid[0].len() as i32;
     let height = grid.len() as i32;
 
-    dist[source.1][source.0] = 0;
-    heap.push(Search {
-        risk_level: 0,
-        coords: source,
-    });
-
-    while let Some(Search { coords, risk_level }) = heap.pop() {
-        if coords == target {
-            return Some(dist[coords.1][coords.0]);
-        }
-
-        // we've already found a better way
-        if risk_level > dist[coords.1][coords.0] {
-            continue;
-        }
-
-        for offset in NEIGHBOUR_OFFSETS {
+    let neighbours = |&coords: &(usize, usize), _| {
+        NEIGHBOUR_OFFSETS.iter().filter_map(move |offset| {
             let c: (i32, i32) = (coords.0 as i32 + offset.0, coords.1 as i32 + offset.1);
             if c.0 >= 0 && c.1 >= 0 && c.0 < width && c.1 < height {
                 let neighbour = (c.0 as usize, c.1 as usize);
-                let next = Search {
-                    risk_level: risk_level + grid[neighbour.1][neighbour.0],
-                    coords: neighbour,
-                };
-
-                if next.risk_level < dist[neighbour.1][neighbour.0] {
-                    heap.push(next);
-                    dist[neighbour.1][neighbour.0] = next.risk_level;
-                }
+                Some((neighbour, grid[neighbour.1][neighbour.0]))
+            } else {
+                None
             }
-        }
-    }
+        })
+    };
 
-    None
+    search::dijkstra([source], neighbours, |coords| *coords == target, on_event)
 }
 
 fn solve(grid: Vec<Vec<u32>>) -> Result<u32, AocError> {
     let source = (0, 0);
     let target = (grid[0].len() - 1, grid.len() - 1);
 
-    if let Some(risk_level) = dijkstra(grid, source, target) {
-        return Ok(risk_level);
+    if let Some(path) = dijkstra(&grid, source, target, |_| {}) {
+        return Ok(path.cost);
     }
 
     Err(AocError::logic("No path found!"))
@@ -141,6 +102,26 @@ impl Solution for Day15 {
     }
 }
 
+impl Visualize for Day15 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "chiton" {
+            return Err(unknown_visualization(name));
+        }
+
+        let grid = parse_repeating(input, 1);
+        let target = (grid[0].len() - 1, grid.len() - 1);
+        let mut events = Vec::new();
+
+        let path = dijkstra(&grid, (0, 0), target, |event| {
+            events.push(event.map(|coords| *coords))
+        });
+
+        let tiles = input.lines().map(|line| line.chars().collect()).collect();
+
+        Ok(search_frames(tiles, events, path))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,4 +249,20 @@ mod tests {
 
         assert_eq!(solve(grid), Ok(315));
     }
+
+    #[test]
+    fn it_visualizes_the_safest_path() {
+        let frames: Vec<_> = Day15
+            .visualize("chiton", "116\n138\n213")
+            .unwrap()
+            .collect();
+
+        assert_eq!(
+            frames.last(),
+            Some(&crate::visualization::Frame::grid(
+                "Lowest cost: 7",
+                vec!["#16".into(), "#38".into(), "###".into()]
+            ))
+        );
+    }
 }
@@ -6,15 +6,30 @@ use nom::{
     IResult,
 };
 
-use std::num::ParseIntError;
+use std::fmt::{self, Write};
+use std::str::FromStr;
 
 use crate::solution::{AocError, Solution};
 
 pub struct Day16;
 
-#[derive(Debug, PartialEq, Eq)]
-enum Packet {
-    LiteraValue {
+const LITERAL_VALUE: u8 = 4;
+
+/// Names of the operators in the expression syntax, indexed by the packet type
+const OPERATORS: [&str; 8] = ["sum", "product", "min", "max", "", "gt", "lt", "eq"];
+
+/// Comparisons take exactly two values, and the other operators at least one
+fn is_valid_arity(packet_type: u8, sub_packets: usize) -> bool {
+    match packet_type {
+        0..=3 => sub_packets >= 1,
+        5..=7 => sub_packets == 2,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    LiteralValue {
         packet_version: u8,
         packet_type: u8,
         value: u64,
@@ -29,12 +44,192 @@ enum Packet {
 
 type BitStream<'a> = (&'a [u8], usize);
 
-pub fn decode_hex_input(input: &str) -> Result<Vec<u8>, ParseIntError> {
-    let line = input.lines().next().unwrap();
+impl Packet {
+    /// Encodes the packet as a hexadecimal transmission, padded with zeroes to full bytes.
+    pub fn to_hex(&self) -> Result<String, AocError> {
+        let mut bits = Vec::new();
+        self.write_bits(&mut bits)?;
+
+        bits.resize(bits.len().div_ceil(8) * 8, false);
+
+        let mut hex = String::new();
+        for byte in bits.chunks(8) {
+            let byte = byte.iter().fold(0u8, |acc, bit| acc << 1 | *bit as u8);
+            write!(hex, "{byte:02X}").map_err(|err| AocError::logic(err.to_string()))?;
+        }
+
+        Ok(hex)
+    }
+
+    fn write_bits(&self, bits: &mut Vec<bool>) -> Result<(), AocError> {
+        let push = |bits: &mut Vec<bool>, value: u64, width: usize| {
+            bits.extend((0..width).rev().map(|bit| value >> bit & 1 == 1));
+        };
+
+        match self {
+            Packet::LiteralValue {
+                packet_version,
+                packet_type,
+                value,
+            } => {
+                if *packet_version > 7 || *packet_type != LITERAL_VALUE {
+                    return Err(AocError::logic(format!("Invalid literal value {self:?}")));
+                }
+
+                push(bits, *packet_version as u64, 3);
+                push(bits, *packet_type as u64, 3);
+
+                // Groups of four bits, each but the last one prefixed with a 1 bit
+                let groups = ((64 - value.leading_zeros() as usize).div_ceil(4)).max(1);
+                for group in (0..groups).rev() {
+                    push(bits, (group > 0) as u64, 1);
+                    push(bits, value >> (group * 4) & 0b1111, 4);
+                }
+            }
+            Packet::Operator {
+                packet_version,
+                packet_type,
+                len_type,
+                sub_packets,
+            } => {
+                if *packet_version > 7 || !is_valid_arity(*packet_type, sub_packets.len()) {
+                    return Err(AocError::logic(format!("Invalid operator {self:?}")));
+                }
+
+                push(bits, *packet_version as u64, 3);
+                push(bits, *packet_type as u64, 3);
+
+                let mut sub_bits = Vec::new();
+                for sub_packet in sub_packets {
+                    sub_packet.write_bits(&mut sub_bits)?;
+                }
+
+                match len_type {
+                    0 if sub_bits.len() < 1 << 15 => {
+                        push(bits, 0, 1);
+                        push(bits, sub_bits.len() as u64, 15);
+                    }
+                    1 if sub_packets.len() < 1 << 11 => {
+                        push(bits, 1, 1);
+                        push(bits, sub_packets.len() as u64, 11);
+                    }
+                    _ => return Err(AocError::logic("Too many sub-packets for the length type")),
+                }
+
+                bits.extend(sub_bits);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Expression of the packet, eg. `sum(3, max(7, 8))`. Versions aren't part of the expression.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::LiteralValue { value, .. } => write!(f, "{value}"),
+            Packet::Operator {
+                packet_type,
+                sub_packets,
+                ..
+            } => {
+                let name = OPERATORS.get(*packet_type as usize).ok_or(fmt::Error)?;
+                write!(f, "{name}(")?;
+
+                for (index, sub_packet) in sub_packets.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{sub_packet}")?;
+                }
+
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Parses the expression syntax of `Display`. The packets get version 0, and the operators
+/// count their sub-packets.
+impl FromStr for Packet {
+    type Err = AocError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match expression(input.trim()) {
+            Ok(("", packet)) => Ok(packet),
+            Ok((remaining, _)) => Err(AocError::parse(remaining, "Unexpected trailing input")),
+            Err(err) => Err(AocError::parse(input, err)),
+        }
+    }
+}
+
+fn expression(input: &str) -> IResult<&str, Packet> {
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{alpha1, multispace0, u64},
+        combinator::{map, map_res},
+        multi::separated_list1,
+        sequence::{delimited, pair},
+    };
+
+    let literal = map(u64, |value| Packet::LiteralValue {
+        packet_version: 0,
+        packet_type: LITERAL_VALUE,
+        value,
+    });
+
+    let arguments = delimited(
+        pair(tag("("), multispace0),
+        separated_list1(delimited(multispace0, tag(","), multispace0), expression),
+        pair(multispace0, tag(")")),
+    );
+
+    let operator = map_res(pair(alpha1, arguments), |(name, sub_packets)| {
+        let packet_type = OPERATORS
+            .iter()
+            .position(|operator| !operator.is_empty() && *operator == name)
+            .ok_or(AocError::parse(name, "Unknown operator"))?;
+
+        if !is_valid_arity(packet_type as u8, sub_packets.len()) {
+            return Err(AocError::parse(name, "Expected two values to compare"));
+        }
+
+        Ok(Packet::Operator {
+            packet_version: 0,
+            packet_type: packet_type as u8,
+            len_type: 1,
+            sub_packets,
+        })
+    });
+
+    alt((literal, operator))(input)
+}
+
+/// Decodes the hexadecimal transmission into the outermost packet.
+pub fn parse_transmission(input: &str) -> Result<Packet, AocError> {
+    let bit_stream = decode_hex_input(input.trim())?;
+    let (_remaining, packet) = parse_packet((&bit_stream, 0))
+        .map_err(|err| AocError::parse(input, err.map_input(|_| "bits")))?;
+
+    Ok(packet)
+}
+
+pub fn decode_hex_input(input: &str) -> Result<Vec<u8>, AocError> {
+    let line = input.lines().next().unwrap_or_default();
+    if line.len() % 2 != 0 {
+        return Err(AocError::parse(line, "Expected whole bytes of hex digits"));
+    }
 
     (0..line.len())
         .step_by(2)
-        .map(|i| u8::from_str_radix(&input[i..i + 2], 16))
+        .map(|i| {
+            let byte = line
+                .get(i..i + 2)
+                .ok_or_else(|| AocError::parse(line, "Expected hex digits"))?;
+
+            u8::from_str_radix(byte, 16).map_err(|err| AocError::parse(byte, err))
+        })
         .collect()
 }
 
@@ -64,7 +259,7 @@ fn parse_literal_value(input: BitStream) -> IResult<BitStream, Packet> {
         }
     }
 
-    let packet = Packet::LiteraValue {
+    let packet = Packet::LiteralValue {
         packet_version: version,
         packet_type: 0b100u8,
         value,
@@ -123,11 +318,11 @@ fn parse_operator_11_bits(input: BitStream) -> IResult<BitStream, Packet> {
     ))
 }
 
-fn version_sum(packet: &Packet) -> u64 {
+pub fn version_sum(packet: &Packet) -> u64 {
     let mut sum: u64 = 0;
 
     match packet {
-        Packet::LiteraValue { packet_version, .. } => {
+        Packet::LiteralValue { packet_version, .. } => {
             sum += *packet_version as u64;
         }
         Packet::Operator {
@@ -143,58 +338,47 @@ fn version_sum(packet: &Packet) -> u64 {
     sum
 }
 
-fn packet_value(packet: &Packet) -> u64 {
+pub fn packet_value(packet: &Packet) -> Result<u64, AocError> {
     match packet {
-        Packet::LiteraValue { value, .. } => *value,
+        Packet::LiteralValue { value, .. } => Ok(*value),
         Packet::Operator {
             packet_type,
             sub_packets,
             ..
         } => {
-            let mut values = sub_packets.iter().map(packet_value);
-
-            match packet_type {
-                0 => {
-                    // Packets with type ID 0 are sum packets
-                    values.sum()
-                }
-                1 => {
-                    // Packets with type ID 1 are product packets
-                    values.product()
-                }
-                2 => {
-                    // Packets with type ID 2 are minimum packets
-                    values.min().unwrap()
-                }
-                3 => {
-                    // Packets with type ID 3 are maximum packets
-                    values.max().unwrap()
-                }
-                5 => {
-                    // Packets with type ID 5 are greater than packets
-                    if values.next().unwrap() > values.next().unwrap() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                6 => {
-                    // Packets with type ID 6 are less than packets
-                    if values.next().unwrap() < values.next().unwrap() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                7 => {
-                    // Packets with type ID 7 are equal to packets
-                    if values.next().unwrap() == values.next().unwrap() {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                _ => unreachable!(),
+            let values = sub_packets
+                .iter()
+                .map(packet_value)
+                .collect::<Result<Vec<u64>, _>>()?;
+
+            let overflow = || AocError::logic(format!("Value of {packet} overflows"));
+
+            match (packet_type, values.as_slice()) {
+                (_, []) => Err(AocError::logic(format!(
+                    "Operator of type {packet_type} has no sub-packets"
+                ))),
+                // Sum packets
+                (0, values) => values
+                    .iter()
+                    .try_fold(0u64, |sum, value| sum.checked_add(*value))
+                    .ok_or_else(overflow),
+                // Product packets
+                (1, values) => values
+                    .iter()
+                    .try_fold(1u64, |product, value| product.checked_mul(*value))
+                    .ok_or_else(overflow),
+                // Minimum packets
+                (2, values) => Ok(*values.iter().min().unwrap_or(&0)),
+                // Maximum packets
+                (3, values) => Ok(*values.iter().max().unwrap_or(&0)),
+                // Greater than, less than and equal to packets
+                (5, [a, b]) => Ok((a > b) as u64),
+                (6, [a, b]) => Ok((a < b) as u64),
+                (7, [a, b]) => Ok((a == b) as u64),
+                _ => Err(AocError::logic(format!(
+                    "Operator of type {packet_type} can't take {} sub-packets",
+                    values.len()
+                ))),
             }
         }
     }
@@ -209,17 +393,15 @@ impl Solution for Day16 {
     }
 
     fn part_1(&self, input: &str) -> Result<u64, AocError> {
-        let bit_stream = decode_hex_input(input).unwrap();
-        let (_remaining, packet) = parse_packet((&bit_stream, 0)).unwrap();
+        let packet = parse_transmission(input)?;
 
         Ok(version_sum(&packet))
     }
 
     fn part_2(&self, input: &str) -> Result<u64, AocError> {
-        let bit_stream = decode_hex_input(input).unwrap();
-        let (_remaining, packet) = parse_packet((&bit_stream, 0)).unwrap();
+        let packet = parse_transmission(input)?;
 
-        Ok(packet_value(&packet))
+        packet_value(&packet)
     }
 }
 
@@ -233,7 +415,7 @@ mod tests {
         let (_, parsed) = parse_literal_value((&bit_stream, 0)).unwrap();
         assert_eq!(
             parsed,
-            Packet::LiteraValue {
+            Packet::LiteralValue {
                 packet_version: 6,
                 packet_type: 4,
                 value: 2021
@@ -252,12 +434,12 @@ mod tests {
                 packet_type: 6,
                 len_type: 0,
                 sub_packets: vec![
-                    Packet::LiteraValue {
+                    Packet::LiteralValue {
                         packet_version: 6,
                         packet_type: 4,
                         value: 10
                     },
-                    Packet::LiteraValue {
+                    Packet::LiteralValue {
                         packet_version: 2,
                         packet_type: 4,
                         value: 20
@@ -278,17 +460,17 @@ mod tests {
                 packet_type: 3,
                 len_type: 1,
                 sub_packets: vec![
-                    Packet::LiteraValue {
+                    Packet::LiteralValue {
                         packet_version: 2,
                         packet_type: 4,
                         value: 1
                     },
-                    Packet::LiteraValue {
+                    Packet::LiteralValue {
                         packet_version: 4,
                         packet_type: 4,
                         value: 2
                     },
-                    Packet::LiteraValue {
+                    Packet::LiteralValue {
                         packet_version: 1,
                         packet_type: 4,
                         value: 3
@@ -317,4 +499,140 @@ mod tests {
         assert_eq!(Day16.part_2("9C005AC2F8F0"), Ok(0));
         assert_eq!(Day16.part_2("9C0141080250320F1802104A08"), Ok(1));
     }
+
+    #[test]
+    fn it_prints_expressions() {
+        let packet = parse_transmission("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(packet.to_string(), "eq(sum(1, 3), product(2, 2))");
+
+        let packet = parse_transmission("CE00C43D881120").unwrap();
+        assert_eq!(packet.to_string(), "max(7, 8, 9)");
+    }
+
+    #[test]
+    fn it_parses_expressions() {
+        let packet: Packet = "sum(3, max(7,8) )".parse().unwrap();
+        assert_eq!(packet.to_string(), "sum(3, max(7, 8))");
+        assert_eq!(packet_value(&packet), Ok(11));
+
+        assert!("sum()".parse::<Packet>().is_err());
+        assert!("gt(1, 2, 3)".parse::<Packet>().is_err());
+        assert!("avg(1, 2)".parse::<Packet>().is_err());
+        assert!("sum(1, 2) 3".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn it_rejects_invalid_transmissions() {
+        // min() and gt(1) can be decoded, but not evaluated
+        assert!(Day16.part_2("080000").is_err());
+        assert!(Day16.part_2("36004410").is_err());
+        assert!(parse_transmission("36004410").is_ok());
+        assert!(parse_transmission("080000").is_ok());
+
+        assert!(parse_transmission("D2FE2").is_err());
+        assert!(parse_transmission("D2FEäa").is_err());
+        assert!(parse_transmission("D2FEXX").is_err());
+
+        let overflow = "product(4294967296, 4294967296)".parse().unwrap();
+        assert!(packet_value(&overflow).is_err());
+    }
+
+    #[test]
+    fn it_encodes_the_examples() {
+        for hex in [
+            "D2FE28",
+            "38006F45291200",
+            "EE00D40C823060",
+            "8A004A801A8002F478",
+        ] {
+            let packet = parse_transmission(hex).unwrap();
+            assert_eq!(packet.to_hex().unwrap(), hex);
+        }
+    }
+
+    #[test]
+    fn it_rejects_packets_that_cant_be_encoded() {
+        let packet = Packet::Operator {
+            packet_version: 8,
+            packet_type: 0,
+            len_type: 0,
+            sub_packets: vec![],
+        };
+        assert!(packet.to_hex().is_err());
+
+        let packet = Packet::Operator {
+            packet_version: 0,
+            packet_type: 0,
+            len_type: 1,
+            sub_packets: vec!["1".parse().unwrap(); 2048],
+        };
+        assert!(packet.to_hex().is_err());
+
+        for (packet_type, count) in [(2, 0), (3, 0), (5, 1), (6, 3), (7, 0), (4, 2)] {
+            let packet = Packet::Operator {
+                packet_version: 0,
+                packet_type,
+                len_type: 1,
+                sub_packets: vec!["1".parse().unwrap(); count],
+            };
+            assert!(packet.to_hex().is_err());
+            assert!(packet_value(&packet).is_err());
+        }
+    }
+
+    /// Xorshift generator, to get the same random trees every run
+    fn random(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    fn random_packet(state: &mut u64, depth: u32) -> Packet {
+        let packet_version = (random(state) % 8) as u8;
+
+        if depth == 0 || random(state) % 3 == 0 {
+            let value = random(state) >> (random(state) % 64);
+            return Packet::LiteralValue {
+                packet_version,
+                packet_type: LITERAL_VALUE,
+                value,
+            };
+        }
+
+        let packet_type = [0, 1, 2, 3, 5, 6, 7][(random(state) % 7) as usize];
+        let count = if packet_type > 4 {
+            2
+        } else {
+            1 + random(state) % 4
+        };
+
+        Packet::Operator {
+            packet_version,
+            packet_type,
+            len_type: (random(state) % 2) as u8,
+            sub_packets: (0..count)
+                .map(|_| random_packet(state, depth - 1))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn it_round_trips_random_packets() {
+        let mut state = 2021;
+
+        for _ in 0..200 {
+            let packet = random_packet(&mut state, 4);
+
+            let hex = packet.to_hex().unwrap();
+            assert_eq!(parse_transmission(&hex), Ok(packet.clone()));
+
+            let expression: Packet = packet.to_string().parse().unwrap();
+            assert_eq!(expression.to_string(), packet.to_string());
+            assert_eq!(
+                parse_transmission(&expression.to_hex().unwrap()),
+                Ok(expression)
+            );
+        }
+    }
 }
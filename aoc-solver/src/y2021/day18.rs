@@ -1,362 +1,311 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+
 use itertools::Itertools;
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::rc::Weak;
 
 use crate::solution::{AocError, Solution};
 
+/// Pairs nested deeper than this explode
+const MAX_DEPTH: u8 = 4;
+/// Regular numbers at least this large split
+const SPLIT_THRESHOLD: u32 = 10;
+/// Largest regular number accepted by the parser. A number has at most 32 regular numbers
+/// and a magnitude of at most 5^5 times its largest regular number, so neither the sums of
+/// exploding nor the magnitude can overflow.
+const MAX_REGULAR: u32 = 1_000_000;
+
 pub struct Day18;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Op {
-    Pair,
-    Value(u32),
+/// Regular number and the number of pairs it is nested in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Leaf {
+    depth: u8,
+    value: u32,
 }
 
-#[derive(Debug, Clone)]
-struct BTNode {
-    parent: Option<Weak<RefCell<BTNode>>>,
-    left: Option<Rc<RefCell<BTNode>>>,
-    right: Option<Rc<RefCell<BTNode>>>,
-    op: Op,
+/// Snailfish number stored as its regular numbers from left to right with their depths.
+/// The depths are enough to rebuild the tree, and both reduction actions only touch
+/// neighbouring regular numbers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snailfish {
+    leaves: Vec<Leaf>,
 }
 
-impl BTNode {
-    fn new_empty_pair() -> Rc<RefCell<BTNode>> {
-        Rc::new(RefCell::new(BTNode {
-            parent: None,
-            left: None,
-            right: None,
-            op: Op::Pair,
-        }))
-    }
-    fn new_pair(
-        left: Option<Rc<RefCell<BTNode>>>,
-        right: Option<Rc<RefCell<BTNode>>>,
-    ) -> Rc<RefCell<BTNode>> {
-        Rc::new(RefCell::new(BTNode {
-            parent: None,
-            left,
-            right,
-            op: Op::Pair,
-        }))
-    }
-    fn new_value(value: u32) -> Rc<RefCell<BTNode>> {
-        Rc::new(RefCell::new(BTNode {
-            parent: None,
-            left: None,
-            right: None,
-            op: Op::Value(value),
-        }))
-    }
-    fn new_value_with_parent(value: u32, parent: &Rc<RefCell<BTNode>>) -> Rc<RefCell<BTNode>> {
-        Rc::new(RefCell::new(BTNode {
-            parent: Some(Rc::downgrade(parent)),
-            left: None,
-            right: None,
-            op: Op::Value(value),
-        }))
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Addition,
+    Explode,
+    Split,
 }
 
-impl PartialEq for BTNode {
-    fn eq(&self, other: &Self) -> bool {
-        self.left == other.left && self.right == other.right && self.op == other.op
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Addition => write!(f, "addition"),
+            Action::Explode => write!(f, "explode"),
+            Action::Split => write!(f, "split"),
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct BinaryTree {
-    head: Option<Rc<RefCell<BTNode>>>,
+/// Number after an action of an addition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub action: Action,
+    pub number: Snailfish,
 }
 
-impl std::fmt::Display for BinaryTree {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", BinaryTree::unparse(self.head.as_ref().unwrap()))
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let action = format!("{}:", self.action);
+        write!(f, "after {action:<9} {}", self.number)
     }
 }
 
-impl BinaryTree {
-    fn parse(input: &str) -> BinaryTree {
-        let mut depth = 0;
-        let mut stack: Vec<(usize, Rc<RefCell<BTNode>>)> = Vec::new();
-        for c in input.chars() {
-            if c == '[' {
-                let node = BTNode::new_empty_pair();
-                if let Some((_depth, parent)) = stack.last() {
-                    let mut node_mut = node.borrow_mut();
-                    node_mut.parent = Some(Rc::downgrade(parent));
-                }
-                stack.push((depth, node));
-                depth += 1;
-            } else if c == ']' {
-                // Empty pair "[]" is illegal
-                assert!(stack.len() >= 2);
-                let (r_depth, right) = stack.pop().unwrap();
-                let (l_depth, left) = stack.pop().unwrap();
-
-                if left.borrow().op == Op::Pair && l_depth != r_depth {
-                    // There was no left node
-                    let parent = left;
-                    right.borrow_mut().parent = Some(Rc::downgrade(&parent));
-                    parent.borrow_mut().right = Some(right);
-
-                    stack.push((depth, parent));
-                } else {
-                    let (depth, parent) = stack.pop().unwrap();
-                    right.borrow_mut().parent = Some(Rc::downgrade(&parent));
-                    left.borrow_mut().parent = Some(Rc::downgrade(&parent));
-                    parent.borrow_mut().right = Some(right);
-                    parent.borrow_mut().left = Some(left);
-
-                    stack.push((depth, parent));
+impl Snailfish {
+    pub fn magnitude(&self) -> u32 {
+        // Siblings end up next to each other on the stack as soon as both are complete
+        let mut stack: Vec<Leaf> = Vec::new();
+
+        for leaf in &self.leaves {
+            let mut leaf = *leaf;
+            while let Some(left) = stack.last().filter(|left| left.depth == leaf.depth) {
+                leaf = Leaf {
+                    depth: leaf.depth - 1,
+                    value: 3 * left.value + 2 * leaf.value,
                 };
-
-                depth -= 1;
-            } else if c.is_ascii_digit() {
-                let value = c.to_digit(10).unwrap();
-                let node = BTNode::new_value(value);
-                stack.push((depth, node));
+                stack.pop();
             }
+            stack.push(leaf);
         }
-        // Now the stack should only contain the head
-        assert_eq!(stack.len(), 1);
 
-        BinaryTree {
-            head: Some(stack.pop().unwrap().1),
-        }
+        stack.first().map_or(0, |root| root.value)
     }
 
-    // For debugging purposes
-    fn unparse(node: &Rc<RefCell<BTNode>>) -> String {
-        if let Op::Value(value) = node.borrow().op {
-            return value.to_string();
-        }
-        let mut output = String::new();
-        if let Some(left) = &node.borrow().left {
-            output += "[";
-            output += BinaryTree::unparse(left).as_str();
-        }
-        if let Some(right) = &node.borrow().right {
-            output += ",";
-            output += BinaryTree::unparse(right).as_str();
-            output += "]";
-        }
-        output
+    pub fn is_reduced(&self) -> bool {
+        self.leaves
+            .iter()
+            .all(|leaf| leaf.depth <= MAX_DEPTH && leaf.value < SPLIT_THRESHOLD)
     }
 
-    fn add(&mut self, other: BinaryTree) {
-        let head = BTNode::new_pair(std::mem::take(&mut self.head), other.head);
-        head.borrow().left.as_ref().unwrap().borrow_mut().parent = Some(Rc::downgrade(&head));
-        head.borrow().right.as_ref().unwrap().borrow_mut().parent = Some(Rc::downgrade(&head));
-
-        self.head = Some(head);
+    /// Applies the first applicable reduction action, if any
+    pub fn step(&mut self) -> Option<Action> {
+        if let Some(index) = self.leaves.iter().position(|leaf| leaf.depth > MAX_DEPTH) {
+            self.explode(index);
+            Some(Action::Explode)
+        } else if let Some(index) = self
+            .leaves
+            .iter()
+            .position(|leaf| leaf.value >= SPLIT_THRESHOLD)
+        {
+            self.split(index);
+            Some(Action::Split)
+        } else {
+            None
+        }
     }
 
-    fn find_leftmost_explosive(&self) -> Option<Rc<RefCell<BTNode>>> {
-        BinaryTree::find_explosive_recursive(self.head.as_ref().unwrap(), 0)
+    pub fn reduce(&mut self) {
+        while self.step().is_some() {}
     }
 
-    fn find_explosive_recursive(
-        node: &Rc<RefCell<BTNode>>,
-        depth: usize,
-    ) -> Option<Rc<RefCell<BTNode>>> {
-        if depth == 4 && node.borrow().op == Op::Pair {
-            return Some(node.clone());
-        }
+    /// Adds the numbers and records the number after every action of the reduction
+    pub fn trace_add(&self, other: &Snailfish) -> Vec<Step> {
+        let mut number = self.clone().join(other.clone());
+        let mut steps = vec![Step {
+            action: Action::Addition,
+            number: number.clone(),
+        }];
 
-        if let Some(left) = &node.borrow().left {
-            let found = BinaryTree::find_explosive_recursive(left, depth + 1);
-            if found.is_some() {
-                return found;
-            }
-        }
-        if let Some(right) = &node.borrow().right {
-            return BinaryTree::find_explosive_recursive(right, depth + 1);
+        while let Some(action) = number.step() {
+            steps.push(Step {
+                action,
+                number: number.clone(),
+            });
         }
 
-        None
+        steps
     }
 
-    fn find_leftmost_splittable(&self) -> Option<Rc<RefCell<BTNode>>> {
-        BinaryTree::find_splittable_recursive(self.head.as_ref().unwrap())
+    /// Pairs up the numbers without reducing the result. The operands are reduced first,
+    /// so that the only pairs nested too deep are pairs of two regular numbers.
+    fn join(mut self, mut other: Snailfish) -> Snailfish {
+        self.reduce();
+        other.reduce();
+
+        let leaves = self
+            .leaves
+            .into_iter()
+            .chain(other.leaves)
+            .map(|leaf| Leaf {
+                depth: leaf.depth + 1,
+                ..leaf
+            })
+            .collect();
+
+        Snailfish { leaves }
     }
 
-    fn find_splittable_recursive(node: &Rc<RefCell<BTNode>>) -> Option<Rc<RefCell<BTNode>>> {
-        if let Op::Value(value) = node.borrow().op {
-            if value >= 10 {
-                return Some(node.clone());
-            }
-        }
+    /// The leftmost regular number nested too deep is the left side of the exploding
+    /// pair, and the right side is the next regular number
+    fn explode(&mut self, index: usize) {
+        let left = self.leaves[index];
+        let right = self.leaves.remove(index + 1);
 
-        if let Some(left) = &node.borrow().left {
-            let found = BinaryTree::find_splittable_recursive(left);
-            if found.is_some() {
-                return found;
-            }
+        if let Some(previous) = index.checked_sub(1) {
+            self.leaves[previous].value += left.value;
         }
-        if let Some(right) = &node.borrow().right {
-            return BinaryTree::find_splittable_recursive(right);
+        if let Some(next) = self.leaves.get_mut(index + 1) {
+            next.value += right.value;
         }
 
-        None
-    }
-
-    fn add_to_ancestors_left(node: Rc<RefCell<BTNode>>, value: u32) {
-        if let Some(parent_weak) = &node.borrow().parent {
-            if let Some(parent) = parent_weak.upgrade() {
-                if let Some(left) = &parent.borrow().left {
-                    if Rc::ptr_eq(left, &node) {
-                        BinaryTree::add_to_ancestors_left(parent.clone(), value);
-                    } else {
-                        let op = left.borrow().op;
-                        match op {
-                            Op::Pair => {
-                                BinaryTree::add_to_offspring_right(left.clone(), value);
-                            }
-                            Op::Value(current_value) => {
-                                left.borrow_mut().op = Op::Value(current_value + value);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        self.leaves[index] = Leaf {
+            depth: left.depth - 1,
+            value: 0,
+        };
+    }
+
+    fn split(&mut self, index: usize) {
+        let Leaf { depth, value } = self.leaves[index];
+
+        self.leaves[index] = Leaf {
+            depth: depth + 1,
+            value: value / 2,
+        };
+        self.leaves.insert(
+            index + 1,
+            Leaf {
+                depth: depth + 1,
+                value: (value + 1) / 2,
+            },
+        );
     }
 
-    fn add_to_ancestors_right(node: Rc<RefCell<BTNode>>, value: u32) {
-        if let Some(parent_weak) = &node.borrow().parent {
-            if let Some(parent) = parent_weak.upgrade() {
-                if let Some(right) = &parent.borrow().right {
-                    if Rc::ptr_eq(right, &node) {
-                        BinaryTree::add_to_ancestors_right(parent.clone(), value);
-                    } else {
-                        let op = right.borrow().op;
-                        match op {
-                            Op::Pair => {
-                                BinaryTree::add_to_offspring_left(right.clone(), value);
-                            }
-                            Op::Value(current_value) => {
-                                right.borrow_mut().op = Op::Value(current_value + value);
-                            }
-                        }
-                    }
+    fn parse_element(
+        input: &str,
+        chars: &mut std::iter::Peekable<std::str::CharIndices>,
+        depth: u8,
+        leaves: &mut Vec<Leaf>,
+    ) -> Result<(), AocError> {
+        let error = |position: Option<(usize, char)>, expected: &str| {
+            let found = position.map_or("end of input".to_string(), |(index, c)| {
+                format!("'{c}' at {index}")
+            });
+            AocError::parse(input, format!("expected {expected}, found {found}"))
+        };
+
+        match chars.peek().copied() {
+            Some((_, '[')) => {
+                // Reduction never nests deeper than the exploding pairs
+                if depth > MAX_DEPTH {
+                    return Err(AocError::parse(input, "pairs are nested too deep"));
                 }
-            }
-        }
-    }
 
-    fn add_to_offspring_left(node: Rc<RefCell<BTNode>>, value: u32) {
-        if let Some(left) = &node.borrow().left {
-            let op = left.borrow().op;
-            match op {
-                Op::Pair => {
-                    BinaryTree::add_to_offspring_left(left.clone(), value);
+                chars.next();
+                Snailfish::parse_element(input, chars, depth + 1, leaves)?;
+                match chars.next() {
+                    Some((_, ',')) => {}
+                    other => return Err(error(other, "','")),
                 }
-                Op::Value(current_value) => {
-                    left.borrow_mut().op = Op::Value(current_value + value);
+                Snailfish::parse_element(input, chars, depth + 1, leaves)?;
+                match chars.next() {
+                    Some((_, ']')) => Ok(()),
+                    other => Err(error(other, "']'")),
                 }
             }
-        }
-    }
-
-    fn add_to_offspring_right(node: Rc<RefCell<BTNode>>, value: u32) {
-        if let Some(right) = &node.borrow().right {
-            let op = right.borrow().op;
-            match op {
-                Op::Pair => {
-                    BinaryTree::add_to_offspring_right(right.clone(), value);
-                }
-                Op::Value(current_value) => {
-                    right.borrow_mut().op = Op::Value(current_value + value);
+            Some((_, c)) if c.is_ascii_digit() => {
+                let mut value: u32 = 0;
+                while let Some(digit) = chars.peek().and_then(|(_, c)| c.to_digit(10)) {
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit))
+                        .filter(|value| *value <= MAX_REGULAR)
+                        .ok_or_else(|| AocError::parse(input, "regular number is too large"))?;
+                    chars.next();
                 }
+                leaves.push(Leaf { depth, value });
+                Ok(())
             }
+            other => Err(error(other, "'[' or a regular number")),
         }
     }
 
-    fn reduce(&self) {
-        loop {
-            if let Some(node) = self.find_leftmost_explosive() {
-                BinaryTree::explode(&node);
-            } else if let Some(node) = self.find_leftmost_splittable() {
-                BinaryTree::split(&node);
-            } else {
-                break;
-            }
+    fn fmt_element(&self, f: &mut fmt::Formatter, index: &mut usize, depth: u8) -> fmt::Result {
+        let leaf = self.leaves[*index];
+        if leaf.depth == depth {
+            *index += 1;
+            return write!(f, "{}", leaf.value);
         }
-    }
 
-    fn magnitude(&self) -> u32 {
-        BinaryTree::magnitude_recursive(self.head.as_ref().unwrap())
+        write!(f, "[")?;
+        self.fmt_element(f, index, depth + 1)?;
+        write!(f, ",")?;
+        self.fmt_element(f, index, depth + 1)?;
+        write!(f, "]")
     }
+}
 
-    fn magnitude_recursive(node: &Rc<RefCell<BTNode>>) -> u32 {
-        let op = node.borrow().op;
-        let mut magnitude = 0;
-        match op {
-            Op::Value(value) => value,
-            Op::Pair => {
-                if let Some(left) = node.borrow().left.clone() {
-                    magnitude += 3 * BinaryTree::magnitude_recursive(&left);
-                }
+impl FromStr for Snailfish {
+    type Err = AocError;
 
-                if let Some(right) = node.borrow().right.clone() {
-                    magnitude += 2 * BinaryTree::magnitude_recursive(&right);
-                }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().char_indices().peekable();
+        let mut leaves = Vec::new();
 
-                magnitude
-            }
+        if chars.peek().is_some_and(|(_, c)| *c != '[') {
+            return Err(AocError::parse(s, "snailfish numbers are pairs"));
         }
-    }
-
-    fn explode(node: &Rc<RefCell<BTNode>>) {
-        let left = node.borrow().left.clone();
-        let right = node.borrow().right.clone();
 
-        if let Some(left) = left {
-            if let Op::Value(value) = left.borrow().op {
-                BinaryTree::add_to_ancestors_left(left.clone(), value);
-            }
+        Snailfish::parse_element(s, &mut chars, 0, &mut leaves)?;
+        if let Some((index, c)) = chars.next() {
+            return Err(AocError::parse(s, format!("unexpected '{c}' at {index}")));
         }
 
-        if let Some(right) = right {
-            if let Op::Value(value) = right.borrow().op {
-                BinaryTree::add_to_ancestors_right(right.clone(), value);
-            }
-        }
+        Ok(Snailfish { leaves })
+    }
+}
 
-        let mut node_mut = node.borrow_mut();
-        node_mut.op = Op::Value(0);
-        node_mut.left = None;
-        node_mut.right = None;
+impl fmt::Display for Snailfish {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_element(f, &mut 0, 0)
     }
+}
 
-    fn split(node: &Rc<RefCell<BTNode>>) {
-        let op = node.borrow().op;
-        if let Op::Value(value) = op {
-            let left = BTNode::new_value_with_parent(value / 2, node);
-            let right = BTNode::new_value_with_parent((value + 1) / 2, node);
+impl Add for Snailfish {
+    type Output = Snailfish;
 
-            let mut node_mut = node.borrow_mut();
-            node_mut.op = Op::Pair;
-            node_mut.left = Some(left);
-            node_mut.right = Some(right);
-        } else {
-            panic!("Cannot split a non-value node");
-        }
+    fn add(self, other: Snailfish) -> Snailfish {
+        let mut sum = self.join(other);
+        sum.reduce();
+        sum
+    }
+}
+
+impl Add for &Snailfish {
+    type Output = Snailfish;
+
+    fn add(self, other: &Snailfish) -> Snailfish {
+        self.clone() + other.clone()
     }
 }
 
-fn add_list(input: &str) -> BinaryTree {
-    let mut lines = input.lines().map(BinaryTree::parse);
-    let mut result = lines.next().unwrap();
+/// Snailfish numbers have no zero, so the sum of no numbers is `None`
+impl Sum<Snailfish> for Option<Snailfish> {
+    fn sum<I: Iterator<Item = Snailfish>>(iter: I) -> Self {
+        iter.reduce(Add::add)
+    }
+}
 
-    for number in lines {
-        result.add(number);
-        result.reduce();
+impl<'a> Sum<&'a Snailfish> for Option<Snailfish> {
+    fn sum<I: Iterator<Item = &'a Snailfish>>(iter: I) -> Self {
+        iter.cloned().sum()
     }
+}
 
-    result
+fn parse(input: &str) -> Result<Vec<Snailfish>, AocError> {
+    input.lines().map(str::parse).collect()
 }
 
 impl Solution for Day18 {
@@ -368,34 +317,21 @@ impl Solution for Day18 {
     }
 
     fn part_1(&self, input: &str) -> Result<u32, AocError> {
-        let result = add_list(input);
-        let magnitude = result.magnitude();
+        let sum: Option<Snailfish> = parse(input)?.into_iter().sum();
+        let sum = sum.ok_or_else(|| AocError::logic("No snailfish numbers"))?;
 
-        Ok(magnitude)
+        Ok(sum.magnitude())
     }
 
     fn part_2(&self, input: &str) -> Result<u32, AocError> {
-        let mut largest_magnitude = 0;
-
-        for (first, second) in input.lines().tuple_combinations() {
-            let mut binary_tree = BinaryTree::parse(first);
-            binary_tree.add(BinaryTree::parse(second));
-            binary_tree.reduce();
-            let magnitude = binary_tree.magnitude();
-            if magnitude > largest_magnitude {
-                largest_magnitude = magnitude;
-            }
+        let numbers = parse(input)?;
 
-            let mut binary_tree = BinaryTree::parse(second);
-            binary_tree.add(BinaryTree::parse(first));
-            binary_tree.reduce();
-            let magnitude = binary_tree.magnitude();
-            if magnitude > largest_magnitude {
-                largest_magnitude = magnitude;
-            }
-        }
-
-        Ok(largest_magnitude)
+        numbers
+            .iter()
+            .permutations(2)
+            .map(|pair| (pair[0] + pair[1]).magnitude())
+            .max()
+            .ok_or_else(|| AocError::logic("Less than two snailfish numbers"))
     }
 }
 
@@ -403,258 +339,168 @@ impl Solution for Day18 {
 mod tests {
     use super::*;
 
-    #[test]
-    fn it_parses_snailfish_numbers_1() {
-        assert_eq!(
-            BinaryTree::parse("[1,2]"),
-            BinaryTree {
-                head: Some(BTNode::new_pair(
-                    Some(BTNode::new_value(1)),
-                    Some(BTNode::new_value(2)),
-                ))
-            }
-        );
+    fn number(input: &str) -> Snailfish {
+        input.parse().unwrap()
     }
 
-    #[test]
-    fn it_parses_snailfish_numbers_2() {
-        assert_eq!(
-            BinaryTree::parse("[[1,2],3]"),
-            BinaryTree {
-                head: Some(BTNode::new_pair(
-                    Some(BTNode::new_pair(
-                        Some(BTNode::new_value(1)),
-                        Some(BTNode::new_value(2)),
-                    )),
-                    Some(BTNode::new_value(3))
-                ))
-            }
-        );
+    fn leaves(leaves: &[(u8, u32)]) -> Snailfish {
+        Snailfish {
+            leaves: leaves
+                .iter()
+                .map(|&(depth, value)| Leaf { depth, value })
+                .collect(),
+        }
     }
 
-    #[test]
-    fn it_parses_snailfish_numbers_3() {
-        assert_eq!(
-            BinaryTree::parse("[9,[8,7]]"),
-            BinaryTree {
-                head: Some(BTNode::new_pair(
-                    Some(BTNode::new_value(9)),
-                    Some(BTNode::new_pair(
-                        Some(BTNode::new_value(8)),
-                        Some(BTNode::new_value(7)),
-                    )),
-                ))
-            }
-        );
+    fn explode(input: &str) -> Snailfish {
+        let mut number = number(input);
+        assert_eq!(number.step(), Some(Action::Explode));
+        number
     }
 
-    #[test]
-    fn it_parses_snailfish_numbers_4() {
-        assert_eq!(
-            BinaryTree::parse("[[1,9],[8,5]]"),
-            BinaryTree {
-                head: Some(BTNode::new_pair(
-                    Some(BTNode::new_pair(
-                        Some(BTNode::new_value(1)),
-                        Some(BTNode::new_value(9)),
-                    )),
-                    Some(BTNode::new_pair(
-                        Some(BTNode::new_value(8)),
-                        Some(BTNode::new_value(5)),
-                    )),
-                ))
-            }
-        );
+    fn add_list(input: &str) -> Snailfish {
+        let sum: Option<Snailfish> = parse(input).unwrap().into_iter().sum();
+        sum.unwrap()
     }
 
     #[test]
-    fn it_parses_snailfish_numbers_5() {
+    fn it_parses_snailfish_numbers() {
+        assert_eq!(number("[1,2]"), leaves(&[(1, 1), (1, 2)]));
+        assert_eq!(number("[[1,2],3]"), leaves(&[(2, 1), (2, 2), (1, 3)]));
+        assert_eq!(number("[9,[8,7]]"), leaves(&[(1, 9), (2, 8), (2, 7)]));
         assert_eq!(
-            BinaryTree::parse("[[[[1,2],[3,4]],[[5,6],[7,8]]],9]"),
-            BinaryTree {
-                head: Some(BTNode::new_pair(
-                    Some(BTNode::new_pair(
-                        Some(BTNode::new_pair(
-                            Some(BTNode::new_pair(
-                                Some(BTNode::new_value(1)),
-                                Some(BTNode::new_value(2)),
-                            )),
-                            Some(BTNode::new_pair(
-                                Some(BTNode::new_value(3)),
-                                Some(BTNode::new_value(4)),
-                            )),
-                        )),
-                        Some(BTNode::new_pair(
-                            Some(BTNode::new_pair(
-                                Some(BTNode::new_value(5)),
-                                Some(BTNode::new_value(6)),
-                            )),
-                            Some(BTNode::new_pair(
-                                Some(BTNode::new_value(7)),
-                                Some(BTNode::new_value(8)),
-                            )),
-                        )),
-                    )),
-                    Some(BTNode::new_value(9)),
-                ))
-            }
+            number("[[1,9],[8,5]]"),
+            leaves(&[(2, 1), (2, 9), (2, 8), (2, 5)])
+        );
+        assert_eq!(
+            number("[[[[1,2],[3,4]],[[5,6],[7,8]]],9]"),
+            leaves(&[
+                (4, 1),
+                (4, 2),
+                (4, 3),
+                (4, 4),
+                (4, 5),
+                (4, 6),
+                (4, 7),
+                (4, 8),
+                (1, 9)
+            ])
         );
     }
 
     #[test]
-    fn it_parses_snailfish_numbers_6() {
-        BinaryTree::parse("[1,2]");
-        BinaryTree::parse("[[1,2],3]");
-        BinaryTree::parse("[9,[8,7]]");
-        BinaryTree::parse("[[1,9],[8,5]]");
-        BinaryTree::parse("[[[[1,2],[3,4]],[[5,6],[7,8]]],9]");
-        BinaryTree::parse("[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]");
-        BinaryTree::parse("[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]");
-    }
-
-    #[test]
-    fn it_adds_two_snailfish_numbers() {
-        let mut binary_tree = BinaryTree::parse("[1,2]");
-        binary_tree.add(BinaryTree::parse("[[3,4],5]"));
-        assert_eq!(binary_tree, BinaryTree::parse("[[1,2],[[3,4],5]]"));
-    }
-
-    #[test]
-    fn it_finds_explosive_pairs_1() {
-        let binary_tree = BinaryTree::parse("[[[[[9,8],1],2],3],4]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        assert!(explosive_pair.is_some());
-        assert_eq!(explosive_pair, BinaryTree::parse("[9,8]").head);
-    }
-
-    #[test]
-    fn it_finds_explosive_pairs_2() {
-        let binary_tree = BinaryTree::parse("[[[[1,[9,8]],2],3],4]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        assert_eq!(explosive_pair, BinaryTree::parse("[9,8]").head);
-    }
-
-    #[test]
-    fn it_finds_explosive_pairs_3() {
-        let binary_tree = BinaryTree::parse("[7,[6,[5,[4,[3,2]]]]]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        assert_eq!(explosive_pair, BinaryTree::parse("[3,2]").head);
-    }
-
-    #[test]
-    fn it_finds_explosive_pairs_4() {
-        let binary_tree = BinaryTree::parse("[[[[1,9],2],3],4]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        assert_eq!(explosive_pair, None);
-    }
-
-    #[test]
-    fn it_explodes_examples_1() {
-        let binary_tree = BinaryTree::parse("[[[[[9,8],1],2],3],4]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
-        assert_eq!(binary_tree, BinaryTree::parse("[[[[0,9],2],3],4]"));
-    }
-
-    #[test]
-    fn it_explodes_examples_2() {
-        let binary_tree = BinaryTree::parse("[7,[6,[5,[4,[3,2]]]]]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
-        assert_eq!(binary_tree, BinaryTree::parse("[7,[6,[5,[7,0]]]]"));
+    fn it_prints_snailfish_numbers() {
+        for input in [
+            "[1,2]",
+            "[[1,2],3]",
+            "[9,[8,7]]",
+            "[[1,9],[8,5]]",
+            "[[[[1,2],[3,4]],[[5,6],[7,8]]],9]",
+            "[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]",
+            "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+            "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+        ] {
+            assert_eq!(number(input).to_string(), input);
+        }
     }
 
     #[test]
-    fn it_explodes_examples_3() {
-        let binary_tree = BinaryTree::parse("[[6,[5,[4,[3,2]]]],1]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
-        assert_eq!(binary_tree, BinaryTree::parse("[[6,[5,[7,0]]],3]"));
+    fn it_rejects_invalid_numbers() {
+        assert!("".parse::<Snailfish>().is_err());
+        assert!("[]".parse::<Snailfish>().is_err());
+        assert!("7".parse::<Snailfish>().is_err());
+        assert!("[1,2".parse::<Snailfish>().is_err());
+        assert!("[1;2]".parse::<Snailfish>().is_err());
+        assert!("[1,2]]".parse::<Snailfish>().is_err());
+        assert!("[[[[[[1,2],3],4],5],6],7]".parse::<Snailfish>().is_err());
+        assert!("[99999999999,1]".parse::<Snailfish>().is_err());
     }
 
     #[test]
-    fn it_explodes_examples_4() {
-        let binary_tree = BinaryTree::parse("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
+    fn it_adds_two_snailfish_numbers() {
         assert_eq!(
-            binary_tree,
-            BinaryTree::parse("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")
+            number("[1,2]") + number("[[3,4],5]"),
+            number("[[1,2],[[3,4],5]]")
         );
     }
 
     #[test]
-    fn it_explodes_examples_5() {
-        let binary_tree = BinaryTree::parse("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]");
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
+    fn it_explodes_examples() {
         assert_eq!(
-            binary_tree,
-            BinaryTree::parse("[[3,[2,[8,0]]],[9,[5,[7,0]]]]")
+            explode("[[[[[9,8],1],2],3],4]"),
+            number("[[[[0,9],2],3],4]")
         );
+        assert_eq!(
+            explode("[7,[6,[5,[4,[3,2]]]]]"),
+            number("[7,[6,[5,[7,0]]]]")
+        );
+        assert_eq!(
+            explode("[[6,[5,[4,[3,2]]]],1]"),
+            number("[[6,[5,[7,0]]],3]")
+        );
+        assert_eq!(
+            explode("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]"),
+            number("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")
+        );
+        assert_eq!(
+            explode("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+            number("[[3,[2,[8,0]]],[9,[5,[7,0]]]]")
+        );
+        assert_eq!(number("[[[[1,9],2],3],4]").step(), None);
     }
 
     #[test]
     fn it_splits_example_manually() {
-        let mut binary_tree = BinaryTree::parse("[[[[4,3],4],4],[7,[[8,4],9]]]");
-        binary_tree.add(BinaryTree::parse("[1,1]"));
+        let mut sum = number("[[[[4,3],4],4],[7,[[8,4],9]]]").join(number("[1,1]"));
+        assert_eq!(sum, number("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]"));
 
-        assert_eq!(
-            binary_tree,
-            BinaryTree::parse("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]")
-        );
+        assert_eq!(sum.step(), Some(Action::Explode));
+        assert_eq!(sum.step(), Some(Action::Explode));
+        assert_eq!(sum.step(), Some(Action::Split));
+        assert_eq!(sum.step(), Some(Action::Split));
+        assert_eq!(sum, number("[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]"));
 
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
-
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
-
-        let splittable = binary_tree.find_leftmost_splittable();
-        BinaryTree::split(&splittable.unwrap());
-
-        let splittable = binary_tree.find_leftmost_splittable();
-        BinaryTree::split(&splittable.unwrap());
-        assert_eq!(
-            binary_tree,
-            BinaryTree::parse("[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]")
-        );
+        assert_eq!(sum.step(), Some(Action::Explode));
+        assert_eq!(sum, number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
+        assert_eq!(sum.step(), None);
+        assert!(sum.is_reduced());
+    }
 
-        let explosive_pair = binary_tree.find_leftmost_explosive();
-        BinaryTree::explode(&explosive_pair.unwrap());
+    #[test]
+    fn it_traces_reduction() {
+        let steps = number("[[[[4,3],4],4],[7,[[8,4],9]]]").trace_add(&number("[1,1]"));
 
         assert_eq!(
-            binary_tree,
-            BinaryTree::parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
+            steps
+                .iter()
+                .map(|step| step.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "after addition: [[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "after split:    [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "after split:    [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
         );
     }
 
     #[test]
-    fn it_adds_list_of_snailfish_numbers_1() {
+    fn it_adds_list_of_snailfish_numbers() {
         assert_eq!(
             add_list("[1,1]\n[2,2]\n[3,3]\n[4,4]"),
-            BinaryTree::parse("[[[[1,1],[2,2]],[3,3]],[4,4]]")
+            number("[[[[1,1],[2,2]],[3,3]],[4,4]]")
         );
-    }
-
-    #[test]
-    fn it_adds_list_of_snailfish_numbers_2() {
         assert_eq!(
             add_list("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]"),
-            BinaryTree::parse("[[[[3,0],[5,3]],[4,4]],[5,5]]")
+            number("[[[[3,0],[5,3]],[4,4]],[5,5]]")
         );
-    }
-
-    #[test]
-    fn it_adds_list_of_snailfish_numbers_3() {
         assert_eq!(
             add_list("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]"),
-            BinaryTree::parse("[[[[5,0],[7,4]],[5,5]],[6,6]]")
+            number("[[[[5,0],[7,4]],[5,5]],[6,6]]")
         );
-    }
 
-    #[test]
-    fn it_adds_list_of_snailfish_numbers_4() {
         let final_sum = add_list(
             "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]\n\
              [7,[[[3,7],[4,3]],[[6,3],[8,8]]]]\n\
@@ -670,23 +516,33 @@ mod tests {
 
         assert_eq!(
             final_sum,
-            BinaryTree::parse("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]")
+            number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]")
         );
     }
 
+    #[test]
+    fn it_sums_references() {
+        let numbers = parse("[1,1]\n[2,2]\n[3,3]\n[4,4]").unwrap();
+        let sum: Option<Snailfish> = numbers.iter().sum();
+        assert_eq!(sum, Some(number("[[[[1,1],[2,2]],[3,3]],[4,4]]")));
+
+        let empty: Option<Snailfish> = Vec::<Snailfish>::new().into_iter().sum();
+        assert_eq!(empty, None);
+    }
+
     #[test]
     #[rustfmt::skip]
     fn it_solves_magnitude_examples() {
-        assert_eq!(BinaryTree::parse("[9,1]").magnitude(), 29);
-        assert_eq!(BinaryTree::parse("[1,9]").magnitude(), 21);
-        assert_eq!(BinaryTree::parse("[[9,1],[1,9]]").magnitude(), 129);
-        assert_eq!(BinaryTree::parse("[[1,2],[[3,4],5]]").magnitude(), 143);
-        assert_eq!(BinaryTree::parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").magnitude(), 1384);
-        assert_eq!(BinaryTree::parse("[[[[1,1],[2,2]],[3,3]],[4,4]]").magnitude(), 445);
-        assert_eq!(BinaryTree::parse("[[[[3,0],[5,3]],[4,4]],[5,5]]").magnitude(), 791);
-        assert_eq!(BinaryTree::parse("[[[[5,0],[7,4]],[5,5]],[6,6]]").magnitude(), 1137);
-        assert_eq!(BinaryTree::parse("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(), 3488);
-        assert_eq!(BinaryTree::parse("[[[[7,8],[6,6]],[[6,0],[7,7]]],[[[7,8],[8,8]],[[7,9],[0,6]]]]").magnitude(), 3993);
+        assert_eq!(number("[9,1]").magnitude(), 29);
+        assert_eq!(number("[1,9]").magnitude(), 21);
+        assert_eq!(number("[[9,1],[1,9]]").magnitude(), 129);
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").magnitude(), 1384);
+        assert_eq!(number("[[[[1,1],[2,2]],[3,3]],[4,4]]").magnitude(), 445);
+        assert_eq!(number("[[[[3,0],[5,3]],[4,4]],[5,5]]").magnitude(), 791);
+        assert_eq!(number("[[[[5,0],[7,4]],[5,5]],[6,6]]").magnitude(), 1137);
+        assert_eq!(number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(), 3488);
+        assert_eq!(number("[[[[7,8],[6,6]],[[6,0],[7,7]]],[[[7,8],[8,8]],[[7,9],[0,6]]]]").magnitude(), 3993);
     }
 
     #[test]
@@ -726,4 +582,20 @@ mod tests {
             Ok(3993)
         );
     }
+
+    #[test]
+    fn it_bounds_regular_numbers_to_avoid_overflow() {
+        assert!("[4000000000,1]".parse::<Snailfish>().is_err());
+        assert!("[1000001,1]".parse::<Snailfish>().is_err());
+
+        fn nested(depth: u8) -> String {
+            match depth {
+                0 => MAX_REGULAR.to_string(),
+                _ => format!("[{0},{0}]", nested(depth - 1)),
+            }
+        }
+
+        let largest: Snailfish = nested(MAX_DEPTH + 1).parse().unwrap();
+        assert_eq!(largest.magnitude(), 3_125_000_000);
+    }
 }
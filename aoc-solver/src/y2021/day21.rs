@@ -1,6 +1,5 @@
-use cached::proc_macro::cached;
-
 use crate::solution::{AocError, Solution};
+use crate::utils::memo::Memoized;
 
 // With three d3 we can get rolls in 27 different ways: (1,1,1), (1,1,2), (1,1,3) ...
 // Of those, distinct sums are: 3, 4, 5, 6, 7, 8, 9
@@ -42,20 +41,34 @@ fn parse(input: &str) -> [Player; 2] {
     ]
 }
 
-#[cached]
+type Universe = ([Player; 2], usize);
+
 fn roll_dirac_dice(players: [Player; 2], active_player: usize) -> [usize; 2] {
-    let mut wins = [0, 0];
+    let mut roll_dirac_dice = Memoized::new(
+        |roll_dirac_dice: &mut dyn FnMut(Universe) -> [usize; 2],
+         (players, active_player): Universe| {
+            let mut wins = [0, 0];
+
+            for (rolls, count) in DISTINCT_D3_ROLLS_AND_COUNTS {
+                let branch_total_wins =
+                    play_quantum_turn(roll_dirac_dice, rolls, players, active_player);
+                wins[0] += count * branch_total_wins[0];
+                wins[1] += count * branch_total_wins[1];
+            }
 
-    for (rolls, count) in DISTINCT_D3_ROLLS_AND_COUNTS {
-        let branch_total_wins = play_quantum_turn(rolls, players, active_player);
-        wins[0] += count * branch_total_wins[0];
-        wins[1] += count * branch_total_wins[1];
-    }
+            wins
+        },
+    );
 
-    wins
+    roll_dirac_dice.call((players, active_player))
 }
 
-fn play_quantum_turn(rolls: u32, mut players: [Player; 2], active_player: usize) -> [usize; 2] {
+fn play_quantum_turn(
+    roll_dirac_dice: &mut dyn FnMut(Universe) -> [usize; 2],
+    rolls: u32,
+    mut players: [Player; 2],
+    active_player: usize,
+) -> [usize; 2] {
     players[active_player].position = (players[active_player].position + rolls) % 10;
     players[active_player].score += players[active_player].position + 1;
 
@@ -67,7 +80,7 @@ fn play_quantum_turn(rolls: u32, mut players: [Player; 2], active_player: usize)
 
     let next_player = (active_player + 1) % 2;
 
-    roll_dirac_dice(players, next_player)
+    roll_dirac_dice((players, next_player))
 }
 
 impl Solution for Day21 {
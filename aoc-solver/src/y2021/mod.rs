@@ -1,4 +1,4 @@
-use crate::solution::{Solution, Solver};
+use crate::solution::{DayInfo, Part, Solution, Solver};
 
 pub mod day01;
 pub mod day02;
@@ -21,31 +21,54 @@ pub mod day18;
 pub mod day21;
 pub mod day22;
 
+const DAYS: &[DayInfo] = &[
+    DayInfo::new(1),
+    DayInfo::new(2),
+    DayInfo::new(3),
+    DayInfo::new(4),
+    DayInfo::new(5),
+    DayInfo::new(6),
+    DayInfo::new(7),
+    DayInfo::new(8),
+    DayInfo::new(9),
+    DayInfo::new(10),
+    DayInfo::new(11),
+    DayInfo::new(12),
+    DayInfo::new(13),
+    DayInfo::new(14),
+    DayInfo::with_visualizations(15, &["chiton"]),
+    DayInfo::new(16),
+    DayInfo::new(17),
+    DayInfo::new(18),
+    DayInfo::new(21),
+    DayInfo::new(22),
+];
+
 pub struct Y2021;
 
 impl Solver for Y2021 {
-    fn run_solution(day: u8, input: Option<String>) -> Vec<String> {
+    fn run_parts(day: u8, input: Option<String>, parts: &[Part]) -> Vec<String> {
         match day {
-            1 => day01::Day01.run(input, 1, 2021),
-            2 => day02::Day02.run(input, 2, 2021),
-            3 => day03::Day03.run(input, 3, 2021),
-            4 => day04::Day04.run(input, 4, 2021),
-            5 => day05::Day05.run(input, 5, 2021),
-            6 => day06::Day06.run(input, 6, 2021),
-            7 => day07::Day07.run(input, 7, 2021),
-            8 => day08::Day08.run(input, 8, 2021),
-            9 => day09::Day09.run(input, 9, 2021),
-            10 => day10::Day10.run(input, 10, 2021),
-            11 => day11::Day11.run(input, 11, 2021),
-            12 => day12::Day12.run(input, 12, 2021),
-            13 => day13::Day13.run(input, 13, 2021),
-            14 => day14::Day14.run(input, 14, 2021),
-            15 => day15::Day15.run(input, 15, 2021),
-            16 => day16::Day16.run(input, 16, 2021),
-            17 => day17::Day17.run(input, 17, 2021),
-            18 => day18::Day18.run(input, 18, 2021),
-            21 => day21::Day21.run(input, 21, 2021),
-            22 => day22::Day22.run(input, 22, 2021),
+            1 => day01::Day01.run_parts(input, 1, 2021, parts),
+            2 => day02::Day02.run_parts(input, 2, 2021, parts),
+            3 => day03::Day03.run_parts(input, 3, 2021, parts),
+            4 => day04::Day04.run_parts(input, 4, 2021, parts),
+            5 => day05::Day05.run_parts(input, 5, 2021, parts),
+            6 => day06::Day06.run_parts(input, 6, 2021, parts),
+            7 => day07::Day07.run_parts(input, 7, 2021, parts),
+            8 => day08::Day08.run_parts(input, 8, 2021, parts),
+            9 => day09::Day09.run_parts(input, 9, 2021, parts),
+            10 => day10::Day10.run_parts(input, 10, 2021, parts),
+            11 => day11::Day11.run_parts(input, 11, 2021, parts),
+            12 => day12::Day12.run_parts(input, 12, 2021, parts),
+            13 => day13::Day13.run_parts(input, 13, 2021, parts),
+            14 => day14::Day14.run_parts(input, 14, 2021, parts),
+            15 => day15::Day15.run_parts(input, 15, 2021, parts),
+            16 => day16::Day16.run_parts(input, 16, 2021, parts),
+            17 => day17::Day17.run_parts(input, 17, 2021, parts),
+            18 => day18::Day18.run_parts(input, 18, 2021, parts),
+            21 => day21::Day21.run_parts(input, 21, 2021, parts),
+            22 => day22::Day22.run_parts(input, 22, 2021, parts),
             _ => vec![String::from("Solution not implemented (yet?)")],
         }
     }
@@ -90,4 +113,8 @@ impl Solver for Y2021 {
             _ => unimplemented!(),
         }
     }
+
+    fn days() -> &'static [DayInfo] {
+        DAYS
+    }
 }
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use crate::{
     solution::{AocError, Solution},
     utils::Coords,
+    visualization::{unknown_visualization, Frame, Frames, Visualize},
 };
 
 type Visited = HashSet<Coords<i32>>;
@@ -100,6 +101,27 @@ impl Day09 {
 
         (visited, rope)
     }
+
+    /// Renders the area around the head of the rope.
+    fn render(rope: &Rope, visited: &Visited) -> Vec<String> {
+        let head = rope[0];
+
+        (head.y - 10..head.y + 10)
+            .map(|y| {
+                (head.x - 20..head.x + 20)
+                    .map(
+                        |x| match rope.iter().position(|knot| knot.x == x && knot.y == y) {
+                            Some(0) => 'H',
+                            Some(knot) if knot == rope.len() - 1 => 'T',
+                            Some(knot) => char::from_digit(knot as u32, 10).unwrap_or('?'),
+                            None if visited.contains(&Coords { x, y }) => '#',
+                            None => '.',
+                        },
+                    )
+                    .collect()
+            })
+            .collect()
+    }
 }
 
 impl Solution for Day09 {
@@ -127,6 +149,42 @@ impl Solution for Day09 {
     }
 }
 
+impl Visualize for Day09 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "rope" {
+            return Err(unknown_visualization(name));
+        }
+
+        let directions = Self::parse(input)?;
+        let total = directions.len();
+
+        let mut rope = vec![Coords { x: 0, y: 0 }; 10];
+        let mut visited = HashSet::new();
+
+        let frames = directions
+            .into_iter()
+            .enumerate()
+            .map(move |(index, direction)| {
+                (visited, rope) = Self::simulate(
+                    vec![direction],
+                    std::mem::take(&mut rope),
+                    std::mem::take(&mut visited),
+                );
+
+                Frame::grid(
+                    format!(
+                        "Move {}/{total}, positions tail visited: {}",
+                        index + 1,
+                        visited.len()
+                    ),
+                    Self::render(&rope, &visited),
+                )
+            });
+
+        Ok(Box::new(frames))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +239,13 @@ mod tests {
             Ok(36)
         );
     }
+
+    #[test]
+    fn it_visualizes_the_rope() {
+        let frames: Vec<Frame> = Day09.visualize("rope", "R 4\nU 4\nL 3").unwrap().collect();
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].caption, "Move 3/3, positions tail visited: 1");
+        assert!(Day09.visualize("knots", "R 4").is_err());
+    }
 }
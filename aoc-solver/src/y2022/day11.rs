@@ -1,225 +1,420 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    num::ParseIntError,
-};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use itertools::Itertools;
+use num::Integer;
 
 use crate::solution::{AocError, Solution};
 
 pub struct Day11;
 
-#[derive(Default)]
-enum Argument {
-    Number(u32),
-    #[default]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
     Old,
+    Number(u64),
 }
 
-struct Monkey {
-    items: VecDeque<u32>,
-    remainders: VecDeque<HashMap<u32, u32>>,
-    operation: Box<dyn Fn(u32, u32) -> u32>,
-    argument: Argument,
-    modulus: u32,
-    next: (usize, usize),
-    activity: u64,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
 }
 
-impl Default for Monkey {
-    fn default() -> Self {
-        Monkey {
-            items: VecDeque::default(),
-            remainders: VecDeque::default(),
-            operation: Box::new(|x, y| x + y),
-            argument: Argument::default(),
-            modulus: 0,
-            next: (0, 0),
-            activity: 0,
-        }
-    }
+/// Right hand side of `new = old * 19`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub left: Operand,
+    pub operator: Operator,
+    pub right: Operand,
 }
 
-enum Parser {
-    Id,
-    StartingItems,
-    Operation,
-    TestDivisible,
-    TestTrue,
-    TestFalse,
-    End,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
+    pub id: usize,
+    pub items: Vec<u64>,
+    pub operation: Operation,
+    pub divisor: u64,
+    pub if_true: usize,
+    pub if_false: usize,
 }
 
-impl Day11 {
-    fn parse(input: &str) -> Result<Vec<Monkey>, AocError> {
-        let mut state = Parser::Id;
-
-        let mut monkeys: Vec<Monkey> = Vec::new();
-        let mut current = Monkey::default();
-
-        for line in input.lines() {
-            match state {
-                Parser::Id => state = Parser::StartingItems,
-
-                Parser::StartingItems => {
-                    current.items = line
-                        .strip_prefix("  Starting items: ")
-                        .ok_or_else(|| AocError::parse(line, "prefix"))?
-                        .split(", ")
-                        .map(|item| item.parse::<u32>())
-                        .collect::<Result<VecDeque<u32>, ParseIntError>>()
-                        .map_err(|err| AocError::parse(line, err))?;
-
-                    state = Parser::Operation;
-                }
+/// How worried to get about the items. Every inspection is followed by dividing the
+/// worry level by `relief`, which is 1 when the items are expected to get damaged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub rounds: usize,
+    pub relief: u64,
+}
 
-                Parser::Operation => {
-                    let (operator, argument): (char, &str) =
-                        serde_scan::scan!("  Operation: new = old {} {}" <- line)
-                            .map_err(|err| AocError::parse(line, err))?;
+impl Config {
+    pub const PART_1: Config = Config {
+        rounds: 20,
+        relief: 3,
+    };
 
-                    let operation: Box<dyn Fn(u32, u32) -> u32> = if operator == '*' {
-                        Box::new(|a, b| -> u32 { a * b })
-                    } else {
-                        Box::new(|a, b| -> u32 { a + b })
-                    };
+    pub const PART_2: Config = Config {
+        rounds: 10000,
+        relief: 1,
+    };
+}
 
-                    let argument = match argument {
-                        "old" => Argument::Old,
-                        number => Argument::Number(
-                            number.parse().map_err(|err| AocError::parse(number, err))?,
-                        ),
-                    };
+/// Items held by each monkey and how many items they have inspected after a round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub round: usize,
+    pub items: Vec<Vec<u64>>,
+    pub inspections: Vec<u64>,
+}
 
-                    current.operation = operation;
-                    current.argument = argument;
+/// Interpreter for the notes, keeping track of the items during the rounds
+pub struct Troop {
+    monkeys: Vec<Monkey>,
+    config: Config,
+    /// Worry levels are only kept modulo this when the operations only add and multiply
+    /// and there is no relief, as the divisibility tests don't change then. Otherwise the
+    /// worry levels are exact, and going negative or overflowing is an error.
+    modulus: Option<u64>,
+    items: Vec<VecDeque<u64>>,
+    inspections: Vec<u64>,
+    round: usize,
+}
 
-                    state = Parser::TestDivisible;
-                }
+impl Operand {
+    fn value(&self, old: u64) -> u64 {
+        match self {
+            Operand::Old => old,
+            Operand::Number(number) => *number,
+        }
+    }
+}
+
+impl Operator {
+    fn apply(&self, a: u64, b: u64) -> Option<u64> {
+        match self {
+            Operator::Add => a.checked_add(b),
+            Operator::Sub => a.checked_sub(b),
+            Operator::Mul => a.checked_mul(b),
+            Operator::Div => a.checked_div(b),
+        }
+    }
 
-                Parser::TestDivisible => {
-                    current.modulus = serde_scan::scan!("  Test: divisible by {}" <- line)
-                        .map_err(|err| AocError::parse(line, err))?;
+    fn apply_modulo(&self, a: u64, b: u64, modulus: u64) -> Option<u64> {
+        let (a, b, modulus) = (a as u128, b as u128, modulus as u128);
+        let (a, b) = (a % modulus, b % modulus);
 
-                    state = Parser::TestTrue;
-                }
+        let result = match self {
+            Operator::Add => (a + b) % modulus,
+            Operator::Mul => a * b % modulus,
+            Operator::Sub | Operator::Div => return None,
+        };
 
-                Parser::TestTrue => {
-                    current.next.1 = serde_scan::scan!("    If true: throw to monkey {}" <- line)
-                        .map_err(|err| AocError::parse(line, err))?;
+        Some(result as u64)
+    }
+}
 
-                    state = Parser::TestFalse;
-                }
+impl Operation {
+    fn apply(&self, old: u64, modulus: Option<u64>) -> Option<u64> {
+        let (a, b) = (self.left.value(old), self.right.value(old));
 
-                Parser::TestFalse => {
-                    current.next.0 = serde_scan::scan!("    If false: throw to monkey {}" <- line)
-                        .map_err(|err| AocError::parse(line, err))?;
+        match modulus {
+            Some(modulus) => self.operator.apply_modulo(a, b, modulus),
+            None => self.operator.apply(a, b),
+        }
+    }
+}
 
-                    monkeys.push(current);
-                    current = Monkey::default();
+impl Troop {
+    pub fn new(monkeys: Vec<Monkey>, config: Config) -> Result<Troop, AocError> {
+        if config.relief == 0 {
+            return Err(AocError::logic("Relief can't divide by zero"));
+        }
 
-                    state = Parser::End;
-                }
+        let is_modular = config.relief == 1
+            && monkeys
+                .iter()
+                .all(|monkey| matches!(monkey.operation.operator, Operator::Add | Operator::Mul));
+
+        let modulus = if is_modular {
+            let modulus = monkeys
+                .iter()
+                .try_fold(1_u64, |modulus, monkey| {
+                    (modulus / modulus.gcd(&monkey.divisor)).checked_mul(monkey.divisor)
+                })
+                .ok_or_else(|| AocError::logic("The least common multiple overflows"))?;
+
+            Some(modulus)
+        } else {
+            None
+        };
+
+        let items = monkeys
+            .iter()
+            .map(|monkey| monkey.items.iter().copied().collect())
+            .collect();
+
+        Ok(Troop {
+            inspections: vec![0; monkeys.len()],
+            monkeys,
+            config,
+            modulus,
+            items,
+            round: 0,
+        })
+    }
 
-                Parser::End => {
-                    state = Parser::Id;
-                }
+    pub fn round(&mut self) -> Result<(), AocError> {
+        for (i, monkey) in self.monkeys.iter().enumerate() {
+            // Monkeys never throw to themselves, so the items can be taken out first
+            let items = std::mem::take(&mut self.items[i]);
+            self.inspections[i] += items.len() as u64;
+
+            for old in items {
+                let worry_level = monkey.operation.apply(old, self.modulus).ok_or_else(|| {
+                    AocError::logic(format!(
+                        "Monkey {} can't calculate {} for {old}",
+                        monkey.id, monkey.operation
+                    ))
+                })? / self.config.relief;
+
+                let target = if worry_level % monkey.divisor == 0 {
+                    monkey.if_true
+                } else {
+                    monkey.if_false
+                };
+
+                self.items[target].push_back(worry_level);
             }
         }
 
-        Ok(monkeys)
+        self.round += 1;
+
+        Ok(())
     }
-}
 
-impl Solution for Day11 {
-    type A = u64;
-    type B = u64;
+    /// Plays the configured rounds, calling `on_round` after each of them
+    pub fn run(&mut self, mut on_round: impl FnMut(&Troop)) -> Result<(), AocError> {
+        while self.round < self.config.rounds {
+            self.round()?;
+            on_round(self);
+        }
 
-    fn default_input(&self) -> &'static str {
-        include_str!("../../../inputs/2022/day11.txt")
+        Ok(())
     }
 
-    fn part_1(&self, input: &str) -> Result<u64, AocError> {
-        let mut monkeys = Self::parse(input)?;
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            round: self.round,
+            items: self
+                .items
+                .iter()
+                .map(|items| items.iter().copied().collect())
+                .collect(),
+            inspections: self.inspections.clone(),
+        }
+    }
 
-        for _round in 1..=20 {
-            for i in 0..monkeys.len() {
-                while let Some(item) = monkeys[i].items.pop_front() {
-                    monkeys[i].activity += 1;
+    /// Product of the inspection counts of the two most active monkeys
+    pub fn monkey_business(&self) -> u64 {
+        self.inspections.iter().sorted().rev().take(2).product()
+    }
+}
 
-                    let argument = match monkeys[i].argument {
-                        Argument::Number(num) => num,
-                        Argument::Old => item,
-                    };
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Old => write!(f, "old"),
+            Operand::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
 
-                    let worry_level = (monkeys[i].operation)(item, argument) / 3;
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operator::Add => write!(f, "+"),
+            Operator::Sub => write!(f, "-"),
+            Operator::Mul => write!(f, "*"),
+            Operator::Div => write!(f, "/"),
+        }
+    }
+}
 
-                    let target = if worry_level % monkeys[i].modulus == 0 {
-                        monkeys[i].next.1
-                    } else {
-                        monkeys[i].next.0
-                    };
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.operator, self.right)
+    }
+}
 
-                    monkeys[target].items.push_back(worry_level);
-                }
+impl fmt::Display for Monkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Monkey {}:", self.id)?;
+        writeln!(f, "  Starting items: {}", self.items.iter().join(", "))?;
+        writeln!(f, "  Operation: new = {}", self.operation)?;
+        writeln!(f, "  Test: divisible by {}", self.divisor)?;
+        writeln!(f, "    If true: throw to monkey {}", self.if_true)?;
+        write!(f, "    If false: throw to monkey {}", self.if_false)
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "== After round {} ==", self.round)?;
+        for (id, (items, inspections)) in self.items.iter().zip(&self.inspections).enumerate() {
+            write!(f, "\nMonkey {id} inspected items {inspections} times")?;
+            if !items.is_empty() {
+                write!(f, ": {}", items.iter().join(", "))?;
             }
         }
 
-        let mut activity: Vec<u64> = monkeys.iter().map(|monkey| monkey.activity).collect();
-        activity.sort();
-
-        Ok(activity.iter().rev().take(2).product())
+        Ok(())
     }
+}
 
-    fn part_2(&self, input: &str) -> Result<u64, AocError> {
-        let mut monkeys = Self::parse(input)?;
-
-        // Gather list of modulus for each monkey
-        let moduli: Vec<u32> = monkeys.iter().map(|monkey| monkey.modulus).collect();
+impl FromStr for Operand {
+    type Err = AocError;
 
-        // Populate the initial item remainders for every divider
-        for monkey in monkeys.iter_mut() {
-            for item in monkey.items.iter() {
-                let mut item_remainders = HashMap::new();
-                for modulus in moduli.iter() {
-                    item_remainders.insert(*modulus, item % modulus);
-                }
-                monkey.remainders.push_back(item_remainders);
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "old" => Ok(Operand::Old),
+            number => number
+                .parse()
+                .map(Operand::Number)
+                .map_err(|err| AocError::parse(number, err)),
         }
+    }
+}
 
-        for _round in 1..=10000 {
-            for i in 0..monkeys.len() {
-                while let Some(mut remainders) = monkeys[i].remainders.pop_front() {
-                    monkeys[i].activity += 1;
+impl FromStr for Operator {
+    type Err = AocError;
 
-                    // Recalculate the item's remainders for each monkey's modulus
-                    for (modulus, remainder) in remainders.iter_mut() {
-                        let argument = match monkeys[i].argument {
-                            Argument::Number(num) => num,
-                            Argument::Old => *remainder,
-                        };
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Operator::Add),
+            "-" => Ok(Operator::Sub),
+            "*" => Ok(Operator::Mul),
+            "/" => Ok(Operator::Div),
+            unknown => Err(AocError::parse(unknown, "unknown operator")),
+        }
+    }
+}
 
-                        *remainder = (monkeys[i].operation)(*remainder, argument) % modulus;
-                    }
+impl FromStr for Operation {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            [left, operator, right] => Ok(Operation {
+                left: left.parse()?,
+                operator: operator.parse()?,
+                right: right.parse()?,
+            }),
+            _ => Err(AocError::parse(s, "expected a binary operation")),
+        }
+    }
+}
 
-                    let remainder = *remainders
-                        .get(&monkeys[i].modulus)
-                        .ok_or_else(|| AocError::logic("unknown remainder"))?;
+impl FromStr for Monkey {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim);
+        let mut field = |prefix: &str| {
+            let line = lines
+                .next()
+                .ok_or_else(|| AocError::parse(s, format!("missing \"{prefix}\"")))?;
+
+            line.strip_prefix(prefix)
+                .map(str::trim)
+                .ok_or_else(|| AocError::parse(line, format!("expected \"{prefix}\"")))
+        };
+
+        let number = |value: &str| value.parse().map_err(|err| AocError::parse(value, err));
+
+        let id = field("Monkey")?;
+        let id = number(id.strip_suffix(':').unwrap_or(id))?;
+
+        let items = field("Starting items:")?;
+        let items = if items.is_empty() {
+            Vec::new()
+        } else {
+            items
+                .split(',')
+                .map(|item| number(item.trim()))
+                .collect::<Result<_, _>>()?
+        };
+
+        let operation = field("Operation: new =")?.parse()?;
+
+        let divisor = number(field("Test: divisible by")?)?;
+        if divisor == 0 {
+            return Err(AocError::parse(s, "divisibility test by zero"));
+        }
 
-                    let target = if remainder == 0 {
-                        monkeys[i].next.1
-                    } else {
-                        monkeys[i].next.0
-                    };
+        let if_true = number(field("If true: throw to monkey")?)? as usize;
+        let if_false = number(field("If false: throw to monkey")?)? as usize;
+
+        Ok(Monkey {
+            id: id as usize,
+            items,
+            operation,
+            divisor,
+            if_true,
+            if_false,
+        })
+    }
+}
 
-                    monkeys[target].remainders.push_back(remainders);
+impl Day11 {
+    pub fn parse(input: &str) -> Result<Vec<Monkey>, AocError> {
+        let monkeys = input
+            .trim()
+            .split("\n\n")
+            .map(str::parse)
+            .collect::<Result<Vec<Monkey>, _>>()?;
+
+        for (index, monkey) in monkeys.iter().enumerate() {
+            if monkey.id != index {
+                return Err(AocError::parse(monkey.id, "monkeys are not in order"));
+            }
+
+            for target in [monkey.if_true, monkey.if_false] {
+                if target == monkey.id {
+                    return Err(AocError::parse(monkey.id, "monkey throws to itself"));
+                }
+                if target >= monkeys.len() {
+                    return Err(AocError::parse(target, "no such monkey"));
                 }
             }
         }
 
-        let mut activity: Vec<u64> = monkeys.iter().map(|monkey| monkey.activity).collect();
-        activity.sort();
+        Ok(monkeys)
+    }
+
+    fn monkey_business(input: &str, config: Config) -> Result<u64, AocError> {
+        let mut troop = Troop::new(Self::parse(input)?, config)?;
+        troop.run(|_| {})?;
+
+        Ok(troop.monkey_business())
+    }
+}
+
+impl Solution for Day11 {
+    type A = u64;
+    type B = u64;
+
+    fn default_input(&self) -> &'static str {
+        include_str!("../../../inputs/2022/day11.txt")
+    }
+
+    fn part_1(&self, input: &str) -> Result<u64, AocError> {
+        Self::monkey_business(input, Config::PART_1)
+    }
 
-        Ok(activity.iter().rev().take(2).product())
+    fn part_2(&self, input: &str) -> Result<u64, AocError> {
+        Self::monkey_business(input, Config::PART_2)
     }
 }
 
@@ -227,6 +422,127 @@ impl Solution for Day11 {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    fn run_rounds(input: &str, config: Config) -> Result<Vec<Snapshot>, AocError> {
+        let mut snapshots = Vec::new();
+        let mut troop = Troop::new(Day11::parse(input)?, config)?;
+        troop.run(|troop| snapshots.push(troop.snapshot()))?;
+
+        Ok(snapshots)
+    }
+
+    #[test]
+    fn it_parses_and_prints_notes() {
+        let monkeys = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(monkeys.len(), 4);
+        assert_eq!(
+            monkeys[2].operation,
+            Operation {
+                left: Operand::Old,
+                operator: Operator::Mul,
+                right: Operand::Old
+            }
+        );
+        assert_eq!(monkeys.iter().join("\n\n"), EXAMPLE);
+    }
+
+    #[test]
+    fn it_rejects_invalid_notes() {
+        let throws_to_itself =
+            EXAMPLE.replace("If true: throw to monkey 2", "If true: throw to monkey 0");
+        assert!(Day11::parse(&throws_to_itself).is_err());
+        assert!(Day11::parse(&EXAMPLE.replace("monkey 3", "monkey 4")).is_err());
+        assert!(Day11::parse(&EXAMPLE.replace("old + 6", "old ^ 6")).is_err());
+        assert!(Day11::parse(&EXAMPLE.replace("old + 6", "old +")).is_err());
+        assert!(Day11::parse(&EXAMPLE.replace("by 19", "by 0")).is_err());
+        assert!(Day11::parse(&EXAMPLE.replace("Monkey 1:", "Monkey 5:")).is_err());
+    }
+
+    #[test]
+    fn it_takes_snapshots_of_rounds() {
+        let snapshots = run_rounds(EXAMPLE, Config::PART_1).unwrap();
+        assert_eq!(snapshots.len(), 20);
+        assert_eq!(
+            snapshots[0].to_string(),
+            "== After round 1 ==\n\
+             Monkey 0 inspected items 2 times: 20, 23, 27, 26\n\
+             Monkey 1 inspected items 4 times: 2080, 25, 167, 207, 401, 1046\n\
+             Monkey 2 inspected items 3 times\n\
+             Monkey 3 inspected items 5 times"
+        );
+        assert_eq!(snapshots[19].items[0], vec![10, 12, 14, 26, 34]);
+        assert_eq!(snapshots[19].inspections, vec![101, 95, 7, 105]);
+
+        let snapshots = run_rounds(EXAMPLE, Config::PART_2).unwrap();
+        assert_eq!(snapshots[0].inspections, vec![2, 4, 3, 6]);
+        assert_eq!(snapshots[19].inspections, vec![99, 97, 8, 103]);
+        assert_eq!(snapshots[999].inspections, vec![5204, 4792, 199, 5192]);
+    }
+
+    #[test]
+    fn it_interprets_other_operations() {
+        let input = EXAMPLE
+            .replace("old * 19", "old - 1")
+            .replace("old + 3", "old / 2");
+
+        let config = Config {
+            rounds: 3,
+            relief: 1,
+        };
+        let snapshots = run_rounds(&input, config).unwrap();
+        assert_eq!(snapshots[0].items[0], vec![60, 71, 81, 80]);
+        assert_eq!(snapshots[0].items[1], vec![37, 39, 48, 3120, 1800, 4704]);
+        assert_eq!(snapshots[0].inspections, vec![2, 4, 3, 6]);
+
+        // Subtraction is exact with or without relief
+        let underflow = EXAMPLE.replace("old * 19", "1 - old");
+        for config in [Config::PART_1, Config::PART_2] {
+            assert_eq!(
+                run_rounds(&underflow, config),
+                Err(AocError::logic("Monkey 0 can't calculate 1 - old for 79"))
+            );
+        }
+
+        let large_divisor = EXAMPLE.replace("by 23", "by 9223372036854775808");
+        assert_eq!(
+            run_rounds(&large_divisor, Config::PART_2),
+            Err(AocError::logic("The least common multiple overflows"))
+        );
+
+        let config = Config {
+            rounds: 1,
+            relief: 0,
+        };
+        assert!(run_rounds(EXAMPLE, config).is_err());
+    }
+
     #[test]
     fn it_solves_part1() {
         assert_eq!(
@@ -1,6 +1,6 @@
-use std::{cmp::Ordering, collections::BinaryHeap};
-
 use crate::solution::{AocError, Solution};
+use crate::utils::search::{self, Path, SearchEvent};
+use crate::visualization::{search_frames, unknown_visualization, Frames, Visualize};
 
 const NEIGHBOURS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
 
@@ -8,25 +8,6 @@ type Coord = (usize, usize);
 
 pub struct Day12;
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct Search {
-    coords: Coord,
-    elevation: u8,
-    distance: u32,
-}
-
-impl Ord for Search {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.distance.cmp(&self.distance)
-    }
-}
-
-impl PartialOrd for Search {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 impl Day12 {
     fn parse(input: &str) -> Result<(Vec<Vec<u8>>, Coord, Coord), AocError> {
         let mut grid = Vec::new();
@@ -52,62 +33,46 @@ impl Day12 {
     }
 }
 
-// Copied my 2021/day15 solution, seems to work here too
-fn dijkstra(grid: Vec<Vec<u8>>, source: Coord, target: Coord) -> Option<u32> {
-    let mut dist: Vec<Vec<u32>> = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
-    let mut heap: BinaryHeap<Search> = BinaryHeap::new();
-
+fn dijkstra<F>(
+    grid: &[Vec<u8>],
+    sources: Vec<Coord>,
+    target: Coord,
+    on_event: F,
+) -> Option<Path<Coord>>
+where
+    F: FnMut(SearchEvent<&Coord>),
+{
     let width = grid[0].len() as i32;
     let height = grid.len() as i32;
 
-    dist[source.1][source.0] = 0;
-    heap.push(Search {
-        elevation: grid[source.1][source.0],
-        distance: 0,
-        coords: source,
-    });
-
-    while let Some(Search {
-        coords,
-        elevation,
-        distance,
-    }) = heap.pop()
-    {
-        if coords == target {
-            return Some(dist[coords.1][coords.0]);
-        }
-
-        // we've already found a better way
-        if distance > dist[coords.1][coords.0] {
-            continue;
-        }
+    let neighbours = |&coords: &Coord, _| {
+        let elevation = grid[coords.1][coords.0];
 
-        for offset in NEIGHBOURS {
+        NEIGHBOURS.iter().filter_map(move |offset| {
             let c: (i32, i32) = (coords.0 as i32 + offset.0, coords.1 as i32 + offset.1);
-            if c.0 >= 0 && c.1 >= 0 && c.0 < width && c.1 < height {
-                let neighbour = (c.0 as usize, c.1 as usize);
-                if grid[neighbour.1][neighbour.0] > elevation
-                    && grid[neighbour.1][neighbour.0] - elevation > 1
-                {
-                    // We can only climb at most one step higher, skip this path
-                    continue;
-                }
+            if c.0 < 0 || c.1 < 0 || c.0 >= width || c.1 >= height {
+                return None;
+            }
 
-                let next = Search {
-                    distance: distance + 1,
-                    elevation: grid[neighbour.1][neighbour.0],
-                    coords: neighbour,
-                };
+            let neighbour = (c.0 as usize, c.1 as usize);
 
-                if next.distance < dist[neighbour.1][neighbour.0] {
-                    heap.push(next);
-                    dist[neighbour.1][neighbour.0] = next.distance;
-                }
+            // We can only climb at most one step higher
+            if grid[neighbour.1][neighbour.0] > elevation + 1 {
+                return None;
             }
-        }
-    }
 
-    None
+            Some((neighbour, 1))
+        })
+    };
+
+    search::dijkstra(sources, neighbours, |coords| *coords == target, on_event)
+}
+
+fn lowest_points(grid: &[Vec<u8>]) -> Vec<Coord> {
+    (0..grid.len())
+        .flat_map(|y| (0..grid[y].len()).map(move |x| (x, y)))
+        .filter(|(x, y)| grid[*y][*x] == 0)
+        .collect()
 }
 
 impl Solution for Day12 {
@@ -120,34 +85,37 @@ impl Solution for Day12 {
 
     fn part_1(&self, input: &str) -> Result<u32, AocError> {
         let (grid, source, target) = Self::parse(input)?;
-        dijkstra(grid, source, target).ok_or_else(|| AocError::logic("no path"))
+        dijkstra(&grid, vec![source], target, |_| {})
+            .map(|path| path.cost)
+            .ok_or_else(|| AocError::logic("no path"))
     }
 
     fn part_2(&self, input: &str) -> Result<u32, AocError> {
         let (grid, _, target) = Self::parse(input)?;
 
-        let mut shortest = None;
-
-        for y in 0..grid.len() {
-            for x in 0..grid[y].len() {
-                if grid[y][x] == 0 {
-                    if let Some(distance) = dijkstra(grid.clone(), (x, y), target) {
-                        match shortest {
-                            None => {
-                                shortest = Some(distance);
-                            }
-                            Some(previous) => {
-                                if previous > distance {
-                                    shortest = Some(distance);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        // Searching from all of the lowest points at once finds the closest one
+        dijkstra(&grid, lowest_points(&grid), target, |_| {})
+            .map(|path| path.cost)
+            .ok_or_else(|| AocError::logic("no path"))
+    }
+}
+
+impl Visualize for Day12 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "hill" {
+            return Err(unknown_visualization(name));
         }
 
-        shortest.ok_or_else(|| AocError::logic("no path"))
+        let (grid, source, target) = Self::parse(input)?;
+        let mut events = Vec::new();
+
+        let path = dijkstra(&grid, vec![source], target, |event| {
+            events.push(event.map(|coords| *coords))
+        });
+
+        let tiles = input.lines().map(|line| line.chars().collect()).collect();
+
+        Ok(search_frames(tiles, events, path))
     }
 }
 
@@ -182,4 +150,33 @@ mod tests {
             Ok(29)
         );
     }
+
+    #[test]
+    fn it_visualizes_the_climb() {
+        let frames: Vec<_> = Day12
+            .visualize(
+                "hill",
+                "Sabqponm\n\
+                 abcryxxl\n\
+                 accszExk\n\
+                 acctuvwj\n\
+                 abdefghi",
+            )
+            .unwrap()
+            .collect();
+
+        assert_eq!(
+            frames.last(),
+            Some(&crate::visualization::Frame::grid(
+                "Lowest cost: 31",
+                vec![
+                    "########".into(),
+                    "ab######".into(),
+                    "ac######".into(),
+                    "ac######".into(),
+                    "ab######".into(),
+                ]
+            ))
+        );
+    }
 }
@@ -3,9 +3,13 @@ use std::collections::{BTreeSet, HashMap};
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 pub struct Day14;
 
+/// Roughly how many frames the falling sand is split into
+const VISUALIZED_FRAMES: usize = 200;
+
 impl Day14 {
     fn parse(input: &str) -> Result<HashMap<i32, BTreeSet<i32>>, AocError> {
         let mut world: HashMap<i32, BTreeSet<i32>> = HashMap::new();
@@ -35,7 +39,12 @@ impl Day14 {
         Ok(world)
     }
 
-    fn simulate(mut world: HashMap<i32, BTreeSet<i32>>) -> usize {
+    /// Drops sand until it flows into the void or blocks the source. Every grain of sand
+    /// that comes to rest is reported to `on_rest`.
+    fn simulate<F>(mut world: HashMap<i32, BTreeSet<i32>>, mut on_rest: F) -> usize
+    where
+        F: FnMut((i32, i32)),
+    {
         let mut sand_count = 0;
         let source = (500, 0);
 
@@ -79,6 +88,7 @@ impl Day14 {
                         .or_default();
 
                     sand_count += 1;
+                    on_rest(sand);
 
                     // Sand blocks the source
                     if sand == source {
@@ -106,7 +116,7 @@ impl Solution for Day14 {
     fn part_1(&self, input: &str) -> Result<usize, AocError> {
         let world = Day14::parse(input)?;
 
-        Ok(Day14::simulate(world))
+        Ok(Day14::simulate(world, |_| {}))
     }
 
     fn part_2(&self, input: &str) -> Result<usize, AocError> {
@@ -122,7 +132,60 @@ impl Solution for Day14 {
             world.entry(x).or_default().insert(floor_level);
         }
 
-        Ok(Day14::simulate(world))
+        Ok(Day14::simulate(world, |_| {}))
+    }
+}
+
+impl Visualize for Day14 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "sand" {
+            return Err(unknown_visualization(name));
+        }
+
+        let world = Day14::parse(input)?;
+
+        let rocks: Vec<(i32, i32)> = world
+            .iter()
+            .flat_map(|(x, column)| column.iter().map(|y| (*x, *y)))
+            .collect();
+
+        let (min_x, max_x) = rocks
+            .iter()
+            .map(|(x, _)| *x)
+            .minmax()
+            .into_option()
+            .ok_or_else(|| AocError::logic("no rocks"))?;
+        let max_y = rocks.iter().map(|(_, y)| *y).max().unwrap_or(0);
+
+        // One tile of air around the rocks, sand falls off the edges into the void
+        let (left, width, height) = (min_x - 1, (max_x - min_x + 3) as usize, max_y as usize + 2);
+
+        let mut grid = vec![vec!['.'; width]; height];
+        grid[0][(500 - left) as usize] = '+';
+        for (x, y) in rocks {
+            grid[y as usize][(x - left) as usize] = '#';
+        }
+
+        let mut sand = Vec::new();
+        Day14::simulate(world, |grain| sand.push(grain));
+
+        let chunk_size = sand.len().div_ceil(VISUALIZED_FRAMES).max(1);
+        let chunks: Vec<Vec<(i32, i32)>> = sand.chunks(chunk_size).map(|c| c.to_vec()).collect();
+        let mut resting = 0;
+
+        let frames = chunks.into_iter().map(move |chunk| {
+            resting += chunk.len();
+            for (x, y) in chunk {
+                grid[y as usize][(x - left) as usize] = 'o';
+            }
+
+            Frame::grid(
+                format!("{resting} units of sand at rest"),
+                grid.iter().map(|row| row.iter().collect()).collect(),
+            )
+        });
+
+        Ok(Box::new(frames))
     }
 }
 
@@ -151,4 +214,37 @@ mod tests {
             Ok(93)
         );
     }
+
+    #[test]
+    fn it_visualizes_the_sand() {
+        let frames: Vec<_> = Day14
+            .visualize(
+                "sand",
+                "498,4 -> 498,6 -> 496,6\n\
+                 503,4 -> 502,4 -> 502,9 -> 494,9",
+            )
+            .unwrap()
+            .collect();
+
+        assert_eq!(frames.len(), 24);
+        assert_eq!(
+            frames.last(),
+            Some(&Frame::grid(
+                "24 units of sand at rest",
+                vec![
+                    ".......+....".into(),
+                    "............".into(),
+                    ".......o....".into(),
+                    "......ooo...".into(),
+                    ".....#ooo##.".into(),
+                    "....o#ooo#..".into(),
+                    "...###ooo#..".into(),
+                    ".....oooo#..".into(),
+                    "..o.ooooo#..".into(),
+                    ".#########..".into(),
+                    "............".into(),
+                ]
+            ))
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::{BTreeSet, HashMap};
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 const SHAPES: [&[(i64, i64)]; 5] = [
     &[(0, 0), (1, 0), (2, 0), (3, 0)],         // "horizontal line"
@@ -12,6 +13,10 @@ const SHAPES: [&[(i64, i64)]; 5] = [
 
 const CHAMBER_WIDTH: i64 = 7;
 
+/// How many rocks are visualized, and how many rows from the top of the chamber are shown
+const VISUALIZED_ROCKS: u64 = 500;
+const VISUALIZED_ROWS: i64 = 40;
+
 type Chamber = BTreeSet<(i64, i64)>;
 
 enum Direction {
@@ -74,7 +79,12 @@ impl Rock {
 pub struct Day17;
 
 impl Day17 {
-    fn simulate(input: &str, count: u64) -> u64 {
+    /// Drops `count` rocks into the chamber and returns the height of the tower. The chamber
+    /// is reported to `on_rest` after each rock has come to rest, with the pixels of the rock.
+    fn simulate<F>(input: &str, count: u64, mut on_rest: F) -> u64
+    where
+        F: FnMut(&Chamber, &[(i64, i64)]),
+    {
         let mut rocks = 0;
         let mut total_height = 0;
 
@@ -98,8 +108,9 @@ impl Day17 {
 
             if !rock.move_direction(Direction::Down, &chamber) {
                 // Hit the bottom
-                for pixel in rock.pixels() {
-                    chamber.insert(pixel);
+                let mut landed = rock.pixels();
+                for pixel in landed.iter() {
+                    chamber.insert(*pixel);
                 }
 
                 // Find the lowest spots from every column and get rid of everything else
@@ -123,6 +134,10 @@ impl Day17 {
 
                     total_height += cutoff as u64;
 
+                    for pixel in landed.iter_mut() {
+                        pixel.1 -= cutoff;
+                    }
+
                     if !use_cache {
                         if let Some((previous_rocks, previous_height)) =
                             cache.insert((index, chamber.clone()), (rocks, total_height))
@@ -139,6 +154,7 @@ impl Day17 {
                 }
 
                 let height = *chamber.iter().map(|(_, y)| y).max().unwrap_or(&0);
+                on_rest(&chamber, &landed);
 
                 if rocks + 1 >= count {
                     return total_height + height as u64 + 1;
@@ -152,6 +168,29 @@ impl Day17 {
     }
 }
 
+fn render(chamber: &Chamber, landed: &[(i64, i64)]) -> Vec<String> {
+    let top = chamber.iter().map(|(_, y)| *y).max().unwrap_or(0);
+
+    (top.saturating_sub(VISUALIZED_ROWS - 1).max(0)..=top)
+        .rev()
+        .map(|y| {
+            let row: String = (0..CHAMBER_WIDTH)
+                .map(|x| {
+                    if landed.contains(&(x, y)) {
+                        '@'
+                    } else if chamber.contains(&(x, y)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            format!("|{row}|")
+        })
+        .collect()
+}
+
 impl Solution for Day17 {
     type A = u64;
     type B = u64;
@@ -161,11 +200,37 @@ impl Solution for Day17 {
     }
 
     fn part_1(&self, input: &str) -> Result<u64, AocError> {
-        Ok(Day17::simulate(input, 2022))
+        Ok(Day17::simulate(input, 2022, |_, _| {}))
     }
 
     fn part_2(&self, input: &str) -> Result<u64, AocError> {
-        Ok(Day17::simulate(input, 1000000000000))
+        Ok(Day17::simulate(input, 1000000000000, |_, _| {}))
+    }
+}
+
+impl Visualize for Day17 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "chamber" {
+            return Err(unknown_visualization(name));
+        }
+
+        let input = input.trim();
+        if input.is_empty() || input.chars().any(|c| c != '<' && c != '>') {
+            return Err(AocError::parse(input, "Expected only jets of < and >"));
+        }
+
+        let mut frames = Vec::new();
+        let mut rocks = 0;
+
+        Day17::simulate(input, VISUALIZED_ROCKS, |chamber, landed| {
+            rocks += 1;
+            frames.push(Frame::grid(
+                format!("{rocks} rocks have stopped"),
+                render(chamber, landed),
+            ));
+        });
+
+        Ok(Box::new(frames.into_iter()))
     }
 }
 
@@ -184,4 +249,25 @@ mod tests {
     fn it_solves_part2() {
         assert_eq!(Day17.part_2(INPUT), Ok(1514285714288));
     }
+
+    #[test]
+    fn it_visualizes_the_chamber() {
+        let frames: Vec<_> = Day17.visualize("chamber", INPUT).unwrap().take(2).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Frame::grid("1 rocks have stopped", vec!["|..@@@@.|".into()]),
+                Frame::grid(
+                    "2 rocks have stopped",
+                    vec![
+                        "|...@...|".into(),
+                        "|..@@@..|".into(),
+                        "|...@...|".into(),
+                        "|..####.|".into(),
+                    ]
+                ),
+            ]
+        );
+    }
 }
@@ -2,6 +2,7 @@ use serde_scan::ScanError;
 use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize, Voxel};
 
 type Point = (i32, i32, i32);
 type Bounds = ((i32, i32), (i32, i32), (i32, i32));
@@ -177,9 +178,66 @@ impl Solution for Day18 {
     }
 }
 
+impl Visualize for Day18 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        let (labeled, bounds) = Day18::label_cubes(Day18::parse(input)?);
+
+        match name {
+            // Slices of the droplet, from bottom to top
+            "lava" => {
+                let frames = (bounds.2 .0..=bounds.2 .1).map(move |z| {
+                    let rows = (bounds.1 .0..bounds.1 .1)
+                        .map(|y| {
+                            (bounds.0 .0..bounds.0 .1)
+                                .map(|x| match labeled.get(&(x, y, z)) {
+                                    Some(Label::Exterior) => '.',
+                                    Some(Label::Lava) => '#',
+                                    Some(Label::Pocket) => 'x',
+                                    None => '?',
+                                })
+                                .collect()
+                        })
+                        .collect();
+
+                    Frame::grid(format!("Z: {z}"), rows)
+                });
+
+                Ok(Box::new(frames))
+            }
+            // The droplet built up layer by layer, revealing the air pockets inside
+            "droplet" => {
+                let mut voxels: Vec<Voxel> = labeled
+                    .into_iter()
+                    .filter_map(|(position, label)| match label {
+                        Label::Lava => Some(Voxel {
+                            position,
+                            color: "#ff9933".into(),
+                        }),
+                        Label::Pocket => Some(Voxel {
+                            position,
+                            color: "#66ccff".into(),
+                        }),
+                        Label::Exterior => None,
+                    })
+                    .collect();
+                voxels.sort_by_key(|voxel| (voxel.position.2, voxel.position.1, voxel.position.0));
+
+                let frames = (bounds.2 .0..=bounds.2 .1).map(move |z| {
+                    let layers = voxels.partition_point(|voxel| voxel.position.2 <= z);
+                    Frame::voxels(format!("Z: {z}"), voxels[..layers].to_vec())
+                });
+
+                Ok(Box::new(frames))
+            }
+            _ => Err(unknown_visualization(name)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualization::Content;
 
     const INPUT: &str = "2,2,2\n\
         1,2,2\n\
@@ -209,4 +267,20 @@ mod tests {
     fn it_solves_part2() {
         assert_eq!(Day18.part_2(INPUT), Ok(58));
     }
+
+    #[test]
+    fn it_builds_the_droplet_from_voxels() {
+        let frames: Vec<Frame> = Day18.visualize("droplet", INPUT).unwrap().collect();
+
+        let voxel_counts: Vec<usize> = frames
+            .iter()
+            .map(|frame| match &frame.content {
+                Content::Voxels(voxels) => voxels.len(),
+                _ => 0,
+            })
+            .collect();
+
+        // The single air pocket at 2,2,5 shows up with the layer of lava around it
+        assert_eq!(voxel_counts, vec![0, 1, 6, 7, 8, 13, 14, 14]);
+    }
 }
@@ -1,21 +1,192 @@
 use crate::solution::{AocError, Solution};
-use num::complex::Complex;
-use std::collections::HashMap;
+use crate::utils::linalg::{self, LinearExpr};
+use num::{BigRational, Zero};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 type Operations<'a> = HashMap<&'a str, Operation<'a>>;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
 #[derive(Clone, Debug)]
 enum Operation<'a> {
-    Value(Complex<f64>),
+    Value(BigRational),
     Equation {
         left: &'a str,
         right: &'a str,
-        op: fn(Complex<f64>, Complex<f64>) -> Complex<f64>,
+        op: Operator,
     },
 }
 
+/// Expression of the numbers yelled by the monkeys, with the constant parts folded
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expr {
+    Constant(BigRational),
+    Unknown,
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+/// Equation where both sides should yell the same number
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Equation {
+    left: Expr,
+    right: Expr,
+}
+
 pub struct Day21;
 
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Operator::Add => '+',
+            Operator::Sub => '-',
+            Operator::Mul => '*',
+            Operator::Div => '/',
+        };
+
+        write!(f, "{symbol}")
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Constant(value) if value.is_integer() => write!(f, "{value}"),
+            Expr::Constant(value) => write!(f, "({value})"),
+            Expr::Unknown => write!(f, "x"),
+            Expr::Binary(left, op, right) => {
+                for (index, operand) in [left, right].into_iter().enumerate() {
+                    if index == 1 {
+                        write!(f, " {op} ")?;
+                    }
+
+                    match **operand {
+                        Expr::Binary(..) => write!(f, "({operand})")?,
+                        _ => write!(f, "{operand}")?,
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.left, self.right)
+    }
+}
+
+impl Expr {
+    /// Combines the expressions, calculating the result right away if both are constants
+    fn apply(left: Expr, op: Operator, right: Expr) -> Result<Expr, AocError> {
+        match (left, right) {
+            (Expr::Constant(left), Expr::Constant(right)) => {
+                let value = match op {
+                    Operator::Add => left + right,
+                    Operator::Sub => left - right,
+                    Operator::Mul => left * right,
+                    Operator::Div if right.is_zero() => {
+                        return Err(AocError::logic(format!("{left} / 0")))
+                    }
+                    Operator::Div => left / right,
+                };
+
+                Ok(Expr::Constant(value))
+            }
+            (left, right) => Ok(Expr::Binary(Box::new(left), op, Box::new(right))),
+        }
+    }
+
+    fn unknowns(&self) -> usize {
+        match self {
+            Expr::Constant(_) => 0,
+            Expr::Unknown => 1,
+            Expr::Binary(left, _, right) => left.unknowns() + right.unknowns(),
+        }
+    }
+
+    fn to_linear(&self) -> Result<LinearExpr, AocError> {
+        match self {
+            Expr::Constant(value) => Ok(LinearExpr::constant(value.clone())),
+            Expr::Unknown => Ok(LinearExpr::unknown()),
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (left.to_linear()?, right.to_linear()?);
+
+                match op {
+                    Operator::Add => Ok(&left + &right),
+                    Operator::Sub => Ok(&left - &right),
+                    Operator::Mul => left.mul(&right),
+                    Operator::Div => left.div(&right),
+                }
+            }
+        }
+    }
+}
+
+impl Equation {
+    /// Solves the unknown by undoing the operations around it one at a time. If the unknown
+    /// appears more than once the equation has to be linear to be solved.
+    fn solve(&self) -> Result<BigRational, AocError> {
+        let (mut expr, mut value) = match (&self.left, &self.right) {
+            (expr, Expr::Constant(value)) | (Expr::Constant(value), expr)
+                if expr.unknowns() == 1 =>
+            {
+                (expr, value.clone())
+            }
+            (left, right) if left.unknowns() + right.unknowns() == 0 => {
+                return Err(AocError::logic(format!("{self} has no unknown")))
+            }
+            (left, right) => return left.to_linear()?.solve(&right.to_linear()?),
+        };
+
+        let ambiguous = || AocError::logic(format!("{self} has no unique solution"));
+
+        loop {
+            let (left, op, right) = match expr {
+                Expr::Unknown => return Ok(value),
+                Expr::Binary(left, op, right) => (left, op, right),
+                Expr::Constant(_) => unreachable!("The unknown is on this side"),
+            };
+
+            value = match (&**left, op, &**right) {
+                (_, Operator::Mul, Expr::Constant(c)) | (Expr::Constant(c), Operator::Mul, _)
+                    if c.is_zero() =>
+                {
+                    return Err(ambiguous())
+                }
+                (_, Operator::Div, Expr::Constant(c)) if c.is_zero() => {
+                    return Err(AocError::logic(format!("{self} divides by zero")))
+                }
+                // c / x = value has no single solution when either side is zero
+                (Expr::Constant(c), Operator::Div, _) if c.is_zero() || value.is_zero() => {
+                    return Err(ambiguous())
+                }
+                (_, Operator::Add, Expr::Constant(c)) | (Expr::Constant(c), Operator::Add, _) => {
+                    value - c
+                }
+                (_, Operator::Sub, Expr::Constant(c)) => value + c,
+                (Expr::Constant(c), Operator::Sub, _) => c - value,
+                (_, Operator::Mul, Expr::Constant(c)) | (Expr::Constant(c), Operator::Mul, _) => {
+                    value / c
+                }
+                (_, Operator::Div, Expr::Constant(c)) => value * c,
+                (Expr::Constant(c), Operator::Div, _) => c / value,
+                _ => unreachable!("The unknown appears only once"),
+            };
+
+            expr = if left.unknowns() == 1 { left } else { right };
+        }
+    }
+}
+
 impl Day21 {
     fn parse(input: &str) -> Result<Operations, AocError> {
         let mut monkeys = HashMap::new();
@@ -27,20 +198,20 @@ impl Day21 {
 
             let operation = if !operation.contains(' ') {
                 let value = operation
-                    .parse::<f64>()
+                    .parse::<BigRational>()
                     .map_err(|err| AocError::parse(operation, err))?;
 
-                Operation::Value(Complex::new(value, 0.0))
+                Operation::Value(value)
             } else {
                 let mut iter = operation.split_ascii_whitespace();
 
                 match (iter.next(), iter.next(), iter.next()) {
                     (Some(left), Some(operator), Some(right)) => {
                         let op = match operator {
-                            "+" => |a, b| a + b,
-                            "-" => |a, b| a - b,
-                            "*" => |a, b| a * b,
-                            "/" => |a, b| a / b,
+                            "+" => Operator::Add,
+                            "-" => Operator::Sub,
+                            "*" => Operator::Mul,
+                            "/" => Operator::Div,
                             _ => return Err(AocError::parse(operator, "unknown operator")),
                         };
                         Operation::Equation { left, right, op }
@@ -55,26 +226,53 @@ impl Day21 {
         Ok(monkeys)
     }
 
-    fn reduce(mut operations: Operations<'_>) -> Operations<'_> {
-        let mut is_reduced = false;
+    /// Builds the expression of the number the monkey yells, with `unknown` as the unknown
+    fn expr<'a>(
+        operations: &Operations<'a>,
+        name: &'a str,
+        unknown: Option<&str>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Result<Expr, AocError> {
+        if unknown == Some(name) {
+            return Ok(Expr::Unknown);
+        }
 
-        while !is_reduced {
-            is_reduced = true;
+        if !visiting.insert(name) {
+            return Err(AocError::logic(format!("{name} depends on itself")));
+        }
 
-            let keys: Vec<_> = operations.keys().cloned().collect();
-            for name in keys {
-                if let Some(Operation::Equation { left, right, op }) = operations.get(&name) {
-                    if let (Some(Operation::Value(left_val)), Some(Operation::Value(right_val))) =
-                        (operations.get(*left), operations.get(*right))
-                    {
-                        operations.insert(name, Operation::Value(op(*left_val, *right_val)));
-                        is_reduced = false;
-                    }
-                };
+        let expr = match operations.get(name) {
+            Some(Operation::Value(value)) => Expr::Constant(value.clone()),
+            Some(Operation::Equation { left, right, op }) => {
+                let left = Day21::expr(operations, left, unknown, visiting)?;
+                let right = Day21::expr(operations, right, unknown, visiting)?;
+                Expr::apply(left, *op, right)?
             }
+            None => return Err(AocError::logic(format!("no monkey {name}"))),
+        };
+
+        visiting.remove(name);
+
+        Ok(expr)
+    }
+
+    /// Equation of the numbers that `root` compares, with `humn` as the unknown
+    fn equation(operations: &Operations) -> Result<Equation, AocError> {
+        if !operations.contains_key("humn") {
+            return Err(AocError::logic("no humn"));
         }
 
-        operations
+        let (left, right) = match operations.get("root") {
+            Some(Operation::Equation { left, right, .. }) => (*left, *right),
+            _ => return Err(AocError::logic("no root")),
+        };
+
+        let mut visiting = HashSet::from(["root"]);
+
+        Ok(Equation {
+            left: Day21::expr(operations, left, Some("humn"), &mut visiting)?,
+            right: Day21::expr(operations, right, Some("humn"), &mut visiting)?,
+        })
     }
 }
 
@@ -88,47 +286,18 @@ impl Solution for Day21 {
 
     fn part_1(&self, input: &str) -> Result<i64, AocError> {
         let monkeys = Day21::parse(input)?;
-        let reduced = Day21::reduce(monkeys);
 
-        match reduced.get("root") {
-            Some(Operation::Value(value)) => Ok(value.re as i64),
+        match Day21::expr(&monkeys, "root", None, &mut HashSet::new())? {
+            Expr::Constant(value) => linalg::to_integer(&value),
             _ => Err(AocError::logic("impossible to solve")),
         }
     }
 
     fn part_2(&self, input: &str) -> Result<i64, AocError> {
-        let mut monkeys = Day21::parse(input)?;
-
-        match monkeys.get_mut("humn") {
-            Some(Operation::Value(humn)) => {
-                // Set humn to "0 + i" and solve the "i" later from the reduced equation
-                *humn = Complex::new(0.0, 1.0);
-            }
-            _ => return Err(AocError::logic("no humn")),
-        };
-
-        let (left, right) = match monkeys.get("root") {
-            Some(Operation::Equation { left, right, .. }) => (*left, *right),
-            _ => return Err(AocError::logic("no root")),
-        };
+        let monkeys = Day21::parse(input)?;
+        let equation = Day21::equation(&monkeys)?;
 
-        let reduced = Day21::reduce(monkeys);
-
-        match (reduced.get(left), reduced.get(right)) {
-            (Some(Operation::Value(left)), Some(Operation::Value(right))) => {
-                // The side with imaginary part contained "humn" originally
-                if left.im == 0.0 {
-                    Ok(((left.re - right.re) / right.im) as i64)
-                } else if right.im == 0.0 {
-                    Ok(((right.re - left.re) / left.im) as i64)
-                } else {
-                    // The input didn't actually contain this, but if both sides of the
-                    // equation depended on "humn" this would still solve it
-                    Ok(((left.re - right.re) / (right.im - left.im)) as i64)
-                }
-            }
-            _ => Err(AocError::logic("failed reduce")),
-        }
+        linalg::to_integer(&equation.solve()?)
     }
 }
 
@@ -161,4 +330,66 @@ mod tests {
     fn it_solves_part2() {
         assert_eq!(Day21.part_2(INPUT), Ok(301));
     }
+
+    fn equation(input: &str) -> Equation {
+        Day21::equation(&Day21::parse(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn it_prints_the_simplified_equation() {
+        assert_eq!(equation(INPUT).to_string(), "(4 + (2 * (x - 3))) / 4 = 150");
+    }
+
+    #[test]
+    fn it_solves_the_unknown_on_either_side() {
+        let swapped = INPUT.replace("root: pppw + sjmn", "root: sjmn + pppw");
+        assert_eq!(
+            equation(&swapped).to_string(),
+            "150 = (4 + (2 * (x - 3))) / 4"
+        );
+        assert_eq!(Day21.part_2(&swapped), Ok(301));
+
+        // 12 / (x - 4) = 3 and 20 - 2 * x = 4
+        let divided = "root: a + b\nb: 3\na: c / d\nc: 12\nd: humn - e\ne: 4\nhumn: 0";
+        assert_eq!(Day21.part_2(divided), Ok(8));
+
+        let subtracted = "root: a + b\na: 4\nb: c - d\nc: 20\nd: e * humn\ne: 2\nhumn: 0";
+        assert_eq!(Day21.part_2(subtracted), Ok(8));
+    }
+
+    #[test]
+    fn it_solves_repeated_unknowns_when_linear() {
+        // x + x * 3 = 20
+        let input = "root: a + b\na: humn + c\nc: humn * d\nd: 3\nb: 20\nhumn: 0";
+        assert_eq!(Day21.part_2(input), Ok(5));
+    }
+
+    #[test]
+    fn it_rejects_unsolvable_equations() {
+        // x * x = 4
+        let squared = "root: a + b\na: humn * humn\nb: 4\nhumn: 0";
+        assert!(Day21.part_2(squared).is_err());
+
+        // (x - x) + 1 = 1
+        let ambiguous = "root: a + b\na: c + d\nc: humn - humn\nd: 1\nb: 1\nhumn: 0";
+        assert!(Day21.part_2(ambiguous).is_err());
+
+        // x * 0 = 0
+        let zero = "root: a + b\na: humn * c\nc: 0\nb: 0\nhumn: 0";
+        assert!(Day21.part_2(zero).is_err());
+
+        // 0 / x = 5
+        let zero_numerator = "root: a + b\na: c / humn\nc: 0\nb: 5\nhumn: 0";
+        assert!(equation(zero_numerator).solve().is_err());
+
+        let cycle = "root: a + b\na: b * humn\nb: a + humn\nhumn: 0";
+        assert!(Day21.part_2(cycle).is_err());
+
+        let fraction = "root: a + b\na: humn * c\nc: 2\nb: 3\nhumn: 0";
+        assert!(Day21.part_2(fraction).is_err());
+        assert_eq!(
+            equation(fraction).solve(),
+            Ok(BigRational::new(3.into(), 2.into()))
+        );
+    }
 }
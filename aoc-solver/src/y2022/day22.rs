@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 use nom::{
     branch::alt,
@@ -11,6 +12,9 @@ use nom::{
     IResult,
 };
 
+/// Roughly how many frames the walk on the cube is split into
+const WALK_FRAMES: usize = 200;
+
 pub type Coords = (i64, i64);
 pub type Bounds = HashMap<i64, Coords>;
 pub type World = HashMap<Coords, Tile>;
@@ -95,6 +99,15 @@ impl Direction {
         }
     }
 
+    fn to_arrow(self) -> char {
+        match self {
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Up => '^',
+        }
+    }
+
     fn to_delta(self) -> Coords {
         match self {
             Direction::Right => (1, 0),
@@ -387,13 +400,60 @@ impl Day22 {
         }
     }
 
-    pub fn follow_path(starting_facet: Coords, path: Vec<Instruction>, facets: &HashMap<Coords, Facet>, size: i64) -> Result<(Coords, Coords, Direction), AocError> {
+    /// Folds the map into a cube and walks the path on it.
+    /// Returns the world coordinates and the facing where the path ends.
+    pub fn walk_cube(
+        input: &str,
+        visit: impl FnMut(Coords, Direction),
+    ) -> Result<(Coords, Direction), AocError> {
+        let (world, path, rows, _) = Day22::parse(input)?;
+
+        let size = rows.values().map(|(min, max)| max - min + 1).min().unwrap();
+
+        let mut facets: HashMap<Coords, Facet> = HashMap::new();
+        let leftmost_open: Coords = Day22::find_leftmost_open(&world, &rows)?;
+        let starting_facet = Day22::find_cube_facets(size, world, &mut facets, leftmost_open);
+
+        // Then fold the cube and solve neighbour links, increasing the search distance
+        // from one to up until all the neighbours are found.
+        Day22::fold_cube(&mut facets);
+
+        let (facet, position, direction) =
+            Day22::follow_path(starting_facet, path, &facets, size, visit)?;
+
+        match facets.get(&facet).and_then(|f| f.tiles.get(&position)) {
+            Some((_, world_position)) => Ok((*world_position, direction)),
+            None => Err(AocError::logic("final facet missing")),
+        }
+    }
+
+    /// Walks the path on the folded cube, calling `visit` with the world coordinates and
+    /// the facing after every step and turn.
+    pub fn follow_path(
+        starting_facet: Coords,
+        path: Vec<Instruction>,
+        facets: &HashMap<Coords, Facet>,
+        size: i64,
+        mut visit: impl FnMut(Coords, Direction),
+    ) -> Result<(Coords, Coords, Direction), AocError> {
         let mut facet = starting_facet;
         let mut position = (0, 0);
         let mut direction = Direction::Right;
+
+        let mut visit = |facet: Coords, position: Coords, direction: Direction| {
+            let tile = facets.get(&facet).and_then(|f| f.tiles.get(&position));
+            if let Some((_, world_position)) = tile {
+                visit(*world_position, direction);
+            }
+        };
+        visit(facet, position, direction);
+
         for instruction in path {
             match instruction {
-                Instruction::L | Instruction::R => direction.turn(instruction),
+                Instruction::L | Instruction::R => {
+                    direction.turn(instruction);
+                    visit(facet, position, direction);
+                }
                 Instruction::Move(steps) => {
                     for _step in 0..steps {
                         let delta = direction.to_delta();
@@ -401,7 +461,10 @@ impl Day22 {
                         let current_facet = facets.get(&facet).unwrap();
     
                         match current_facet.tiles.get(&target_pos) {
-                            Some((Tile::Open, _)) => position = target_pos,
+                            Some((Tile::Open, _)) => {
+                                position = target_pos;
+                                visit(facet, position, direction);
+                            }
                             Some((Tile::Solid, _)) => break,
                             None => {
                                 // Jump to a neighbour facet in that direction
@@ -423,6 +486,7 @@ impl Day22 {
                                                 facet = *next_facet;
                                                 position = arrival_position;
                                                 direction = arrival_direction.reverse();
+                                                visit(facet, position, direction);
                                             }
                                             Some((Tile::Solid, _)) => break,
                                             None => {
@@ -507,33 +571,66 @@ impl Solution for Day22 {
     }
 
     fn part_2(&self, input: &str) -> Result<i64, AocError> {
-        let (world, path, rows, _) = Day22::parse(input)?;
+        let ((x, y), direction) = Day22::walk_cube(input, |_, _| {})?;
 
-        let size = rows.values().map(|(min, max)| max - min + 1).min().unwrap();
-
-        let mut facets: HashMap<Coords, Facet> = HashMap::new();
-        let leftmost_open: Coords = Day22::find_leftmost_open(&world, &rows)?;
-        let starting_facet = Day22::find_cube_facets(size, world, &mut facets, leftmost_open);
+        Ok(1000 * y + 4 * x + direction.to_password())
+    }
+}
 
-        // Then fold the cube and solve neighbour links, increasing the search distance
-        // from one to up until all the neighbours are found.
-        Day22::fold_cube(&mut facets);
+impl Visualize for Day22 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "cube" {
+            return Err(unknown_visualization(name));
+        }
 
-        let (facet, position, direction) = Day22::follow_path(starting_facet, path, &facets, size)?;
+        let mut trail: Vec<(Coords, Direction)> = Vec::new();
+        let ((x, y), direction) = Day22::walk_cube(input, |position, direction| {
+            trail.push((position, direction))
+        })?;
+        let password = 1000 * y + 4 * x + direction.to_password();
 
-        let final_facet = facets.get(&facet).unwrap();
-        match final_facet.tiles.get(&position) {
-            Some((_, world_position)) => {
-                Ok(1000 * world_position.1 + 4 * world_position.0 + direction.to_password())
+        // The walk is drawn on the unfolded cube of the input, like in the puzzle description
+        let (map, _) = input
+            .split_once("\n\n")
+            .ok_or_else(|| AocError::parse(input, "split"))?;
+        let width = map.lines().map(|row| row.len()).max().unwrap_or(0);
+        let mut grid: Vec<Vec<char>> = map
+            .lines()
+            .map(|row| format!("{row:width$}").chars().collect())
+            .collect();
+
+        let steps = trail.len();
+        let chunk_size = steps.div_ceil(WALK_FRAMES).max(1);
+        let chunks: Vec<Vec<(Coords, Direction)>> = trail
+            .chunks(chunk_size)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        let mut walked = 0;
+        let frames = chunks.into_iter().map(move |chunk| {
+            walked += chunk.len();
+            for ((x, y), direction) in chunk {
+                grid[y as usize - 1][x as usize - 1] = direction.to_arrow();
             }
-            None => Err(AocError::logic("final facet missing")),
-        }
+
+            let rows = grid.iter().map(|row| row.iter().collect()).collect();
+            let caption = if walked == steps {
+                format!("Password: {password}")
+            } else {
+                format!("Steps: {walked}/{steps}")
+            };
+
+            Frame::grid(caption, rows)
+        });
+
+        Ok(Box::new(frames))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualization::Content;
 
     const INPUT: &[&str] = &[
         "        ...#    ",
@@ -561,4 +658,36 @@ mod tests {
     fn it_solves_part2() {
         assert_eq!(Day22.part_2(&INPUT.join("\n")), Ok(5031));
     }
+
+    #[test]
+    fn it_draws_the_walk_on_the_cube() {
+        let frames: Vec<Frame> = Day22
+            .visualize("cube", &INPUT.join("\n"))
+            .unwrap()
+            .collect();
+        let last = frames.last().unwrap();
+
+        assert_eq!(last.caption, "Password: 5031");
+        assert_eq!(
+            last.content,
+            Content::Grid(
+                [
+                    "        >>v#    ",
+                    "        .#v.    ",
+                    "        #.v.    ",
+                    "        ..v.    ",
+                    "...#..^...v#    ",
+                    ".>>>>>^.#.>>    ",
+                    ".^#....#....    ",
+                    ".^........#.    ",
+                    "        ...#..v.",
+                    "        .....#v.",
+                    "        .#v<<<<.",
+                    "        ..v...#.",
+                ]
+                .map(String::from)
+                .to_vec()
+            )
+        );
+    }
 }
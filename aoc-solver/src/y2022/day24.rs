@@ -1,32 +1,19 @@
-use std::{
-    cmp::Ordering,
-    collections::{HashMap, VecDeque},
-};
+use std::collections::{HashMap, HashSet};
 
 use crate::solution::{AocError, Solution};
+use crate::utils::search::{self, Path, SearchEvent};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 const ACTIONS: [(i32, i32); 5] = [(1, 0), (-1, 0), (0, 1), (0, -1), (0, 0)];
 
 type Coords = (i32, i32);
 type Blizzards = Vec<(Coords, Direction)>;
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct Search {
-    coords: Coords,
-    minute: i32,
-}
-
-impl Ord for Search {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.minute.cmp(&self.minute)
-    }
-}
+/// Position and the phase of the blizzards, the minute is the cost of reaching the state
+type State = (Coords, (i32, i32));
 
-impl PartialOrd for Search {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// How many minutes of the first trip through the basin are visualized at most
+const VISUALIZED_MINUTES: usize = 1000;
 
 #[derive(Debug, Clone, Copy)]
 enum Direction {
@@ -98,80 +85,170 @@ impl Day24 {
         start_time: i32,
         start: Coords,
         end: Coords,
-        (width, height): Coords,
+        dimensions: Coords,
     ) -> Option<i32> {
-        let mut dist: HashMap<(Coords, (i32, i32)), i32> = HashMap::new();
-        let mut queue: VecDeque<Search> = VecDeque::new();
+        Day24::search(blizzards, start_time, start, end, dimensions, |_| {})
+            .map(|path| start_time + path.cost as i32)
+    }
+
+    fn search<F>(
+        blizzards: &Blizzards,
+        start_time: i32,
+        start: Coords,
+        end: Coords,
+        (width, height): Coords,
+        on_event: F,
+    ) -> Option<Path<State>>
+    where
+        F: FnMut(SearchEvent<&State>),
+    {
         let mut blizzards_by_minute: HashMap<i32, Vec<Coords>> = HashMap::new();
 
-        dist.insert(
-            (start, (start_time % width, start_time % height)),
-            start_time,
-        );
-        queue.push_back(Search {
-            minute: start_time,
-            coords: start,
-        });
+        let neighbours = |&(coords, _): &State, elapsed: u32| {
+            let next_minute = start_time + elapsed as i32 + 1;
+
+            // Calculate the blizzard locations only once for each minute
+            let blizzards_next_minute =
+                blizzards_by_minute
+                    .entry(next_minute)
+                    .or_insert_with_key(|minute| {
+                        Day24::blizzard_positions(blizzards, *minute, width, height)
+                    });
+
+            ACTIONS
+                .iter()
+                .map(|delta| (coords.0 + delta.0, coords.1 + delta.1))
+                .filter(|next| {
+                    let is_within_bounds =
+                        next.0 >= 1 && next.1 >= 1 && next.0 < width - 1 && next.1 < height - 1;
+
+                    (is_within_bounds || *next == start || *next == end)
+                        && !blizzards_next_minute.contains(next)
+                })
+                .map(|next| ((next, (next_minute % width, next_minute % height)), 1))
+                .collect::<Vec<_>>()
+        };
+
+        search::dijkstra(
+            [(start, (start_time % width, start_time % height))],
+            neighbours,
+            |(coords, _)| *coords == end,
+            on_event,
+        )
+    }
 
-        while let Some(Search { coords, minute }) = queue.pop_front() {
-            let current = (coords, (minute % width, minute % height));
+    fn blizzard_positions(
+        blizzards: &Blizzards,
+        minute: i32,
+        width: i32,
+        height: i32,
+    ) -> Vec<Coords> {
+        blizzards
+            .iter()
+            .map(|(initial_pos, direction)| {
+                Day24::blizzard_position(*initial_pos, *direction, minute, width, height)
+            })
+            .collect()
+    }
 
-            if coords == end {
-                return Some(*dist.get(&current).unwrap_or(&i32::MAX));
-            }
+    fn render(
+        blizzards: &Blizzards,
+        minute: i32,
+        (start, end): (Coords, Coords),
+        (width, height): Coords,
+        possible: &HashSet<Coords>,
+        expedition: Option<Coords>,
+    ) -> Vec<String> {
+        let mut grid: Vec<Vec<char>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let is_wall = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                        if is_wall {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        grid[start.1 as usize][start.0 as usize] = '.';
+        grid[end.1 as usize][end.0 as usize] = '.';
+
+        for (initial_pos, direction) in blizzards {
+            let (x, y) = Day24::blizzard_position(*initial_pos, *direction, minute, width, height);
+            let tile = &mut grid[y as usize][x as usize];
+
+            *tile = match (*tile, direction) {
+                ('.', Direction::Right) => '>',
+                ('.', Direction::Down) => 'v',
+                ('.', Direction::Left) => '<',
+                ('.', Direction::Up) => '^',
+                ('>' | 'v' | '<' | '^', _) => '2',
+                (count, _) => count
+                    .to_digit(10)
+                    .map_or('*', |count| char::from_digit(count + 1, 10).unwrap_or('*')),
+            };
+        }
 
-            if minute > *dist.entry(current).or_insert(i32::MAX) {
-                continue;
-            }
+        for (x, y) in possible {
+            grid[*y as usize][*x as usize] = '+';
+        }
 
-            for delta in ACTIONS {
-                let next: (i32, i32) = (coords.0 + delta.0, coords.1 + delta.1);
-                let is_within_bounds =
-                    next.0 >= 1 && next.1 >= 1 && next.0 < width - 1 && next.1 < height - 1;
-                let is_end = next.0 == end.0 && next.1 == end.1;
-                let is_start = next.0 == start.0 && next.1 == start.1;
-
-                if is_within_bounds || is_start || is_end {
-                    let next_minute = minute + 1;
-                    let is_blizzard_next_minute = blizzards_by_minute
-                        .entry(next_minute)
-                        .or_insert_with_key(|minute| {
-                            // Calculate the blizzard locations only once for each minute
-                            blizzards
-                                .iter()
-                                .map(|(initial_pos, direction)| {
-                                    Day24::blizzard_position(
-                                        *initial_pos,
-                                        *direction,
-                                        *minute,
-                                        width,
-                                        height,
-                                    )
-                                })
-                                .collect()
-                        })
-                        .iter()
-                        .any(|blizzard| next.0 == blizzard.0 && next.1 == blizzard.1);
-
-                    if is_blizzard_next_minute {
-                        continue;
-                    }
-
-                    let next_key = (next, (next_minute % width, next_minute % height));
-                    let known_fastest = dist.entry(next_key).or_insert(i32::MAX);
-
-                    if next_minute < *known_fastest {
-                        queue.push_back(Search {
-                            minute: next_minute,
-                            coords: next,
-                        });
-                        *known_fastest = next_minute;
-                    }
-                }
-            }
+        if let Some((x, y)) = expedition {
+            grid[y as usize][x as usize] = 'E';
+        }
+
+        grid.iter().map(|row| row.iter().collect()).collect()
+    }
+}
+
+impl Visualize for Day24 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "basin" {
+            return Err(unknown_visualization(name));
         }
 
-        None
+        let (blizzards, start, end, dimensions) = Day24::parse(input)?;
+
+        // Positions where the expedition could be at each minute
+        let mut possible: Vec<HashSet<Coords>> = Vec::new();
+
+        let path = Day24::search(&blizzards, 0, start, end, dimensions, |event| {
+            if let SearchEvent::Discover((coords, _), minute) = event {
+                let minute = minute as usize;
+                if minute < VISUALIZED_MINUTES {
+                    possible.resize(possible.len().max(minute + 1), HashSet::new());
+                    possible[minute].insert(*coords);
+                }
+            }
+        })
+        .ok_or_else(|| AocError::logic("can't reach the end"))?;
+
+        // Other states of the last minute were expanded before reaching the goal
+        possible.truncate(path.cost as usize + 1);
+
+        let frames = possible
+            .into_iter()
+            .enumerate()
+            .map(move |(minute, possible)| {
+                let expedition = path.nodes.get(minute).map(|(coords, _)| *coords);
+
+                Frame::grid(
+                    format!("Minute {minute}, {} possible positions", possible.len()),
+                    Day24::render(
+                        &blizzards,
+                        minute as i32,
+                        (start, end),
+                        dimensions,
+                        &possible,
+                        expedition,
+                    ),
+                )
+            });
+
+        Ok(Box::new(frames))
     }
 }
 
@@ -244,4 +321,26 @@ mod tests {
     fn it_solves_part2_complex() {
         assert_eq!(Day24.part_2(INPUT_COMPLEX), Ok(54));
     }
+
+    #[test]
+    fn it_visualizes_the_expedition() {
+        let frames: Vec<_> = Day24.visualize("basin", INPUT_COMPLEX).unwrap().collect();
+
+        assert_eq!(frames.len(), 19);
+        assert_eq!(
+            frames[0],
+            Frame::grid(
+                "Minute 0, 1 possible positions",
+                vec![
+                    "#E######".into(),
+                    "#>>.<^<#".into(),
+                    "#.<..<<#".into(),
+                    "#>v.><>#".into(),
+                    "#<^v^^>#".into(),
+                    "######.#".into(),
+                ]
+            )
+        );
+        assert_eq!(frames[18].caption, "Minute 18, 8 possible positions");
+    }
 }
@@ -1,54 +1,8 @@
 use crate::solution::{AocError, Solution};
-use num::Integer;
+use crate::utils::balanced::{BalancedBase, Snafu};
 
 pub struct Day25;
 
-impl Day25 {
-    fn from_snafu(snafu: &str) -> Result<i64, AocError> {
-        let mut decimal = 0;
-
-        for (exp, digit) in snafu.chars().rev().enumerate() {
-            let multiplier = match digit {
-                '2' => 2,
-                '1' => 1,
-                '0' => 0,
-                '-' => -1,
-                '=' => -2,
-                unexpected => return Err(AocError::parse(unexpected, "unknown snafu digit")),
-            };
-
-            decimal += multiplier * 5_i64.pow(exp as u32)
-        }
-
-        Ok(decimal)
-    }
-
-    fn to_snafu(mut decimal: i64) -> String {
-        let mut snafu = String::new();
-
-        while decimal > 0 {
-            let (quotient, remainder) = decimal.div_rem(&5);
-            match remainder {
-                0..=2 => {
-                    decimal = quotient;
-                    snafu = format!("{remainder}{snafu}")
-                }
-                3 => {
-                    decimal = quotient + 1;
-                    snafu = format!("={snafu}")
-                }
-                4 => {
-                    decimal = quotient + 1;
-                    snafu = format!("-{snafu}")
-                }
-                _ => unreachable!(),
-            }
-        }
-
-        snafu
-    }
-}
-
 impl Solution for Day25 {
     type A = String;
     type B = String;
@@ -58,15 +12,12 @@ impl Solution for Day25 {
     }
 
     fn part_1(&self, input: &str) -> Result<String, AocError> {
-        let mut decimal_sum = 0;
-
-        for snafu in input.lines() {
-            decimal_sum += Day25::from_snafu(snafu)?;
-        }
-
-        println!("{decimal_sum}");
+        let sum: BalancedBase<Snafu> = input
+            .lines()
+            .map(|line| line.parse::<BalancedBase<Snafu>>())
+            .sum::<Result<_, _>>()?;
 
-        Ok(Day25::to_snafu(decimal_sum))
+        Ok(sum.to_string())
     }
 
     fn part_2(&self, _input: &str) -> Result<String, AocError> {
@@ -144,14 +95,15 @@ mod tests {
     #[test]
     fn it_converts_snafu_to_decimal() {
         for (decimal, snafu) in EXAMPLES {
-            assert_eq!(Day25::from_snafu(snafu), Ok(decimal));
+            let number: BalancedBase<Snafu> = snafu.parse().unwrap();
+            assert_eq!(i64::try_from(&number), Ok(decimal));
         }
     }
 
     #[test]
     fn it_converts_decimal_to_snafu() {
         for (decimal, snafu) in EXAMPLES {
-            assert_eq!(Day25::to_snafu(decimal), snafu);
+            assert_eq!(BalancedBase::<Snafu>::from(decimal).to_string(), snafu);
         }
     }
 }
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::solution::{AocError, Solution};
+use crate::utils::geometry::{self, Point};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 pub struct Day10;
 
@@ -22,9 +24,11 @@ struct Pipe {
 }
 
 type Coords = (i32, i32);
-type Point = (f64, f64);
 type Pipes = HashMap<Coords, Pipe>;
 
+/// Roughly how many frames tracing the loop is split into
+const VISUALIZED_FRAMES: usize = 100;
+
 fn parse(input: &str) -> Result<(Pipes, Coords), AocError> {
     let mut pipes: HashMap<Coords, Pipe> = input
         .trim()
@@ -107,7 +111,10 @@ fn find_loop(
         let pipe = pipes.get(&current).ok_or(AocError::logic("Missing pipe"))?;
 
         visited.insert(current);
-        vertices.push((current.0 as f64, current.1 as f64));
+        vertices.push(Point {
+            x: current.0 as i64,
+            y: current.1 as i64,
+        });
 
         if let Some(next) = pipe
             .connections
@@ -121,27 +128,55 @@ fn find_loop(
     }
 }
 
-fn shoelace(vertices: &[Point]) -> f64 {
-    let mut area = 0.0;
-    let n = vertices.len();
-
-    for i in 0..n {
-        let j = (i + 1) % n;
-
-        let x1 = vertices[i].0;
-        let y1 = vertices[i].1;
-        let x2 = vertices[j].0;
-        let y2 = vertices[j].1;
+impl Shape {
+    fn symbol(&self) -> char {
+        match self {
+            Shape::Vertical => '│',
+            Shape::Horizontal => '─',
+            Shape::NorthEast => '└',
+            Shape::NorthWest => '┘',
+            Shape::SouthWest => '┐',
+            Shape::SouthEast => '┌',
+            Shape::Start => 'S',
+        }
+    }
 
-        area += x1 * y2 - x2 * y1;
+    fn connects_north(&self) -> bool {
+        matches!(self, Shape::Vertical | Shape::NorthEast | Shape::NorthWest)
     }
+}
 
-    area.abs() / 2.0
+/// Renders the traced part of the loop. Once the loop is complete the tiles
+/// enclosed by it are filled, crossing the loop flips between outside and inside.
+fn render(
+    shapes: &HashMap<Coords, Shape>,
+    traced: &HashSet<Coords>,
+    (width, height): Coords,
+    fill: bool,
+) -> Vec<String> {
+    (0..height)
+        .map(|y| {
+            let mut is_inside = false;
+
+            (0..width)
+                .map(|x| match shapes.get(&(x, y)) {
+                    Some(shape) if traced.contains(&(x, y)) => {
+                        if shape.connects_north() {
+                            is_inside = !is_inside;
+                        }
+                        shape.symbol()
+                    }
+                    _ if fill && is_inside => 'I',
+                    _ => ' ',
+                })
+                .collect()
+        })
+        .collect()
 }
 
 impl Solution for Day10 {
     type A = u32;
-    type B = i32;
+    type B = u64;
 
     fn default_input(&self) -> &'static str {
         include_str!("../../../inputs/2023/day10.txt")
@@ -154,28 +189,69 @@ impl Solution for Day10 {
         Ok(pipe_loop.len() as u32 / 2)
     }
 
-    fn part_2(&self, input: &str) -> Result<i32, AocError> {
+    fn part_2(&self, input: &str) -> Result<u64, AocError> {
         let (pipes, start) = parse(input)?;
+        let (_, vertices) = find_loop(pipes, start)?;
+
+        // Every tile of the loop is a vertex of the polygon, so the tiles
+        // enclosed by the loop are the interior lattice points of the polygon.
+        Ok(geometry::interior_points(&vertices))
+    }
+}
+
+impl Visualize for Day10 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "loop" {
+            return Err(unknown_visualization(name));
+        }
+
+        let (pipes, start) = parse(input)?;
+        let shapes: HashMap<Coords, Shape> = pipes
+            .iter()
+            .map(|(coords, pipe)| (*coords, pipe.shape))
+            .collect();
+
+        let size = (
+            input.lines().map(|line| line.len()).max().unwrap_or(0) as i32,
+            input.trim().lines().count() as i32,
+        );
+
         let (pipe_loop, vertices) = find_loop(pipes, start)?;
+        let enclosed = geometry::interior_points(&vertices);
+
+        let chunk_size = vertices.len().div_ceil(VISUALIZED_FRAMES).max(1);
+        let chunks: Vec<Vec<Coords>> = vertices
+            .chunks(chunk_size)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|point| (point.x as i32, point.y as i32))
+                    .collect()
+            })
+            .collect();
 
-        // Calculate the area "A" of polygon using Shoelace formula
-        // https://en.wikipedia.org/wiki/Shoelace_formula
-        let area = shoelace(&vertices) as i32;
+        let mut traced = HashSet::new();
+        let frames = chunks.into_iter().map(move |chunk| {
+            traced.extend(chunk);
 
-        // Solve the amount of interior points "i" with Pick's theorem,
-        // using pipe_loop's length as "b" and the area from shoelace as "A"
-        // https://en.wikipedia.org/wiki/Pick%27s_theorem
-        // A = i + b/2 - 1
-        // i = -b/2 + 1 + A
-        let interior_points = pipe_loop.len() as i32 / -2 + 1 + area;
+            let is_complete = traced.len() == pipe_loop.len();
+            let caption = if is_complete {
+                format!("Loop length: {}, enclosed tiles: {enclosed}", traced.len())
+            } else {
+                format!("Loop length: {}", traced.len())
+            };
 
-        Ok(interior_points)
+            Frame::grid(caption, render(&shapes, &traced, size, is_complete))
+        });
+
+        Ok(Box::new(frames))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualization::Content;
 
     #[test]
     fn it_parses_part1_simple() {
@@ -407,4 +483,40 @@ mod tests {
             Ok(10)
         )
     }
+
+    #[test]
+    fn it_visualizes_enclosed_tiles() {
+        let frames: Vec<Frame> = Day10
+            .visualize(
+                "loop",
+                "...........\n\
+                 .S-------7.\n\
+                 .|F-----7|.\n\
+                 .||.....||.\n\
+                 .||.....||.\n\
+                 .|L-7.F-J|.\n\
+                 .|..|.|..|.\n\
+                 .L--J.L--J.\n\
+                 ...........\n",
+            )
+            .unwrap()
+            .collect();
+
+        let last = frames.last().unwrap();
+        assert_eq!(last.caption, "Loop length: 46, enclosed tiles: 4");
+        assert_eq!(
+            last.content,
+            Content::Grid(vec![
+                "           ".to_string(),
+                " ┌───────┐ ".to_string(),
+                " │┌─────┐│ ".to_string(),
+                " ││     ││ ".to_string(),
+                " ││     ││ ".to_string(),
+                " │└─┐ ┌─┘│ ".to_string(),
+                " │II│ │II│ ".to_string(),
+                " └──┘ └──┘ ".to_string(),
+                "           ".to_string(),
+            ])
+        );
+    }
 }
@@ -1,8 +1,7 @@
-use std::collections::HashMap;
-
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::utils::memo::Memoized;
 
 pub struct Day12;
 
@@ -52,53 +51,49 @@ fn parse(input: &str) -> Result<Vec<Record>, AocError> {
         .try_collect()
 }
 
-fn find_possible(
-    memo: &mut HashMap<(usize, usize, usize), usize>,
-    record: &Record,
-    spring_index: usize,
-    group_index: usize,
-    contiguous: usize,
-) -> usize {
-    if let Some(cached) = memo.get(&(spring_index, group_index, contiguous)) {
-        return *cached;
-    }
-
-    if spring_index == record.springs.len() {
-        let is_at_end_done = group_index == record.groups.len() && contiguous == 0;
-        let is_at_last_just_finishing =
-            group_index == record.groups.len() - 1 && record.groups[group_index] == contiguous;
-
-        if is_at_end_done || is_at_last_just_finishing {
-            return 1;
-        }
-
-        return 0;
-    }
-
-    let mut possible = 0;
-
-    if matches!(
-        record.springs[spring_index],
-        Spring::Operational | Spring::Unknown,
-    ) {
-        if contiguous == 0 {
-            // Currently not gathering a group, move to next spring
-            possible += find_possible(memo, record, spring_index + 1, group_index, 0)
-        } else if *record.groups.get(group_index).unwrap_or(&0) == contiguous {
-            // A group ended just before this spring
-            possible += find_possible(memo, record, spring_index + 1, group_index + 1, 0)
-        }
-    }
-
-    if matches!(
-        record.springs[spring_index],
-        Spring::Damaged | Spring::Unknown,
-    ) {
-        possible += find_possible(memo, record, spring_index + 1, group_index, contiguous + 1)
-    }
-
-    memo.insert((spring_index, group_index, contiguous), possible);
-    possible
+fn find_possible(record: &Record) -> usize {
+    let mut find_possible = Memoized::new(
+        |find_possible: &mut dyn FnMut((usize, usize, usize)) -> usize,
+         (spring_index, group_index, contiguous): (usize, usize, usize)| {
+            if spring_index == record.springs.len() {
+                let is_at_end_done = group_index == record.groups.len() && contiguous == 0;
+                let is_at_last_just_finishing = group_index == record.groups.len() - 1
+                    && record.groups[group_index] == contiguous;
+
+                if is_at_end_done || is_at_last_just_finishing {
+                    return 1;
+                }
+
+                return 0;
+            }
+
+            let mut possible = 0;
+
+            if matches!(
+                record.springs[spring_index],
+                Spring::Operational | Spring::Unknown,
+            ) {
+                if contiguous == 0 {
+                    // Currently not gathering a group, move to next spring
+                    possible += find_possible((spring_index + 1, group_index, 0))
+                } else if *record.groups.get(group_index).unwrap_or(&0) == contiguous {
+                    // A group ended just before this spring
+                    possible += find_possible((spring_index + 1, group_index + 1, 0))
+                }
+            }
+
+            if matches!(
+                record.springs[spring_index],
+                Spring::Damaged | Spring::Unknown,
+            ) {
+                possible += find_possible((spring_index + 1, group_index, contiguous + 1))
+            }
+
+            possible
+        },
+    );
+
+    find_possible.call((0, 0, 0))
 }
 
 impl Solution for Day12 {
@@ -112,10 +107,7 @@ impl Solution for Day12 {
     fn part_1(&self, input: &str) -> Result<usize, AocError> {
         let records = parse(input)?;
 
-        let total = records
-            .iter()
-            .map(|record| find_possible(&mut HashMap::new(), record, 0, 0, 0))
-            .sum();
+        let total = records.iter().map(find_possible).sum();
 
         Ok(total)
     }
@@ -143,7 +135,7 @@ impl Solution for Day12 {
                     .take(5 * record.groups.len())
                     .collect();
 
-                find_possible(&mut HashMap::new(), &Record { springs, groups }, 0, 0, 0)
+                find_possible(&Record { springs, groups })
             })
             .sum();
 
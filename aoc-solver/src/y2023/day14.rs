@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 pub struct Day14;
 
@@ -13,6 +14,7 @@ enum Tile {
     Empty,
 }
 
+#[derive(Debug)]
 enum Direction {
     North,
     East,
@@ -20,6 +22,9 @@ enum Direction {
     West,
 }
 
+/// Spin cycles shown by the visualization
+const VISUALIZED_CYCLES: usize = 20;
+
 const SPIN_CYCLE: [Direction; 4] = [
     Direction::North,
     Direction::West,
@@ -182,6 +187,20 @@ fn support_beams_load(grid: &Grid) -> u32 {
         .sum()
 }
 
+fn render(grid: &Grid) -> Vec<String> {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| match tile {
+                    Tile::Rounded => 'O',
+                    Tile::Cube => '#',
+                    Tile::Empty => '.',
+                })
+                .collect()
+        })
+        .collect()
+}
+
 impl Solution for Day14 {
     type A = u32;
     type B = u32;
@@ -209,6 +228,37 @@ impl Solution for Day14 {
     }
 }
 
+impl Visualize for Day14 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "rocks" {
+            return Err(unknown_visualization(name));
+        }
+
+        let mut grid = parse(input)?;
+        let initial = Frame::grid(
+            format!("Initial load: {}", support_beams_load(&grid)),
+            render(&grid),
+        );
+
+        let tilts = (1..=VISUALIZED_CYCLES)
+            .flat_map(|cycle| SPIN_CYCLE.iter().map(move |direction| (cycle, direction)));
+
+        let frames = tilts.map(move |(cycle, direction)| {
+            tilt(&mut grid, direction);
+
+            Frame::grid(
+                format!(
+                    "Cycle {cycle}, tilted {direction:?}, load: {}",
+                    support_beams_load(&grid)
+                ),
+                render(&grid),
+            )
+        });
+
+        Ok(Box::new(std::iter::once(initial).chain(frames)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,4 +305,18 @@ mod tests {
     fn it_solves_part2_real() {
         assert_eq!(Day14.part_2(Day14.default_input()), Ok(87700));
     }
+
+    #[test]
+    fn it_visualizes_spin_cycles() {
+        let frames: Vec<Frame> = Day14.visualize("rocks", "O.#\n.O.\n..O").unwrap().collect();
+
+        assert_eq!(frames.len(), 1 + VISUALIZED_CYCLES * 4);
+        assert_eq!(
+            frames[1],
+            Frame::grid(
+                "Cycle 1, tilted North, load: 8",
+                vec!["OO#".to_string(), "..O".to_string(), "...".to_string()]
+            )
+        );
+    }
 }
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 pub struct Day16;
 
@@ -22,6 +23,10 @@ enum Direction {
 }
 
 type Grid = Vec<Vec<Tile>>;
+type Beam = (usize, usize, Direction);
+
+/// Roughly how many frames the beam propagation is split into
+const VISUALIZED_FRAMES: usize = 200;
 
 fn parse(input: &str) -> Result<(Grid, usize, usize), AocError> {
     let grid: Grid = input
@@ -94,11 +99,27 @@ fn energize(
     grid: &[Vec<Tile>],
     entry: (usize, usize, Direction),
 ) -> Result<usize, AocError> {
+    let trace = trace_beams(width, height, grid, entry)?;
+    let energized: HashSet<(usize, usize)> = trace.into_iter().map(|(x, y, _)| (x, y)).collect();
+
+    Ok(energized.len())
+}
+
+/// Every distinct beam position and direction, in the order they were reached.
+fn trace_beams(
+    width: usize,
+    height: usize,
+    grid: &[Vec<Tile>],
+    entry: Beam,
+) -> Result<Vec<Beam>, AocError> {
     let mut beams = vec![entry];
-    let mut seen: HashSet<(usize, usize, Direction)> = HashSet::new();
+    let mut seen: HashSet<Beam> = HashSet::new();
+    let mut trace = Vec::new();
 
     while let Some((x, y, direction)) = beams.pop() {
         if seen.insert((x, y, direction)) {
+            trace.push((x, y, direction));
+
             let x = x as isize;
             let y = y as isize;
 
@@ -147,9 +168,66 @@ fn energize(
         }
     }
 
-    let energized: HashSet<(usize, usize)> = seen.into_iter().map(|(x, y, _)| (x, y)).collect();
+    Ok(trace)
+}
+
+fn render(grid: &Grid, energized: &HashSet<(usize, usize)>, heads: &[Beam]) -> Vec<String> {
+    grid.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, tile)| {
+                    if let Some((_, _, direction)) =
+                        heads.iter().find(|head| (head.0, head.1) == (x, y))
+                    {
+                        return match direction {
+                            Direction::North => '^',
+                            Direction::East => '>',
+                            Direction::South => 'v',
+                            Direction::West => '<',
+                        };
+                    }
 
-    Ok(energized.len())
+                    match tile {
+                        Tile::Mirror(symbol) | Tile::Splitter(symbol) => *symbol,
+                        Tile::Empty if energized.contains(&(x, y)) => '#',
+                        Tile::Empty => '.',
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl Visualize for Day16 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "beams" {
+            return Err(unknown_visualization(name));
+        }
+
+        let (grid, height, width) = parse(input)?;
+        let trace = trace_beams(width, height, &grid, (0, 0, Direction::East))?;
+
+        let chunk_size = trace.len().div_ceil(VISUALIZED_FRAMES).max(1);
+        let mut energized = HashSet::new();
+
+        let chunks: Vec<Vec<Beam>> = trace
+            .chunks(chunk_size)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        let frames = chunks.into_iter().map(move |chunk| {
+            energized.extend(chunk.iter().map(|(x, y, _)| (*x, *y)));
+
+            Frame::grid(
+                format!("Energized tiles: {}", energized.len()),
+                render(&grid, &energized, &chunk),
+            )
+        });
+
+        Ok(Box::new(frames))
+    }
 }
 
 #[cfg(test)]
@@ -201,4 +279,14 @@ mod tests {
             Ok(51)
         );
     }
+
+    #[test]
+    fn it_visualizes_beams() {
+        let frames: Vec<Frame> = Day16.visualize("beams", ".|.\n...\n.-.").unwrap().collect();
+
+        assert_eq!(
+            frames.last().map(|frame| frame.caption.as_str()),
+            Some("Energized tiles: 6")
+        );
+    }
 }
@@ -1,11 +1,8 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap},
-};
-
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::utils::search::{self, Path, SearchEvent};
+use crate::visualization::{search_frames, unknown_visualization, Frames, Visualize};
 
 pub struct Day17;
 
@@ -44,26 +41,6 @@ impl Direction {
     }
 }
 
-#[derive(Clone, Eq, PartialEq)]
-struct Search {
-    heat_loss: u32,
-    position: Coords,
-    direction: Direction,
-    consequtive: u8,
-}
-
-impl Ord for Search {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.heat_loss.cmp(&self.heat_loss)
-    }
-}
-
-impl PartialOrd for Search {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 fn parse(input: &str) -> Result<Grid, AocError> {
     let grid: Grid = input
         .trim()
@@ -84,74 +61,53 @@ fn parse(input: &str) -> Result<Grid, AocError> {
     Ok(grid)
 }
 
-fn dijkstra(grid: &Grid, min_consequtive: u8, max_consequtive: u8) -> Result<u32, AocError> {
-    let mut heat_losses: HashMap<(Coords, Direction, u8), u32> = HashMap::new();
-    let mut heap: BinaryHeap<Search> = BinaryHeap::new();
-
+type State = (Coords, Direction, u8);
+
+fn dijkstra<F>(
+    grid: &Grid,
+    min_consequtive: u8,
+    max_consequtive: u8,
+    on_event: F,
+) -> Result<Path<State>, AocError>
+where
+    F: FnMut(SearchEvent<&State>),
+{
     let height = grid.len() as isize;
     let width = grid[0].len() as isize;
     let target = (width - 1, height - 1);
 
-    heat_losses.insert(((0, 0), Direction::East, 0), 0);
-    heap.push(Search {
-        position: (0, 0),
-        direction: Direction::East,
-        consequtive: 0,
-        heat_loss: 0,
-    });
-
-    while let Some(Search {
-        position,
-        direction,
-        consequtive,
-        heat_loss,
-    }) = heap.pop()
-    {
-        if position == target && consequtive >= min_consequtive {
-            return Ok(heat_losses[&(position, direction, consequtive)]);
-        }
-
-        if heat_loss
-            > *heat_losses
-                .get(&(position, direction, consequtive))
-                .unwrap_or(&u32::MAX)
-        {
-            continue;
-        }
-
-        for next_direction in direction.possible(consequtive, min_consequtive) {
-            let (dx, dy) = next_direction.as_delta();
-            let (x, y) = (position.0 + dx, position.1 + dy);
-
-            let next_consequtive = if direction == next_direction {
-                consequtive + 1
-            } else {
-                1
-            };
-
-            if x < 0 || y < 0 || x >= width || y >= height || next_consequtive > max_consequtive {
-                continue;
-            }
-
-            let next = Search {
-                position: (x, y),
-                direction: next_direction,
-                heat_loss: heat_loss + grid[y as usize][x as usize] as u32,
-                consequtive: next_consequtive,
-            };
-
-            let best_known = heat_losses
-                .entry((next.position, next.direction, next.consequtive))
-                .or_insert(u32::MAX);
-
-            if next.heat_loss < *best_known {
-                *best_known = next.heat_loss;
-                heap.push(next)
-            }
-        }
-    }
-
-    Err(AocError::logic("No path found"))
+    let neighbours = |&(position, direction, consequtive): &State, _| {
+        direction
+            .possible(consequtive, min_consequtive)
+            .into_iter()
+            .filter_map(move |next_direction| {
+                let (dx, dy) = next_direction.as_delta();
+                let (x, y) = (position.0 + dx, position.1 + dy);
+
+                let next_consequtive = if direction == next_direction {
+                    consequtive + 1
+                } else {
+                    1
+                };
+
+                if x < 0 || y < 0 || x >= width || y >= height || next_consequtive > max_consequtive
+                {
+                    return None;
+                }
+
+                let heat_loss = grid[y as usize][x as usize] as u32;
+
+                Some((((x, y), next_direction, next_consequtive), heat_loss))
+            })
+    };
+
+    search::dijkstra(
+        [((0, 0), Direction::East, 0)],
+        neighbours,
+        |(position, _, consequtive)| *position == target && *consequtive >= min_consequtive,
+        on_event,
+    )
+    .ok_or(AocError::logic("No path found"))
 }
 
 impl Solution for Day17 {
@@ -164,12 +120,47 @@ impl Solution for Day17 {
 
     fn part_1(&self, input: &str) -> Result<u32, AocError> {
         let grid = parse(input)?;
-        dijkstra(&grid, 0, 3)
+        dijkstra(&grid, 0, 3, |_| {}).map(|path| path.cost)
     }
 
     fn part_2(&self, input: &str) -> Result<u32, AocError> {
         let grid = parse(input)?;
-        dijkstra(&grid, 4, 10)
+        dijkstra(&grid, 4, 10, |_| {}).map(|path| path.cost)
+    }
+}
+
+impl Visualize for Day17 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "crucible" {
+            return Err(unknown_visualization(name));
+        }
+
+        let grid = parse(input)?;
+        let mut events = Vec::new();
+
+        let path = dijkstra(&grid, 0, 3, |event| {
+            events.push(event.map(|((x, y), _, _)| (*x as usize, *y as usize)))
+        })?;
+
+        let tiles = grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|heat_loss| (b'0' + heat_loss) as char)
+                    .collect()
+            })
+            .collect();
+
+        let path = Path {
+            cost: path.cost,
+            nodes: path
+                .nodes
+                .iter()
+                .map(|((x, y), _, _)| (*x as usize, *y as usize))
+                .collect(),
+        };
+
+        Ok(search_frames(tiles, events, Some(path)))
     }
 }
 
@@ -234,4 +225,20 @@ mod tests {
             Ok(71)
         );
     }
+
+    #[test]
+    fn it_visualizes_the_search() {
+        let frames: Vec<_> = Day17
+            .visualize("crucible", "241\n321\n325\n")
+            .unwrap()
+            .collect();
+
+        assert_eq!(
+            frames.last(),
+            Some(&crate::visualization::Frame::grid(
+                "Lowest cost: 11",
+                vec!["#41".into(), "###".into(), "32#".into()]
+            ))
+        );
+    }
 }
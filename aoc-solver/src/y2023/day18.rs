@@ -1,11 +1,10 @@
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::utils::geometry::{self, Point};
 
 pub struct Day18;
 
-type Point = (i64, i64);
-
 enum Direction {
     Up,
     Right,
@@ -66,56 +65,27 @@ fn parse(input: &str) -> Result<Vec<Instruction>, AocError> {
 }
 
 fn execute(instructions: &[Instruction]) -> Result<(u64, Vec<Point>), AocError> {
-    let mut current = (0, 0);
+    let mut current = Point { x: 0, y: 0 };
     let mut trench_len = 0;
     let mut vertices = Vec::with_capacity(instructions.len());
 
     for instruction in instructions {
-        vertices.push((current.0, current.1));
+        vertices.push(current);
 
         trench_len += instruction.steps;
 
         let (dx, dy) = instruction.direction.as_delta();
-        current.0 += instruction.steps as i64 * dx as i64;
-        current.1 += instruction.steps as i64 * dy as i64;
+        current.x += instruction.steps as i64 * dx as i64;
+        current.y += instruction.steps as i64 * dy as i64;
     }
 
     Ok((trench_len, vertices))
 }
 
-fn shoelace(vertices: &[Point]) -> i64 {
-    let mut area = 0;
-    let n = vertices.len();
-
-    for i in 0..n {
-        let j = (i + 1) % n;
-
-        let x1 = vertices[i].0;
-        let y1 = vertices[i].1;
-        let x2 = vertices[j].0;
-        let y2 = vertices[j].1;
-
-        area += x1 * y2 - x2 * y1;
-    }
-
-    area.abs() / 2
-}
-
 fn calculate_area(vertices: &[Point], trench_len: u64) -> u64 {
-    // Calculate the area "A" of polygon using Shoelace formula
-    // https://en.wikipedia.org/wiki/Shoelace_formula
-    let area = shoelace(vertices);
-
-    // Solve the amount of interior points "i" with Pick's theorem,
-    // using trench length as "b" and the area from shoelace as "A"
-    // https://en.wikipedia.org/wiki/Pick%27s_theorem
-    // A = i + b/2 - 1
-    // i = -b/2 + 1 + A
-    let interior_points = trench_len as i64 / -2 + 1 + area;
-
     // Add together the volume dug out while digging the trench and
     // the volume contained within it
-    trench_len + interior_points as u64
+    trench_len + geometry::interior_points(vertices)
 }
 
 impl Solution for Day18 {
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 use nom::{
     branch::alt,
@@ -14,39 +15,484 @@ use crate::solution::{AocError, Solution};
 
 pub struct Day19;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-enum Category {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
     ExtremelyCoolLooking,
     Musical, // it makes a noise when you hit it!
     Aerodynamic,
     Shiny,
 }
 
-enum Operator {
+impl Category {
+    const ALL: [Category; 4] = [
+        Category::ExtremelyCoolLooking,
+        Category::Musical,
+        Category::Aerodynamic,
+        Category::Shiny,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
     LessThan,
     GreaterThan,
 }
 
-#[derive(Clone)]
-enum Target {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
     Accepted,
     Rejected,
     Name(String),
 }
 
-enum Step {
+#[derive(Debug, PartialEq, Eq)]
+pub enum Step {
     Comparison((Category, Operator, u32, Target)),
     Name(Target),
 }
 
 type Workflow = (String, Vec<Step>);
-type Workflows = HashMap<String, Vec<Step>>;
-type Part = HashMap<Category, u32>;
+pub type Workflows = HashMap<String, Vec<Step>>;
+pub type Part = HashMap<Category, u32>;
+pub type Ranges = HashMap<Category, Range>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Range {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Category::ExtremelyCoolLooking => 'x',
+            Category::Musical => 'm',
+            Category::Aerodynamic => 'a',
+            Category::Shiny => 's',
+        };
+
+        write!(f, "{symbol}")
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operator::LessThan => write!(f, "<"),
+            Operator::GreaterThan => write!(f, ">"),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Accepted => write!(f, "A"),
+            Target::Rejected => write!(f, "R"),
+            Target::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Comparison((category, operator, operand, target)) => {
+                write!(f, "{category}{operator}{operand}:{target}")
+            }
+            Step::Name(target) => write!(f, "{target}"),
+        }
+    }
+}
+
+/// Position of a step within its workflow
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RuleId {
+    pub workflow: String,
+    pub index: usize,
+}
+
+impl fmt::Display for RuleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.workflow, self.index)
+    }
+}
+
+/// Workflows flattened into binary decisions, skipping the comparisons
+/// that have the same outcome for every part reaching them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Accepted,
+    Rejected,
+    Branch {
+        rule: RuleId,
+        category: Category,
+        operator: Operator,
+        operand: u32,
+        then: Box<Decision>,
+        otherwise: Box<Decision>,
+    },
+}
+
+impl Decision {
+    /// Exports the tree as a Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph workflows {".to_owned()];
+        self.write_dot(&mut lines, &mut 0);
+        lines.push("}".to_owned());
+
+        lines.join("\n")
+    }
+
+    fn write_dot(&self, lines: &mut Vec<String>, count: &mut usize) -> usize {
+        let id = *count;
+        *count += 1;
+
+        match self {
+            Decision::Accepted => {
+                lines.push(format!("  n{id} [label=\"A\", shape=box, color=green];"))
+            }
+            Decision::Rejected => {
+                lines.push(format!("  n{id} [label=\"R\", shape=box, color=red];"))
+            }
+            Decision::Branch {
+                rule,
+                category,
+                operator,
+                operand,
+                then,
+                otherwise,
+            } => {
+                lines.push(format!(
+                    "  n{id} [label=\"{}: {category}{operator}{operand}\"];",
+                    rule.workflow
+                ));
+
+                let then = then.write_dot(lines, count);
+                lines.push(format!("  n{id} -> n{then} [label=\"yes\"];"));
+
+                let otherwise = otherwise.write_dot(lines, count);
+                lines.push(format!("  n{id} -> n{otherwise} [label=\"no\"];"));
+            }
+        }
+
+        id
+    }
+}
+
+pub struct DecisionTree {
+    pub root: Decision,
+    /// Disjoint ranges of the ratings of the accepted parts
+    pub accepted: Vec<Ranges>,
+    /// Rules that never decide where any part goes
+    pub unreachable: Vec<RuleId>,
+}
+
+struct Compiler<'a> {
+    workflows: &'a Workflows,
+    stack: Vec<&'a str>,
+    fired: HashSet<RuleId>,
+    accepted: Vec<Ranges>,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile(
+        &mut self,
+        name: &'a str,
+        index: usize,
+        ranges: Ranges,
+    ) -> Result<Decision, AocError> {
+        let steps = self
+            .workflows
+            .get(name)
+            .ok_or(AocError::logic(format!("Unknown workflow {name}")))?;
+
+        let step = steps.get(index).ok_or(AocError::logic(format!(
+            "Workflow {name} has no final rule"
+        )))?;
+
+        let rule = RuleId {
+            workflow: name.to_owned(),
+            index,
+        };
+
+        match step {
+            Step::Comparison((category, operator, operand, target)) => {
+                let (fulfilled, rest) = split(&ranges, *category, *operator, *operand);
+
+                let then = match fulfilled {
+                    Some(fulfilled) => {
+                        self.fired.insert(rule.clone());
+                        Some(self.enter(target, fulfilled)?)
+                    }
+                    None => None,
+                };
+
+                let otherwise = match rest {
+                    Some(rest) => Some(self.compile(name, index + 1, rest)?),
+                    None => None,
+                };
+
+                match (then, otherwise) {
+                    (Some(then), Some(otherwise)) => Ok(Decision::Branch {
+                        rule,
+                        category: *category,
+                        operator: *operator,
+                        operand: *operand,
+                        then: Box::new(then),
+                        otherwise: Box::new(otherwise),
+                    }),
+                    (Some(decision), None) | (None, Some(decision)) => Ok(decision),
+                    (None, None) => Err(AocError::logic("Empty ranges")),
+                }
+            }
+            Step::Name(target) => {
+                self.fired.insert(rule);
+                self.enter(target, ranges)
+            }
+        }
+    }
+
+    fn enter(&mut self, target: &'a Target, ranges: Ranges) -> Result<Decision, AocError> {
+        match target {
+            Target::Accepted => {
+                self.accepted.push(ranges);
+                Ok(Decision::Accepted)
+            }
+            Target::Rejected => Ok(Decision::Rejected),
+            Target::Name(name) => {
+                if self.stack.contains(&name.as_str()) {
+                    return Err(AocError::logic(format!(
+                        "Cycle between workflows {} -> {name}",
+                        self.stack.join(" -> ")
+                    )));
+                }
+
+                self.stack.push(name);
+                let decision = self.compile(name, 0, ranges);
+                self.stack.pop();
+
+                decision
+            }
+        }
+    }
+}
+
+/// Splits the ranges to the ones fulfilling the condition and the rest, unless either is empty
+fn split(
+    ranges: &Ranges,
+    category: Category,
+    operator: Operator,
+    operand: u32,
+) -> (Option<Ranges>, Option<Ranges>) {
+    let Range { min, max } = ranges[&category];
+
+    // Comparisons against the ends of the u32 range can never be fulfilled
+    let (fulfilled, rest) = match operator {
+        Operator::GreaterThan => (
+            operand.checked_add(1).map(|low| (low.max(min), max)),
+            (min, operand.min(max)),
+        ),
+        Operator::LessThan => (
+            operand.checked_sub(1).map(|high| (min, high.min(max))),
+            (operand.max(min), max),
+        ),
+    };
+
+    let with = |(min, max): (u32, u32)| {
+        if min > max {
+            return None;
+        }
+
+        let mut ranges = ranges.clone();
+        ranges.insert(category, Range { min, max });
+        Some(ranges)
+    };
 
-#[derive(Clone, Default)]
-struct Range {
-    min: u32,
-    max: u32,
+    (fulfilled.and_then(with), with(rest))
+}
+
+/// Flattens the workflows starting from `in` into a decision tree
+pub fn compile(workflows: &Workflows) -> Result<DecisionTree, AocError> {
+    let mut compiler = Compiler {
+        workflows,
+        stack: vec!["in"],
+        fired: HashSet::new(),
+        accepted: Vec::new(),
+    };
+
+    let ranges = Category::ALL
+        .into_iter()
+        .map(|category| (category, Range { min: 1, max: 4000 }))
+        .collect();
+
+    let root = compiler.compile("in", 0, ranges)?;
+
+    let mut unreachable: Vec<RuleId> = workflows
+        .iter()
+        .flat_map(|(workflow, steps)| {
+            (0..steps.len()).map(|index| RuleId {
+                workflow: workflow.clone(),
+                index,
+            })
+        })
+        .filter(|rule| !compiler.fired.contains(rule))
+        .collect();
+    unreachable.sort();
+
+    Ok(DecisionTree {
+        root,
+        accepted: compiler.accepted,
+        unreachable,
+    })
+}
+
+/// Finds the cycles between the workflows, regardless of whether any part could take them
+pub fn find_cycles(workflows: &Workflows) -> Vec<Vec<String>> {
+    fn visit<'a>(
+        workflows: &'a Workflows,
+        name: &'a str,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(start) = path.iter().position(|workflow| *workflow == name) {
+            cycles.push(path[start..].iter().map(|name| name.to_string()).collect());
+            return;
+        }
+
+        if !done.insert(name) {
+            return;
+        }
+
+        path.push(name);
+        for step in workflows.get(name).into_iter().flatten() {
+            if let Step::Comparison((_, _, _, Target::Name(next)))
+            | Step::Name(Target::Name(next)) = step
+            {
+                visit(workflows, next, path, done, cycles);
+            }
+        }
+        path.pop();
+    }
+
+    let mut names: Vec<&String> = workflows.keys().collect();
+    names.sort();
+
+    let mut cycles = Vec::new();
+    let mut done = HashSet::new();
+    for name in names {
+        visit(workflows, name, &mut Vec::new(), &mut done, &mut cycles);
+    }
+
+    cycles
+}
+
+/// Rules evaluated for a part, and whether it was accepted in the end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub rules: Vec<(RuleId, String, bool)>,
+    pub accepted: bool,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (rule, step, is_fulfilled) in self.rules.iter() {
+            let outcome = if *is_fulfilled { "yes" } else { "no" };
+            writeln!(f, "{rule} {step}: {outcome}")?;
+        }
+
+        write!(
+            f,
+            "{}",
+            if self.accepted {
+                "Accepted"
+            } else {
+                "Rejected"
+            }
+        )
+    }
+}
+
+/// Walks the part through the workflows, recording every rule on the way
+pub fn explain(workflows: &Workflows, part: &Part) -> Result<Explanation, AocError> {
+    let mut rules = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = "in";
+
+    loop {
+        if !visited.insert(current) {
+            return Err(AocError::logic(format!(
+                "Part loops back to workflow {current}"
+            )));
+        }
+
+        let steps = workflows
+            .get(current)
+            .ok_or(AocError::logic(format!("Unknown workflow {current}")))?;
+
+        let mut next = None;
+
+        for (index, step) in steps.iter().enumerate() {
+            let (is_fulfilled, target) = match step {
+                Step::Comparison((category, operator, operand, target)) => {
+                    let rating = part.get(category).copied().unwrap_or_default();
+                    let is_fulfilled = match operator {
+                        Operator::GreaterThan => rating > *operand,
+                        Operator::LessThan => rating < *operand,
+                    };
+
+                    (is_fulfilled, target)
+                }
+                Step::Name(target) => (true, target),
+            };
+
+            let rule = RuleId {
+                workflow: current.to_owned(),
+                index,
+            };
+            rules.push((rule, step.to_string(), is_fulfilled));
+
+            if is_fulfilled {
+                next = Some(target);
+                break;
+            }
+        }
+
+        match next {
+            Some(Target::Accepted) => {
+                return Ok(Explanation {
+                    rules,
+                    accepted: true,
+                })
+            }
+            Some(Target::Rejected) => {
+                return Ok(Explanation {
+                    rules,
+                    accepted: false,
+                })
+            }
+            Some(Target::Name(name)) => current = name,
+            None => {
+                return Err(AocError::logic(format!(
+                    "Workflow {current} has no final rule"
+                )))
+            }
+        }
+    }
+}
+
+/// Formats the ranges like `x=1-4000 m=1-2090 a=2006-4000 s=1-1350`
+pub fn describe(ranges: &Ranges) -> String {
+    Category::ALL
+        .iter()
+        .filter_map(|category| {
+            ranges
+                .get(category)
+                .map(|Range { min, max }| format!("{category}={min}-{max}"))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 fn parse_comparison(input: &str) -> IResult<&str, Step> {
@@ -150,111 +596,25 @@ impl Solution for Day19 {
     fn part_1(&self, input: &str) -> Result<u32, AocError> {
         let (workflows, parts) = parse(input)?;
 
-        let sum = parts
-            .iter()
-            .filter_map(|part| {
-                let mut current = "in";
-
-                while let Some(steps) = workflows.get(current) {
-                    for step in steps {
-                        let (continue_next, target) = match step {
-                            Step::Comparison((category, operator, operand, target)) => {
-                                let is_fulfilled = match operator {
-                                    Operator::GreaterThan => part[&category] > *operand,
-                                    Operator::LessThan => part[&category] < *operand,
-                                };
-
-                                (is_fulfilled, target)
-                            }
-                            Step::Name(target) => (true, target),
-                        };
-
-                        if continue_next {
-                            match target {
-                                Target::Accepted => return Some(part.values().sum::<u32>()),
-                                Target::Rejected => return None,
-                                Target::Name(name) => {
-                                    current = &name;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-
-                None
-            })
-            .sum();
+        let mut sum = 0;
+        for part in parts.iter() {
+            if explain(&workflows, part)?.accepted {
+                sum += part.values().sum::<u32>();
+            }
+        }
 
         Ok(sum)
     }
 
     fn part_2(&self, input: &str) -> Result<u64, AocError> {
         let (workflows, _) = parse(input)?;
+        let tree = compile(&workflows)?;
 
-        let mut combinations = 0;
-        let mut stack = vec![(
-            "in",
-            HashMap::from([
-                (Category::ExtremelyCoolLooking, Range { min: 1, max: 4000 }),
-                (Category::Musical, Range { min: 1, max: 4000 }),
-                (Category::Aerodynamic, Range { min: 1, max: 4000 }),
-                (Category::Shiny, Range { min: 1, max: 4000 }),
-            ]),
-        )];
-
-        while let Some((current, mut ranges)) = stack.pop() {
-            for step in &workflows[current] {
-                // `ranges` continues the steps of the current workflow, never fulfilling the condition
-                // `next` fulfills the condition and enters the target, leaving the current workflow.
-                match step {
-                    Step::Comparison((category, operator, operand, target)) => {
-                        let mut next = ranges.clone();
-
-                        match operator {
-                            Operator::GreaterThan => {
-                                ranges.entry(category.clone()).or_default().max = *operand;
-                                next.entry(category.clone()).or_default().min = *operand + 1;
-                            }
-                            Operator::LessThan => {
-                                ranges.entry(category.clone()).or_default().min = *operand;
-                                next.entry(category.clone()).or_default().max = *operand - 1;
-                            }
-                        };
-
-                        // Branch `next` like it met the condition and enter its target
-                        match target {
-                            Target::Rejected => {}
-                            Target::Accepted => {
-                                combinations += valid_combinations(&next);
-                            }
-                            Target::Name(name) => {
-                                stack.push((name, next.clone()));
-                            }
-                        };
-                    }
-                    Step::Name(target) => {
-                        match target {
-                            Target::Rejected => {}
-                            Target::Accepted => {
-                                combinations += valid_combinations(&ranges);
-                            }
-                            Target::Name(name) => {
-                                // Jump to the next workflow
-                                stack.push((name, ranges.clone()));
-                            }
-                        };
-                        break;
-                    }
-                };
-            }
-        }
-
-        Ok(combinations)
+        Ok(tree.accepted.iter().map(valid_combinations).sum())
     }
 }
 
-fn valid_combinations(ranges: &HashMap<Category, Range>) -> u64 {
+fn valid_combinations(ranges: &Ranges) -> u64 {
     ranges
         .values()
         .map(|Range { min, max }| *max as u64 - *min as u64 + 1)
@@ -303,4 +663,90 @@ mod tests {
     fn it_solves_part2_example() {
         assert_eq!(Day19.part_2(EXAMPLE_INPUT), Ok(167409079868000));
     }
+
+    fn parse_rules(input: &str) -> Workflows {
+        parse_workflows(input).unwrap().1
+    }
+
+    #[test]
+    fn it_explains_the_decisions() {
+        let (workflows, parts) = parse(EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(
+            explain(&workflows, &parts[0]).unwrap().to_string(),
+            "in[0] s<1351:px: no\n\
+             in[1] qqz: yes\n\
+             qqz[0] s>2770:qs: yes\n\
+             qs[0] s>3448:A: no\n\
+             qs[1] lnx: yes\n\
+             lnx[0] m>1548:A: yes\n\
+             Accepted"
+        );
+
+        let explanation = explain(&workflows, &parts[1]).unwrap();
+        assert!(!explanation.accepted);
+        assert_eq!(explanation.rules.len(), 7);
+    }
+
+    #[test]
+    fn it_compiles_the_accepted_ranges() {
+        let (workflows, _) = parse(EXAMPLE_INPUT).unwrap();
+        let tree = compile(&workflows).unwrap();
+
+        assert_eq!(
+            tree.accepted.iter().map(valid_combinations).sum::<u64>(),
+            167409079868000
+        );
+        assert!(tree.unreachable.is_empty());
+
+        let tree = compile(&parse_rules("in{x>10:A,R}\n")).unwrap();
+        assert_eq!(
+            tree.accepted.iter().map(describe).collect::<Vec<_>>(),
+            vec!["x=11-4000 m=1-4000 a=1-4000 s=1-4000"]
+        );
+    }
+
+    #[test]
+    fn it_finds_unreachable_rules() {
+        let tree = compile(&parse_rules("in{x>10:A,x>20:R,s<1:A,R}\nzz{A}\n")).unwrap();
+
+        assert_eq!(
+            tree.unreachable
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect::<Vec<_>>(),
+            vec!["in[1]", "in[2]", "zz[0]"]
+        );
+    }
+
+    #[test]
+    fn it_detects_cycles() {
+        // The cycle is never taken, as no part reaching `b` has x < 5
+        let harmless = parse_rules("in{x>10:a,R}\na{b}\nb{x<5:a,A}\n");
+        assert_eq!(find_cycles(&harmless), vec![vec!["a", "b"]]);
+        assert!(compile(&harmless).is_ok());
+
+        let infinite = parse_rules("in{x>10:a,R}\na{b}\nb{m<4001:a,A}\n");
+        assert!(compile(&infinite).is_err());
+
+        let (workflows, parts) = parse(EXAMPLE_INPUT).unwrap();
+        assert!(find_cycles(&workflows).is_empty());
+        assert!(explain(&infinite, &parts[1]).is_err());
+    }
+
+    #[test]
+    fn it_exports_dot() {
+        let tree = compile(&parse_rules("in{x>10:A,R}\n")).unwrap();
+
+        assert_eq!(
+            tree.root.to_dot(),
+            "digraph workflows {\n  \
+               n0 [label=\"in: x>10\"];\n  \
+               n1 [label=\"A\", shape=box, color=green];\n  \
+               n0 -> n1 [label=\"yes\"];\n  \
+               n2 [label=\"R\", shape=box, color=red];\n  \
+               n0 -> n2 [label=\"no\"];\n\
+             }"
+        );
+    }
 }
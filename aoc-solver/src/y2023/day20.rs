@@ -1,19 +1,30 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{
+    lay_out_graphs, unknown_visualization, Edge, Frame, Frames, Graph, Node, Visualize,
+};
 
 pub struct Day20;
 
-#[derive(PartialEq, Eq)]
+/// How many button presses are visualized
+const VISUALIZED_PRESSES: u32 = 20;
+
+/// Button presses after which the sub-circuits are assumed not to be periodic
+const MAX_PRESSES: u32 = 100_000;
+
+#[derive(Clone, PartialEq, Eq)]
 enum ModuleType {
     FlipFlop,
     Conjunction,
     Neutral,
 }
 
-struct Module {
+#[derive(Clone)]
+pub struct Module {
     kind: ModuleType,
     state: bool,
     memory: HashMap<String, bool>,
@@ -21,8 +32,10 @@ struct Module {
     outputs: Vec<String>,
 }
 
-fn parse(input: &str) -> Result<HashMap<String, Module>, AocError> {
-    let mut modules: HashMap<String, Module> = input
+pub type Modules = HashMap<String, Module>;
+
+pub fn parse(input: &str) -> Result<Modules, AocError> {
+    let mut modules: Modules = input
         .lines()
         .map(|line| {
             let (name, outputs) = line
@@ -77,57 +90,359 @@ fn parse(input: &str) -> Result<HashMap<String, Module>, AocError> {
     Ok(modules)
 }
 
-fn press_button(
-    button_press: u32,
-    modules: &mut HashMap<String, Module>,
-    modules_to_find: &mut HashMap<String, Option<u32>>,
-) -> (u32, u32) {
-    let mut output_buffer =
-        VecDeque::from([(String::from("broadcaster"), false, String::from("button"))]);
+/// Pulse sent to the target module, with the value and the name of the source module
+type Pulse = (String, bool, String);
+
+/// Pulses are processed one wave at a time, the pulses of the next wave are the ones sent
+/// while processing the current one. Each wave is reported to `on_wave` before it's processed.
+fn press_button<F>(modules: &mut Modules, mut on_wave: F) -> (u32, u32)
+where
+    F: FnMut(&[Pulse], &Modules),
+{
+    let mut wave: Vec<Pulse> = vec![(String::from("broadcaster"), false, String::from("button"))];
 
     let mut lows = 1;
     let mut highs = 0;
 
-    while let Some((current, input, source)) = output_buffer.pop_front() {
-        if let Some(module) = modules.get_mut(&current) {
-            let output = match module.kind {
-                ModuleType::Neutral => Some(input),
-                ModuleType::Conjunction => {
-                    module.memory.insert(source, input);
-                    let output = module.memory.values().any(|value| !value);
-                    if output {
-                        for (to_find, presses) in modules_to_find.iter_mut() {
-                            if current == *to_find {
-                                *presses = Some(button_press);
-                            }
+    while !wave.is_empty() {
+        on_wave(&wave, modules);
+
+        let mut next_wave = Vec::new();
+
+        for (current, input, source) in wave {
+            if let Some(module) = modules.get_mut(&current) {
+                let output = match module.kind {
+                    ModuleType::Neutral => Some(input),
+                    ModuleType::Conjunction => {
+                        module.memory.insert(source, input);
+                        Some(module.memory.values().any(|value| !value))
+                    }
+                    ModuleType::FlipFlop => {
+                        if !input {
+                            module.state = !module.state;
+                            Some(module.state)
+                        } else {
+                            None
                         }
                     }
-                    Some(output)
-                }
-                ModuleType::FlipFlop => {
-                    if !input {
-                        module.state = !module.state;
-                        Some(module.state)
-                    } else {
-                        None
+                };
+
+                if let Some(output) = output {
+                    match output {
+                        true => highs += module.outputs.len() as u32,
+                        false => lows += module.outputs.len() as u32,
+                    }
+
+                    for target in &module.outputs {
+                        next_wave.push((target.clone(), output, current.to_owned()));
                     }
                 }
+            }
+        }
+
+        wave = next_wave;
+    }
+
+    (lows, highs)
+}
+
+/// Draws the modules with the pulses of a wave on their connections. Flip-flops that are on
+/// and conjunctions that would send a low pulse are lit.
+fn render(modules: &Modules, wave: &[Pulse]) -> Graph {
+    let names: Vec<&str> = modules
+        .iter()
+        .flat_map(|(name, module)| {
+            std::iter::once(name.as_str()).chain(module.outputs.iter().map(|o| o.as_str()))
+        })
+        .chain(std::iter::once("button"))
+        .sorted()
+        .dedup()
+        .collect();
+
+    let index = |name: &str| names.binary_search(&name).unwrap_or_default();
+
+    let nodes = names
+        .iter()
+        .map(|name| {
+            let color = match modules.get(*name) {
+                Some(module) => match module.kind {
+                    ModuleType::FlipFlop if module.state => "#ffff66",
+                    ModuleType::FlipFlop => "#666600",
+                    ModuleType::Conjunction if module.memory.values().all(|value| *value) => {
+                        "#ff66ff"
+                    }
+                    ModuleType::Conjunction => "#660066",
+                    ModuleType::Neutral => "#cccccc",
+                },
+                None => "#cccccc",
             };
 
-            if let Some(output) = output {
-                match output {
-                    true => highs += module.outputs.len() as u32,
-                    false => lows += module.outputs.len() as u32,
+            Node {
+                label: name.to_string(),
+                color: color.into(),
+            }
+        })
+        .collect();
+
+    let connections = modules
+        .iter()
+        .flat_map(|(name, module)| module.outputs.iter().map(move |output| (name, output)))
+        .map(|(from, to)| (from.as_str(), to.as_str()))
+        .chain(std::iter::once(("button", "broadcaster")))
+        .sorted();
+
+    let edges = connections
+        .map(|(from, to)| {
+            let pulse = wave
+                .iter()
+                .find(|(target, _, source)| source == from && target == to);
+
+            Edge {
+                from: index(from),
+                to: index(to),
+                color: match pulse {
+                    Some((_, true, _)) => "#ff6666",
+                    Some((_, false, _)) => "#6666ff",
+                    None => "#333340",
                 }
+                .into(),
+            }
+        })
+        .collect();
+
+    Graph {
+        nodes,
+        edges,
+        ..Default::default()
+    }
+}
+
+/// Pulse sent during a button press, written like `a -high-> b`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedPulse {
+    pub press: u32,
+    pub source: String,
+    pub value: bool,
+    pub target: String,
+}
+
+impl fmt::Display for TracedPulse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = if self.value { "high" } else { "low" };
+        write!(f, "{} -{value}-> {}", self.source, self.target)
+    }
+}
+
+/// Records every pulse sent during the button presses, in the order they're processed
+pub fn trace(modules: &mut Modules, presses: u32) -> Vec<TracedPulse> {
+    let mut pulses = Vec::new();
+
+    for press in 1..=presses {
+        press_button(modules, |wave, _| {
+            pulses.extend(wave.iter().map(|(target, value, source)| TracedPulse {
+                press,
+                source: source.clone(),
+                value: *value,
+                target: target.clone(),
+            }))
+        });
+    }
+
+    pulses
+}
+
+/// Exports the circuit as a Graphviz DOT digraph
+pub fn to_dot(modules: &Modules) -> String {
+    let mut lines = vec![
+        "digraph circuit {".to_owned(),
+        "  button [shape=point];".to_owned(),
+        "  button -> broadcaster;".to_owned(),
+    ];
+
+    for name in modules.keys().sorted() {
+        let module = &modules[name];
+
+        let (prefix, shape) = match module.kind {
+            ModuleType::FlipFlop => ("%", "box"),
+            ModuleType::Conjunction => ("&", "diamond"),
+            ModuleType::Neutral => ("", "ellipse"),
+        };
+        lines.push(format!(
+            "  {name} [label=\"{prefix}{name}\", shape={shape}];"
+        ));
+
+        for output in module.outputs.iter() {
+            lines.push(format!("  {name} -> {output};"));
+        }
+    }
+
+    lines.push("}".to_owned());
+    lines.join("\n")
+}
+
+/// Modules that only feed the combining conjunction through a single output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubCircuit {
+    pub output: String,
+    pub modules: Vec<String>,
+    /// Button presses between the high pulses sent by the output
+    pub period: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Conjunction combining the outputs of the sub-circuits
+    pub combiner: String,
+    pub sub_circuits: Vec<SubCircuit>,
+    /// Fewest button presses until the target receives a low pulse
+    pub presses: u64,
+}
+
+/// Modules sending pulses to the module, directly or through other modules
+fn feeders(modules: &Modules, name: &str) -> HashSet<String> {
+    let mut found = HashSet::new();
+    let mut stack = vec![name];
+
+    while let Some(current) = stack.pop() {
+        if current == "broadcaster" || !found.insert(current.to_owned()) {
+            continue;
+        }
+
+        if let Some(module) = modules.get(current) {
+            stack.extend(module.inputs.iter().map(|input| input.as_str()));
+        }
+    }
+
+    found
+}
+
+/// Finds when the target first receives a low pulse. Assumes the target is fed by a chain of
+/// inverters from a conjunction, whose inputs are outputs of independent sub-circuits sending
+/// a high pulse periodically. The presses are found as the LCM of those periods.
+pub fn analyze(modules: &Modules, target: &str) -> Result<Analysis, AocError> {
+    let mut combiner = modules
+        .iter()
+        .filter(|(_, module)| module.outputs.iter().any(|output| output == target))
+        .map(|(name, _)| name.as_str())
+        .exactly_one()
+        .map_err(|_| AocError::logic(format!("Expected a single module sending to {target}")))?;
+
+    let mut inverters = 0;
+    let mut chain = vec![combiner];
+    let mut visited = HashSet::from([combiner]);
+
+    loop {
+        let module = &modules[combiner];
+
+        if module.kind != ModuleType::Conjunction {
+            return Err(AocError::logic(format!(
+                "Expected {combiner} feeding {target} to be a conjunction"
+            )));
+        }
+
+        if module.inputs.len() > 1 {
+            break;
+        }
+
+        combiner = module
+            .inputs
+            .first()
+            .ok_or(AocError::logic(format!("{combiner} has no inputs")))?;
+        inverters += 1;
+
+        chain.push(combiner);
+        if !visited.insert(combiner) {
+            return Err(AocError::logic(format!(
+                "Inverters {} form a cycle",
+                chain.iter().rev().join(" -> ")
+            )));
+        }
+    }
+
+    if inverters % 2 == 1 {
+        return Err(AocError::logic(format!(
+            "Expected an even number of inverters between {combiner} and {target}"
+        )));
+    }
+
+    let mut sub_circuits: Vec<SubCircuit> = Vec::new();
+
+    for output in modules[combiner].inputs.iter().sorted() {
+        let feeding = feeders(modules, output);
+
+        if feeding.contains(combiner) {
+            return Err(AocError::logic(format!(
+                "{combiner} feeds back to {output}"
+            )));
+        }
+
+        for other in sub_circuits.iter() {
+            if let Some(shared) = other.modules.iter().find(|name| feeding.contains(*name)) {
+                return Err(AocError::logic(format!(
+                    "Sub-circuits of {output} and {} share {shared}",
+                    other.output
+                )));
+            }
+        }
+
+        sub_circuits.push(SubCircuit {
+            output: output.clone(),
+            modules: feeding.into_iter().sorted().collect(),
+            period: 0,
+        });
+    }
+
+    // Presses when each of the outputs sent their first two high pulses to the combiner
+    let mut highs: Vec<Vec<u32>> = vec![Vec::new(); sub_circuits.len()];
+    let mut modules = modules.clone();
+
+    for press in 1..=MAX_PRESSES {
+        press_button(&mut modules, |wave, _| {
+            for (index, sub_circuit) in sub_circuits.iter().enumerate() {
+                let is_high = wave.iter().any(|(to, value, from)| {
+                    *value && to == combiner && *from == sub_circuit.output
+                });
 
-                for target in &module.outputs {
-                    output_buffer.push_back((target.clone(), output, current.to_owned()));
+                if is_high && highs[index].last() != Some(&press) && highs[index].len() < 2 {
+                    highs[index].push(press);
                 }
             }
+        });
+
+        if highs.iter().all(|presses| presses.len() == 2) {
+            break;
         }
     }
 
-    (lows, highs)
+    for (sub_circuit, presses) in sub_circuits.iter_mut().zip(highs) {
+        match presses[..] {
+            [first, second] if second == 2 * first => sub_circuit.period = first as u64,
+            [first, second] => {
+                return Err(AocError::logic(format!(
+                    "Expected {} to send high pulses periodically from the start, \
+                     but they were sent at presses {first} and {second}",
+                    sub_circuit.output
+                )))
+            }
+            _ => {
+                return Err(AocError::logic(format!(
+                    "{} didn't send two high pulses within {MAX_PRESSES} presses",
+                    sub_circuit.output
+                )))
+            }
+        }
+    }
+
+    let presses = sub_circuits
+        .iter()
+        .try_fold(1, |acc, sub_circuit| lcm(acc, sub_circuit.period))
+        .ok_or_else(|| AocError::logic("The least common multiple of the periods overflows"))?;
+
+    Ok(Analysis {
+        combiner: combiner.to_owned(),
+        sub_circuits,
+        presses,
+    })
 }
 
 fn gcd(a: u64, b: u64) -> u64 {
@@ -138,8 +453,8 @@ fn gcd(a: u64, b: u64) -> u64 {
     }
 }
 
-fn lcm(a: u64, b: u64) -> u64 {
-    a / gcd(a, b) * b
+fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
 }
 
 impl Solution for Day20 {
@@ -156,8 +471,8 @@ impl Solution for Day20 {
         let mut lows = 0;
         let mut highs = 0;
 
-        for button_press in 0..1000 {
-            let pulses = press_button(button_press, &mut modules, &mut HashMap::new());
+        for _ in 0..1000 {
+            let pulses = press_button(&mut modules, |_, _| {});
             lows += pulses.0;
             highs += pulses.1;
         }
@@ -166,65 +481,45 @@ impl Solution for Day20 {
     }
 
     fn part_2(&self, input: &str) -> Result<u64, AocError> {
-        let mut modules = parse(input)?;
+        let modules = parse(input)?;
 
-        let (rx, _) = modules
-            .iter()
-            .find(|(_, module)| module.outputs.contains(&String::from("rx")))
-            .ok_or(AocError::logic("Missing rx module"))?;
-
-        let mut current = rx.as_str();
-        let mut modules_to_find: HashMap<String, Option<u32>> = HashMap::new();
-
-        // This isn't very generic solution - assume that there's a chain of conjunction
-        // modules that produces the value of 'rx' module. Traverse this chain until we
-        // see conjunction a module with more than one input. These inputs will trigger
-        // periodically, find the durations of these cycles and the answer by determining
-        // the LCM of those periodic input cycle durations.
-        loop {
-            let target_module = modules
-                .get(current)
-                .ok_or(AocError::logic("Missing module"))?;
-
-            if target_module.kind != ModuleType::Conjunction {
-                return Err(AocError::logic("Chain of conjunction must lead to rx"));
-            }
-
-            // In this puzzle picking the first module with more than one input already
-            // had a period of ~4000 button presses, but if these cycles were longer or
-            // more expensive to calculate we could continue deeper and just do more LCMs
-            if target_module.inputs.len() > 1 {
-                for input in &target_module.inputs {
-                    modules_to_find.insert(input.clone(), None);
-                }
-                break;
-            }
+        Ok(analyze(&modules, "rx")?.presses)
+    }
+}
 
-            current = target_module.inputs[0].as_str();
+impl Visualize for Day20 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "pulses" {
+            return Err(unknown_visualization(name));
         }
 
-        let mut button_press = 1;
-
-        loop {
-            press_button(button_press, &mut modules, &mut modules_to_find);
-
-            if modules_to_find.values().all(|value| value.is_some()) {
-                let fewest_presses = modules_to_find
-                    .values()
-                    .map(|value| value.unwrap_or(0))
-                    .fold(1, |acc, period| lcm(acc, std::cmp::max(period as u64, 1)));
+        let mut modules = parse(input)?;
+        let mut frames = Vec::new();
+
+        for button_press in 1..=VISUALIZED_PRESSES {
+            press_button(&mut modules, |wave, modules| {
+                let highs = wave.iter().filter(|(_, value, _)| *value).count();
+
+                frames.push(Frame::graph(
+                    format!(
+                        "Press {button_press}, {} low and {highs} high pulses",
+                        wave.len() - highs
+                    ),
+                    render(modules, wave),
+                ));
+            });
+        }
 
-                return Ok(fewest_presses);
-            }
+        lay_out_graphs(&mut frames);
 
-            button_press += 1;
-        }
+        Ok(Box::new(frames.into_iter()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualization::Content;
 
     #[test]
     fn it_solves_part1_example_1() {
@@ -258,4 +553,183 @@ mod tests {
     fn it_solves_part2_real() {
         assert_eq!(Day20.part_2(Day20.default_input()), Ok(238920142622879));
     }
+
+    #[test]
+    fn it_visualizes_the_pulse_waves() {
+        let frames: Vec<_> = Day20
+            .visualize(
+                "pulses",
+                "broadcaster -> a, b, c\n\
+                 %a -> b\n\
+                 %b -> c\n\
+                 %c -> inv\n\
+                 &inv -> a\n",
+            )
+            .unwrap()
+            .collect();
+
+        // The pulses ripple through the flip-flops and the inverter in 8 waves every press
+        assert_eq!(frames.len(), 8 * VISUALIZED_PRESSES as usize);
+        assert_eq!(frames[1].caption, "Press 1, 3 low and 0 high pulses");
+
+        let Content::Graph(graph) = &frames[1].content else {
+            panic!("Expected a graph");
+        };
+
+        let labels: Vec<&str> = graph.nodes.iter().map(|node| node.label.as_str()).collect();
+        assert_eq!(labels, vec!["a", "b", "broadcaster", "button", "c", "inv"]);
+        assert_eq!(
+            graph
+                .edges
+                .iter()
+                .filter(|edge| edge.color == "#6666ff")
+                .count(),
+            3
+        );
+    }
+
+    #[rustfmt::skip]
+    const COUNTERS: &str =
+        "broadcaster -> a, b\n\
+         %a -> ia\n\
+         &ia -> comb\n\
+         %b -> c\n\
+         %c -> ic\n\
+         &ic -> comb\n\
+         &comb -> rx\n";
+
+    #[test]
+    fn it_traces_the_pulses() {
+        let mut modules = parse(
+            "broadcaster -> a, b, c\n\
+             %a -> b\n\
+             %b -> c\n\
+             %c -> inv\n\
+             &inv -> a\n",
+        )
+        .unwrap();
+
+        let pulses = trace(&mut modules, 2);
+        assert_eq!(pulses.len(), 24);
+        assert!(pulses[12..].iter().all(|pulse| pulse.press == 2));
+        assert_eq!(
+            pulses[..12]
+                .iter()
+                .map(|pulse| pulse.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "broadcaster -low-> b",
+                "broadcaster -low-> c",
+                "a -high-> b",
+                "b -high-> c",
+                "c -high-> inv",
+                "inv -low-> a",
+                "a -low-> b",
+                "b -low-> c",
+                "c -low-> inv",
+                "inv -high-> a",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_exports_dot() {
+        let modules = parse("broadcaster -> a\n%a -> inv, con\n&inv -> b\n").unwrap();
+
+        assert_eq!(
+            to_dot(&modules),
+            "digraph circuit {\n  \
+               button [shape=point];\n  \
+               button -> broadcaster;\n  \
+               a [label=\"%a\", shape=box];\n  \
+               a -> inv;\n  \
+               a -> con;\n  \
+               broadcaster [label=\"broadcaster\", shape=ellipse];\n  \
+               broadcaster -> a;\n  \
+               inv [label=\"&inv\", shape=diamond];\n  \
+               inv -> b;\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn it_analyzes_independent_sub_circuits() {
+        let modules = parse(COUNTERS).unwrap();
+        let analysis = analyze(&modules, "rx").unwrap();
+
+        assert_eq!(analysis.combiner, "comb");
+        assert_eq!(
+            analysis.sub_circuits,
+            vec![
+                SubCircuit {
+                    output: String::from("ia"),
+                    modules: vec![String::from("a"), String::from("ia")],
+                    period: 2,
+                },
+                SubCircuit {
+                    output: String::from("ic"),
+                    modules: vec![String::from("b"), String::from("c"), String::from("ic")],
+                    period: 4,
+                },
+            ]
+        );
+
+        // Matches the first press when rx receives a low pulse
+        let first_low = trace(&mut parse(COUNTERS).unwrap(), 10)
+            .into_iter()
+            .find(|pulse| pulse.target == "rx" && !pulse.value)
+            .map(|pulse| pulse.press as u64);
+
+        assert_eq!(first_low, Some(analysis.presses));
+        assert_eq!(analysis.presses, 4);
+    }
+
+    #[test]
+    fn it_rejects_unexpected_circuits() {
+        let error = |input: &str| analyze(&parse(input).unwrap(), "rx").unwrap_err();
+
+        assert_eq!(
+            error("broadcaster -> a\n%a -> b\n"),
+            AocError::logic("Expected a single module sending to rx")
+        );
+        assert_eq!(
+            error(&COUNTERS.replace("&comb -> rx", "&comb -> inv\n&inv -> rx")),
+            AocError::logic("Expected an even number of inverters between comb and rx")
+        );
+        assert_eq!(
+            error(
+                "broadcaster -> a\n\
+                 %a -> ia, ib\n\
+                 &ia -> comb\n\
+                 &ib -> comb\n\
+                 &comb -> rx\n"
+            ),
+            AocError::logic("Sub-circuits of ib and ia share a")
+        );
+        assert_eq!(
+            error("broadcaster -> c\n&a -> b, rx\n&b -> a\n"),
+            AocError::logic("Inverters a -> b -> a form a cycle")
+        );
+        assert_eq!(
+            error(
+                "broadcaster -> a, ib\n\
+                 %a -> comb\n\
+                 &ib -> comb\n\
+                 &comb -> rx\n"
+            ),
+            AocError::logic(
+                "Expected a to send high pulses periodically from the start, \
+                 but they were sent at presses 1 and 3"
+            )
+        );
+    }
+
+    #[test]
+    fn it_checks_the_common_multiple_for_overflow() {
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(1 << 62, 1 << 40), Some(1 << 62));
+        assert_eq!(lcm(1 << 63, 3), None);
+    }
 }
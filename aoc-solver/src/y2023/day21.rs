@@ -3,9 +3,12 @@ use std::collections::HashSet;
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{unknown_visualization, Frame, Frames, Visualize};
 
 const DIRECTIONS: [(i8, i8); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
 
+const PART_1_STEPS: usize = 64;
+
 pub struct Day21;
 
 type Coords = (isize, isize);
@@ -46,6 +49,17 @@ fn parse(input: &str) -> Result<(Vec<Vec<Tile>>, Coords), AocError> {
 }
 
 fn visit_reachable(tiles: &[Vec<Tile>], start: (isize, isize), max_steps: usize) -> (u64, u64) {
+    let (even, odd) = reachable(tiles, start, max_steps);
+
+    (even.len() as u64, odd.len() as u64)
+}
+
+/// Tiles reachable in exactly `max_steps` and `max_steps - 1` steps.
+fn reachable(
+    tiles: &[Vec<Tile>],
+    start: (isize, isize),
+    max_steps: usize,
+) -> (HashSet<Coords>, HashSet<Coords>) {
     let mut stack = vec![(start, 0)];
     let mut visited: HashSet<(Coords, usize)> = HashSet::new();
 
@@ -60,7 +74,7 @@ fn visit_reachable(tiles: &[Vec<Tile>], start: (isize, isize), max_steps: usize)
             continue;
         }
 
-        if distance == max_steps - 1 {
+        if distance + 1 == max_steps {
             odd.insert((x, y));
         }
 
@@ -83,7 +97,25 @@ fn visit_reachable(tiles: &[Vec<Tile>], start: (isize, isize), max_steps: usize)
         }
     }
 
-    (even.len() as u64, odd.len() as u64)
+    (even, odd)
+}
+
+fn render(tiles: &[Vec<Tile>], reached: &HashSet<Coords>) -> Vec<String> {
+    tiles
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, tile)| match tile {
+                    _ if reached.contains(&(x as isize, y as isize)) => 'O',
+                    Tile::GardenPlot => '.',
+                    Tile::Rock => '#',
+                    Tile::Start => 'S',
+                })
+                .collect()
+        })
+        .collect()
 }
 
 impl Solution for Day21 {
@@ -96,7 +128,7 @@ impl Solution for Day21 {
 
     fn part_1(&self, input: &str) -> Result<u64, AocError> {
         let (tiles, start) = parse(input)?;
-        let reachable = visit_reachable(&tiles, start, 64).0;
+        let reachable = visit_reachable(&tiles, start, PART_1_STEPS).0;
 
         Ok(reachable)
     }
@@ -201,6 +233,31 @@ impl Solution for Day21 {
     }
 }
 
+impl Visualize for Day21 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "steps" {
+            return Err(unknown_visualization(name));
+        }
+
+        let (tiles, start) = parse(input)?;
+
+        let frames = (0..=PART_1_STEPS).map(move |steps| {
+            let reached = if steps == 0 {
+                HashSet::from([start])
+            } else {
+                reachable(&tiles, start, steps).0
+            };
+
+            Frame::grid(
+                format!("Step {steps}, reachable garden plots: {}", reached.len()),
+                render(&tiles, &reached),
+            )
+        });
+
+        Ok(Box::new(frames))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,4 +302,16 @@ mod tests {
 
         assert_eq!(visit_reachable(&tiles, start, size), (7226, 7257));
     }
+
+    #[test]
+    fn it_visualizes_steps() {
+        let frames: Vec<Frame> = Day21
+            .visualize("steps", EXAMPLE_INPUT)
+            .unwrap()
+            .take(7)
+            .collect();
+
+        assert_eq!(frames[0].caption, "Step 0, reachable garden plots: 1");
+        assert_eq!(frames[6].caption, "Step 6, reachable garden plots: 16");
+    }
 }
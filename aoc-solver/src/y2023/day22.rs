@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::utils::memo::Memo;
 
 pub struct Day22;
 
@@ -141,7 +142,7 @@ impl Solution for Day22 {
         let dropped = apply_gravity(bricks);
         let bricks_with_supports = find_supports(dropped);
 
-        let mut memo: HashMap<usize, HashSet<usize>> = HashMap::new();
+        let mut memo: Memo<usize, HashSet<usize>> = Memo::new();
 
         let total_falls: u32 = bricks_with_supports
             .keys()
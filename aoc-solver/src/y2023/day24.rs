@@ -1,10 +1,8 @@
 use itertools::Itertools;
-use num_bigint::BigInt;
-use num_traits::One;
-use num_traits::ToPrimitive;
-use num_traits::Zero;
+use num::{BigRational, Signed, ToPrimitive};
 
 use crate::solution::{AocError, Solution};
+use crate::utils::linalg::{self, rational};
 
 pub struct Day24;
 
@@ -62,112 +60,35 @@ fn parse_number(number: &str) -> Result<i64, AocError> {
         .map_err(|_| AocError::parse(number, "Error parsing number"))
 }
 
-fn determinant(matrix: &[Vec<i64>]) -> BigInt {
-    let size = matrix.len();
-
-    if size == 1 {
-        return BigInt::from(matrix[0][0]);
-    }
-
-    if size == 2 {
-        return BigInt::from(matrix[0][0]) * BigInt::from(matrix[1][1])
-            - BigInt::from(matrix[1][0]) * BigInt::from(matrix[0][1]);
-    }
-
-    let mut det = BigInt::zero();
-
-    for det_column in 0..size {
-        let mut submatrix = vec![vec![0; size - 1]; size - 1];
-
-        for row in 1..size {
-            let mut sub_column = 0;
-            for column in 0..size {
-                if column == det_column {
-                    continue;
-                }
-                submatrix[row - 1][sub_column] = matrix[row][column];
-                sub_column += 1;
-            }
-        }
-
-        let submatrix_det = determinant(&submatrix);
-
-        let sign = if det_column % 2 == 0 {
-            BigInt::one()
-        } else {
-            -BigInt::one()
-        };
-
-        det += sign * BigInt::from(matrix[0][det_column]) * submatrix_det;
-    }
-
-    det
-}
-
-fn solve_cramers_rule(matrix: &[Vec<i64>]) -> Option<Vec<f64>> {
-    // Last column of the matrix contains the constant solutions
-    // a*i + b*j + c*k + d*l = e*m
-    // ^-------------------^   ^-^
-    //     coefficients         solution
-    let size = matrix[0].len() - 1;
-
-    let coefficients_matrix: Vec<Vec<i64>> = matrix
-        .iter()
-        .map(|row| row.iter().copied().take(size).collect())
-        .collect();
-
-    let det = determinant(&coefficients_matrix);
-
-    if det.is_zero() {
-        return None;
-    }
-
-    let det = det.to_f64().expect("Overflow at determinant");
-
-    let mut results = Vec::new();
-
-    for column in 0..size {
-        let mut det_matrix = coefficients_matrix.clone();
-
-        for row in 0..size {
-            det_matrix[row][column] = matrix[row][size];
-        }
-
-        let det_column = determinant(&det_matrix)
-            .to_f64()
-            .expect("Overflow at column determinant");
-
-        results.push(det_column / det);
-    }
-
-    Some(results)
-}
-
 fn check_intersection(a: &Hailstone, b: &Hailstone, (min, max): (f64, f64)) -> bool {
     // System of two equations and two unknowns (t_a and t_b)
     // x_a + vx_a * t_a = x_b + vx_b * tb
     // y_a + vy_a * t_a = y_b + vy_b * tb
 
-    // Represent this system as a coefficients matrix and solve it using Cramer's Rule
+    // Represent this system as a coefficients matrix and solve it using Gaussian elimination
     // vx_a * ta - vx_b * tb = x_a - x_b
     // ^--^        ^--^        ^-------^
     //  A           B           C
-    let matrix = vec![
+    let matrix = linalg::from_integers(&[
         vec![a.velocity.x, -b.velocity.x, b.position.x - a.position.x],
         vec![a.velocity.y, -b.velocity.y, b.position.y - a.position.y],
-    ];
+    ]);
 
-    let (t_a, t_b) = match solve_cramers_rule(&matrix) {
-        Some(solution) => (solution[0], solution[1]),
-        None => return false,
+    let (t_a, t_b) = match linalg::solve(&matrix) {
+        Ok(solution) => (solution[0].clone(), solution[1].clone()),
+        Err(_) => return false,
     };
 
     // Intersection coordinates
-    let x = a.position.x as f64 + a.velocity.x as f64 * t_a;
-    let y = a.position.y as f64 + a.velocity.y as f64 * t_a;
+    let x = rational(a.position.x) + rational(a.velocity.x) * &t_a;
+    let y = rational(a.position.y) + rational(a.velocity.y) * &t_a;
+
+    let (Some(x), Some(y)) = (x.to_f64(), y.to_f64()) else {
+        return false;
+    };
 
     let is_within_area = x >= min && y >= min && x <= max && y <= max;
-    let is_in_future = t_a > 0.0 && t_b > 0.0;
+    let is_in_future = t_a.is_positive() && t_b.is_positive();
 
     is_within_area && is_in_future
 }
@@ -210,7 +131,7 @@ fn find_position(hailstones: Vec<Hailstone>) -> Result<Vec3, AocError> {
     // Take a sample of five hailstones from the input, and do this for each of the pairs.
     // This results in a system of four equations with four unknowns (X, Y, VX and VY)
 
-    // Represent this system as a 5x4 coefficients matrix and solve it using Cramer's Rule
+    // Represent this system as a 5x4 coefficients matrix and solve it using Gaussian elimination
     let matrix: Vec<Vec<i64>> = hailstones
         .iter()
         .tuple_windows()
@@ -229,10 +150,8 @@ fn find_position(hailstones: Vec<Hailstone>) -> Result<Vec3, AocError> {
         })
         .collect();
 
-    let (x, y, vx, _vy) = match solve_cramers_rule(&matrix) {
-        Some(solution) => (solution[0], solution[1], solution[2], solution[3]),
-        None => return Err(AocError::logic("No solution was found")),
-    };
+    let solution = linalg::solve(&linalg::from_integers(&matrix))?;
+    let (x, y, vx) = (&solution[0], &solution[1], &solution[2]);
 
     // With these we can get the value of t for every hailstone collision.
 
@@ -240,32 +159,25 @@ fn find_position(hailstones: Vec<Hailstone>) -> Result<Vec3, AocError> {
     // system of two equations with two unknowns (Z and VZ). Solve it.
     // t_n = (z_n - Z) / (VZ - vz_n) = (x_n - X) / (VZ - vx_n)
     // Z + t_n * VZ = z_n + t_n * vz_n
+    let collision = |hailstone: &Hailstone| -> Result<Vec<BigRational>, AocError> {
+        let divisor = vx - rational(hailstone.velocity.x);
+        if divisor == rational(0) {
+            return Err(AocError::logic("Hailstone moves parallel to the rock"));
+        }
+
+        let t = (rational(hailstone.position.x) - x) / divisor;
+        let z = rational(hailstone.position.z) + &t * rational(hailstone.velocity.z);
 
-    let t0 = (hailstones[0].position.x as f64 - x) / (vx - hailstones[0].velocity.x as f64);
-    let t1 = (hailstones[1].position.x as f64 - x) / (vx - hailstones[1].velocity.x as f64);
-
-    let matrix = vec![
-        vec![
-            1,
-            t0 as i64,
-            hailstones[0].position.z + t0 as i64 * hailstones[0].velocity.z,
-        ],
-        vec![
-            1,
-            t1 as i64,
-            hailstones[1].position.z + t1 as i64 * hailstones[1].velocity.z,
-        ],
-    ];
-
-    let (z, _vz) = match solve_cramers_rule(&matrix) {
-        Some(solution) => (solution[0], solution[1]),
-        None => return Err(AocError::logic("No solution was found")),
+        Ok(vec![rational(1), t, z])
     };
 
+    let matrix = vec![collision(&hailstones[0])?, collision(&hailstones[1])?];
+    let z = &linalg::solve(&matrix)?[0];
+
     Ok(Vec3 {
-        x: x as i64,
-        y: y as i64,
-        z: z as i64,
+        x: linalg::to_integer(x)?,
+        y: linalg::to_integer(y)?,
+        z: linalg::to_integer(z)?,
     })
 }
 
@@ -329,32 +241,4 @@ mod tests {
     fn it_solves_part2_real() {
         assert_eq!(Day24.part_2(Day24.default_input()), Ok(1007148211789625));
     }
-
-    #[rustfmt::skip]
-    #[test]
-    fn it_calculates_determinant() {
-        assert_eq!(
-            determinant([
-                vec![3, 7],
-                vec![1,-4]].as_slice()),
-            BigInt::from(-19)
-        );
-
-        assert_eq!(
-            determinant([
-                vec![-2,-1, 2],
-                vec![ 2, 1, 4],
-                vec![-3, 3,-1]].as_slice()),
-            BigInt::from(54)
-        );
-
-        assert_eq!(
-            determinant([
-                vec![-1, 1, 4, 2],
-                vec![ 2,-1, 2, 5],
-                vec![ 1, 2, 3, 4],
-                vec![ 3, 4,-1, 2]].as_slice()),
-            BigInt::from(-26)
-        )
-    }
 }
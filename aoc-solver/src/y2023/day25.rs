@@ -1,12 +1,16 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
-};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
 
 use crate::solution::{AocError, Solution};
+use crate::visualization::{
+    self, lay_out_graphs, unknown_visualization, Edge, Frame, Frames, Node, Visualize,
+};
 
 pub struct Day25;
 
+const CUT_SIZE: usize = 3;
+
 type Graph = HashMap<String, HashSet<String>>;
 
 fn parse(input: &str) -> Result<Graph, AocError> {
@@ -26,70 +30,82 @@ fn parse(input: &str) -> Result<Graph, AocError> {
     Ok(graph)
 }
 
-#[derive(Clone, Eq, PartialEq)]
-struct Search {
-    distance: u32,
-    position: String,
-    visited: HashSet<String>,
+/// Wires that have to be cut to split the components into two groups
+struct Cut<'a> {
+    wires: Vec<(&'a str, &'a str)>,
+    /// Components on one side of the cut
+    group: HashSet<&'a str>,
 }
 
-impl Ord for Search {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.distance.cmp(&self.distance)
-    }
-}
-
-impl PartialOrd for Search {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// Breadth first search through the wires that can still take more flow. Returns the path
+/// to the sink, or all of the components that could be reached when the sink couldn't.
+fn augmenting_path<'a>(
+    graph: &'a Graph,
+    source: &'a str,
+    sink: &'a str,
+    flow: &HashSet<(&'a str, &'a str)>,
+) -> Result<Vec<(&'a str, &'a str)>, HashSet<&'a str>> {
+    let mut parents: HashMap<&str, &str> = HashMap::new();
+    let mut visited: HashSet<&str> = HashSet::from([source]);
+    let mut queue = VecDeque::from([source]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == sink {
+            let mut path = Vec::new();
+            let mut node = sink;
+
+            while let Some(parent) = parents.get(node) {
+                path.push((*parent, node));
+                node = parent;
+            }
 
-fn dijkstra(graph: &Graph, start: &str, target: &str) -> Option<u32> {
-    let mut dist: HashMap<String, u32> = HashMap::new();
-    let mut heap: BinaryHeap<Search> = BinaryHeap::new();
-
-    heap.push(Search {
-        distance: 0,
-        position: start.to_owned(),
-        visited: HashSet::new(),
-    });
-
-    while let Some(Search {
-        position,
-        distance,
-        mut visited,
-    }) = heap.pop()
-    {
-        if position == target {
-            return Some(dist[&position]);
+            return Ok(path);
         }
 
-        if !visited.insert(position.clone()) {
-            continue;
+        for next in graph[current].iter() {
+            // Every wire can carry one unit of flow in either direction
+            if !flow.contains(&(current, next.as_str())) && visited.insert(next) {
+                parents.insert(next, current);
+                queue.push_back(next);
+            }
         }
+    }
 
-        if distance > *dist.get(&position).unwrap_or(&u32::MAX) {
-            continue;
-        }
+    Err(visited)
+}
 
-        for edge in graph[&position].iter() {
-            if edge == target && position == start {
-                // We've cut the connection from start to target
-                continue;
-            }
+/// Finds the minimum cut between the first component and any component on the other side.
+/// Components on the same side are connected by more than `CUT_SIZE` separate paths.
+fn find_cut(graph: &Graph) -> Option<Cut> {
+    let source = graph.keys().min()?.as_str();
 
-            let next = Search {
-                position: edge.clone(),
-                distance: distance + 1,
-                visited: visited.clone(),
-            };
+    for sink in graph.keys().sorted() {
+        if sink == source {
+            continue;
+        }
 
-            let best_known = dist.entry(next.position.clone()).or_insert(u32::MAX);
+        let mut flow: HashSet<(&str, &str)> = HashSet::new();
 
-            if next.distance < *best_known {
-                *best_known = next.distance;
-                heap.push(next)
+        for _ in 0..=CUT_SIZE {
+            match augmenting_path(graph, source, sink, &flow) {
+                Ok(path) => {
+                    for (from, to) in path {
+                        // Flow back along a wire cancels out the flow already on it
+                        if !flow.remove(&(to, from)) {
+                            flow.insert((from, to));
+                        }
+                    }
+                }
+                Err(group) => {
+                    let wires = group
+                        .iter()
+                        .flat_map(|from| graph[*from].iter().map(move |to| (*from, to.as_str())))
+                        .filter(|(_, to)| !group.contains(to))
+                        .sorted()
+                        .collect();
+
+                    return Some(Cut { wires, group });
+                }
             }
         }
     }
@@ -97,13 +113,44 @@ fn dijkstra(graph: &Graph, start: &str, target: &str) -> Option<u32> {
     None
 }
 
-fn traverse(current: String, graph: &Graph, visited: &mut HashSet<String>) {
-    if !visited.insert(current.clone()) {
-        return;
-    }
+fn render(graph: &Graph, cut: &Cut, wires_cut: usize, show_groups: bool) -> visualization::Graph {
+    let names: Vec<&str> = graph.keys().map(|name| name.as_str()).sorted().collect();
+    let index = |name: &str| names.binary_search(&name).unwrap_or_default();
+
+    let nodes = names
+        .iter()
+        .map(|name| Node {
+            label: name.to_string(),
+            color: match (show_groups, cut.group.contains(name)) {
+                (false, _) => "#009900",
+                (true, true) => "#ffff66",
+                (true, false) => "#66ccff",
+            }
+            .into(),
+        })
+        .collect();
+
+    let edges = names
+        .iter()
+        .flat_map(|from| graph[*from].iter().map(move |to| (*from, to.as_str())))
+        .filter(|(from, to)| from < to)
+        .map(|(from, to)| {
+            let is_cut = cut.wires[..wires_cut]
+                .iter()
+                .any(|wire| *wire == (from, to) || *wire == (to, from));
+
+            Edge {
+                from: index(from),
+                to: index(to),
+                color: if is_cut { "#ff6666" } else { "#333340" }.into(),
+            }
+        })
+        .collect();
 
-    for edge in graph[&current].iter() {
-        traverse(edge.clone(), graph, visited)
+    visualization::Graph {
+        nodes,
+        edges,
+        ..Default::default()
     }
 }
 
@@ -116,55 +163,13 @@ impl Solution for Day25 {
     }
 
     fn part_1(&self, input: &str) -> Result<u32, AocError> {
-        let mut graph = parse(input)?;
-
-        loop {
-            let starts: Vec<String> = graph.keys().cloned().collect();
-
-            let mut max = (0, String::new(), String::new());
-
-            for start in starts {
-                // Check what the distance to the current's neighbours would be if we cut the connection
-                let edges = graph.get(&start).cloned().unwrap();
-
-                for edge in edges.iter() {
-                    // TODO: If we've already calculated A -> B, don't calculate B -> A
-                    // TODO: Cache the distances we see while searching for the distance
-                    let distance = dijkstra(&graph, &start, edge);
+        let graph = parse(input)?;
+        let cut = find_cut(&graph).ok_or(AocError::logic("No cut found"))?;
 
-                    match distance {
-                        Some(distance) if distance > max.0 => {
-                            max = (distance, start.clone(), edge.clone())
-                        }
-                        None => {
-                            // We're done and the graph is now split into two groups!
-                            // Determine the sizes of these groups by traversing one of them
-
-                            // Cut the max connection permanently
-                            graph.entry(start.clone()).or_default().remove(edge);
-                            graph.entry(edge.clone()).or_default().remove(&start);
-
-                            let mut visited = HashSet::new();
-
-                            traverse(start, &graph, &mut visited);
+        let size_1 = cut.group.len();
+        let size_2 = graph.len() - size_1;
 
-                            let size_1 = visited.len();
-                            let size_2 = graph.len() - size_1;
-
-                            return Ok((size_1 * size_2) as u32);
-                        }
-                        _ => {}
-                    }
-                }
-
-                *graph.entry(start).or_default() = edges.clone();
-            }
-
-            // The edge that leads to max distance to its neighbour is
-            // along separation line, cut that connection permanently
-            graph.entry(max.1.clone()).or_default().remove(&max.2);
-            graph.entry(max.2).or_default().remove(&max.1);
-        }
+        Ok((size_1 * size_2) as u32)
     }
 
     fn part_2(&self, _input: &str) -> Result<String, AocError> {
@@ -185,6 +190,42 @@ impl Solution for Day25 {
     }
 }
 
+impl Visualize for Day25 {
+    fn visualize(&self, name: &str, input: &str) -> Result<Frames, AocError> {
+        if name != "wiring" {
+            return Err(unknown_visualization(name));
+        }
+
+        let graph = parse(input)?;
+        let cut = find_cut(&graph).ok_or(AocError::logic("No cut found"))?;
+
+        let mut frames = vec![Frame::graph(
+            format!("{} components", graph.len()),
+            render(&graph, &cut, 0, false),
+        )];
+
+        for (cuts, (from, to)) in cut.wires.iter().enumerate() {
+            frames.push(Frame::graph(
+                format!("Cut the wire between {from} and {to}"),
+                render(&graph, &cut, cuts + 1, false),
+            ));
+        }
+
+        frames.push(Frame::graph(
+            format!(
+                "Groups of {} and {} components",
+                cut.group.len(),
+                graph.len() - cut.group.len()
+            ),
+            render(&graph, &cut, cut.wires.len(), true),
+        ));
+
+        lay_out_graphs(&mut frames);
+
+        Ok(Box::new(frames.into_iter()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +250,27 @@ mod tests {
     fn it_solves_part1_example() {
         assert_eq!(Day25.part_1(EXAMPLE_INPUT), Ok(54));
     }
+
+    #[test]
+    fn it_finds_the_wires_to_cut() {
+        let graph = parse(EXAMPLE_INPUT).unwrap();
+        let cut = find_cut(&graph).unwrap();
+
+        let mut wires: Vec<(&str, &str)> = cut
+            .wires
+            .iter()
+            .map(|(from, to)| (*from.min(to), *from.max(to)))
+            .collect();
+        wires.sort();
+
+        assert_eq!(wires, vec![("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+    }
+
+    #[test]
+    fn it_visualizes_the_groups() {
+        let frames: Vec<_> = Day25.visualize("wiring", EXAMPLE_INPUT).unwrap().collect();
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[4].caption, "Groups of 6 and 9 components");
+    }
 }
@@ -1,4 +1,4 @@
-use crate::solution::{Solution, Solver};
+use crate::solution::{DayInfo, Part, Solution, Solver};
 
 pub mod day01;
 pub mod day02;
@@ -28,36 +28,64 @@ pub mod day25;
 
 pub const MAX_DAYS: u8 = 25;
 
+const DAYS: &[DayInfo] = &[
+    DayInfo::new(1),
+    DayInfo::new(2),
+    DayInfo::new(3),
+    DayInfo::new(4),
+    DayInfo::new(5),
+    DayInfo::new(6),
+    DayInfo::new(7),
+    DayInfo::new(8),
+    DayInfo::new(9),
+    DayInfo::with_visualizations(10, &["loop"]),
+    DayInfo::new(11),
+    DayInfo::new(12),
+    DayInfo::new(13),
+    DayInfo::with_visualizations(14, &["rocks"]),
+    DayInfo::new(15),
+    DayInfo::with_visualizations(16, &["beams"]),
+    DayInfo::with_visualizations(17, &["crucible"]),
+    DayInfo::new(18),
+    DayInfo::new(19),
+    DayInfo::with_visualizations(20, &["pulses"]),
+    DayInfo::with_visualizations(21, &["steps"]),
+    DayInfo::new(22),
+    DayInfo::new(23),
+    DayInfo::new(24),
+    DayInfo::with_visualizations(25, &["wiring"]),
+];
+
 pub struct Y2023;
 
 impl Solver for Y2023 {
-    fn run_solution(day: u8, input: Option<String>) -> Vec<String> {
+    fn run_parts(day: u8, input: Option<String>, parts: &[Part]) -> Vec<String> {
         match day {
-            1 => day01::Day01.run(input, 1, 2023),
-            2 => day02::Day02.run(input, 2, 2023),
-            3 => day03::Day03.run(input, 3, 2023),
-            4 => day04::Day04.run(input, 4, 2023),
-            5 => day05::Day05.run(input, 5, 2023),
-            6 => day06::Day06.run(input, 6, 2023),
-            7 => day07::Day07.run(input, 7, 2023),
-            8 => day08::Day08.run(input, 8, 2023),
-            9 => day09::Day09.run(input, 9, 2023),
-            10 => day10::Day10.run(input, 10, 2023),
-            11 => day11::Day11.run(input, 11, 2023),
-            12 => day12::Day12.run(input, 12, 2023),
-            13 => day13::Day13.run(input, 13, 2023),
-            14 => day14::Day14.run(input, 14, 2023),
-            15 => day15::Day15.run(input, 15, 2023),
-            16 => day16::Day16.run(input, 16, 2023),
-            17 => day17::Day17.run(input, 17, 2023),
-            18 => day18::Day18.run(input, 18, 2023),
-            19 => day19::Day19.run(input, 19, 2023),
-            20 => day20::Day20.run(input, 20, 2023),
-            21 => day21::Day21.run(input, 21, 2023),
-            22 => day22::Day22.run(input, 22, 2023),
-            23 => day23::Day23.run(input, 23, 2023),
-            24 => day24::Day24.run(input, 24, 2023),
-            25 => day25::Day25.run(input, 25, 2023),
+            1 => day01::Day01.run_parts(input, 1, 2023, parts),
+            2 => day02::Day02.run_parts(input, 2, 2023, parts),
+            3 => day03::Day03.run_parts(input, 3, 2023, parts),
+            4 => day04::Day04.run_parts(input, 4, 2023, parts),
+            5 => day05::Day05.run_parts(input, 5, 2023, parts),
+            6 => day06::Day06.run_parts(input, 6, 2023, parts),
+            7 => day07::Day07.run_parts(input, 7, 2023, parts),
+            8 => day08::Day08.run_parts(input, 8, 2023, parts),
+            9 => day09::Day09.run_parts(input, 9, 2023, parts),
+            10 => day10::Day10.run_parts(input, 10, 2023, parts),
+            11 => day11::Day11.run_parts(input, 11, 2023, parts),
+            12 => day12::Day12.run_parts(input, 12, 2023, parts),
+            13 => day13::Day13.run_parts(input, 13, 2023, parts),
+            14 => day14::Day14.run_parts(input, 14, 2023, parts),
+            15 => day15::Day15.run_parts(input, 15, 2023, parts),
+            16 => day16::Day16.run_parts(input, 16, 2023, parts),
+            17 => day17::Day17.run_parts(input, 17, 2023, parts),
+            18 => day18::Day18.run_parts(input, 18, 2023, parts),
+            19 => day19::Day19.run_parts(input, 19, 2023, parts),
+            20 => day20::Day20.run_parts(input, 20, 2023, parts),
+            21 => day21::Day21.run_parts(input, 21, 2023, parts),
+            22 => day22::Day22.run_parts(input, 22, 2023, parts),
+            23 => day23::Day23.run_parts(input, 23, 2023, parts),
+            24 => day24::Day24.run_parts(input, 24, 2023, parts),
+            25 => day25::Day25.run_parts(input, 25, 2023, parts),
             _ => vec![String::from("Solution not implemented (yet?)")],
         }
     }
@@ -103,4 +131,8 @@ impl Solver for Y2023 {
             _ => unimplemented!(),
         }
     }
+
+    fn days() -> &'static [DayInfo] {
+        DAYS
+    }
 }
@@ -0,0 +1,204 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+use yew_agent::reactor::{use_reactor_bridge, ReactorEvent};
+
+use aoc_solver::registry;
+
+use crate::runner::{Output, PartOutput, Request, SolutionRequest, SolutionTask};
+
+#[derive(Properties, PartialEq)]
+pub struct DashboardProps {
+    pub year: u32,
+}
+
+#[derive(Clone, PartialEq)]
+enum Status {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Clone, PartialEq)]
+struct Row {
+    day: u8,
+    answers: [Option<String>; 2],
+    duration: i64,
+    status: Status,
+}
+
+#[derive(Default, PartialEq)]
+struct DashboardState {
+    id: u32,
+    rows: Vec<Row>,
+}
+
+enum DashboardAction {
+    Start(u32),
+    Output(PartOutput),
+}
+
+/// Strips the `[2023/01][Part 1] ` prefix of the output line.
+fn answer(output: &str) -> &str {
+    output
+        .split_once("] ")
+        .map(|(_, answer)| answer)
+        .unwrap_or(output)
+}
+
+impl Reducible for DashboardState {
+    type Action = DashboardAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            DashboardAction::Start(year) => {
+                let rows = registry::days(year)
+                    .iter()
+                    .enumerate()
+                    .map(|(index, info)| Row {
+                        day: info.day,
+                        answers: [None, None],
+                        duration: 0,
+                        status: if index == 0 {
+                            Status::Running
+                        } else {
+                            Status::Pending
+                        },
+                    })
+                    .collect();
+
+                Rc::new(DashboardState {
+                    id: self.id + 1,
+                    rows,
+                })
+            }
+            DashboardAction::Output(output) if output.id != self.id => self,
+            DashboardAction::Output(output) => {
+                let mut rows = self.rows.clone();
+
+                let Some(index) = rows.iter().position(|row| row.day == output.day) else {
+                    return self;
+                };
+
+                let row = &mut rows[index];
+                let answer = answer(&output.output);
+                if answer.starts_with("Error") {
+                    row.status = Status::Failed;
+                }
+                row.answers[usize::from(output.part - 1)] = Some(answer.to_owned());
+                row.duration += output.duration;
+
+                if output.done {
+                    if row.status != Status::Failed {
+                        row.status = Status::Done;
+                    }
+                    // The worker runs the days one after another
+                    if let Some(next) = rows.get_mut(index + 1) {
+                        next.status = Status::Running;
+                    }
+                }
+
+                Rc::new(DashboardState { id: self.id, rows })
+            }
+        }
+    }
+}
+
+#[function_component(Dashboard)]
+pub fn dashboard(props: &DashboardProps) -> Html {
+    let state = use_reducer(DashboardState::default);
+
+    let bridge = {
+        let state = state.clone();
+        use_reactor_bridge::<SolutionTask, _>(move |event| {
+            if let ReactorEvent::Output(Output::Part(output)) = event {
+                state.dispatch(DashboardAction::Output(output));
+            }
+        })
+    };
+
+    let year = props.year;
+
+    let run_all = {
+        let state = state.clone();
+
+        move || {
+            let id = state.id + 1;
+            state.dispatch(DashboardAction::Start(year));
+
+            for info in registry::days(year) {
+                bridge.send(Request::Solve(SolutionRequest {
+                    id,
+                    year,
+                    day: info.day,
+                    input: None,
+                    part: None,
+                }));
+            }
+        }
+    };
+
+    let onclick = {
+        let run_all = run_all.clone();
+        Callback::from(move |_: MouseEvent| run_all())
+    };
+
+    use_effect_with(year, move |_| run_all());
+
+    let total: i64 = state.rows.iter().map(|row| row.duration).sum();
+    let is_running = state
+        .rows
+        .iter()
+        .any(|row| matches!(row.status, Status::Pending | Status::Running));
+
+    html! {
+        <div class="fade-in dashboard">
+            <table>
+                <thead>
+                    <tr>
+                        <th>{"Day"}</th>
+                        <th>{"Part 1"}</th>
+                        <th>{"Part 2"}</th>
+                        <th>{"Status"}</th>
+                        <th>{"Time"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {
+                        for state.rows.iter().map(|row| {
+                            let (status, class) = match row.status {
+                                Status::Pending => ("Pending", ""),
+                                Status::Running => ("Running...", ""),
+                                Status::Done => ("*", "success"),
+                                Status::Failed => ("Failed", "failure"),
+                            };
+
+                            let answers = row.answers.iter().map(|answer| {
+                                html! { <td>{ answer.clone().unwrap_or_default() }</td> }
+                            });
+
+                            html! {
+                                <tr>
+                                    <td>{ row.day }</td>
+                                    { for answers }
+                                    <td class={class}>{ status }</td>
+                                    <td class="duration">{ format!("{} ms", row.duration) }</td>
+                                </tr>
+                            }
+                        })
+                    }
+                </tbody>
+                <tfoot>
+                    <tr>
+                        <td colspan="4">{"Total"}</td>
+                        <td class="duration">{ format!("{total} ms") }</td>
+                    </tr>
+                </tfoot>
+            </table>
+            <div class="links">
+                <button {onclick} disabled={is_running}>{"[Run again]"}</button>
+            </div>
+        </div>
+    }
+}
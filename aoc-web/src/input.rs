@@ -0,0 +1,102 @@
+use gloo::file::callbacks::FileReader;
+use web_sys::{DragEvent, HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct InputPanelProps {
+    /// Input to start with, eg. from a shared link
+    #[prop_or_default]
+    pub initial: Option<String>,
+    /// Emits the pasted input, or `None` when going back to the default input
+    pub onrun: Callback<Option<String>>,
+}
+
+#[function_component(InputPanel)]
+pub fn input_panel(props: &InputPanelProps) -> Html {
+    let draft = {
+        let initial = props.initial.clone();
+        use_state(move || initial.unwrap_or_default())
+    };
+    let reader = use_mut_ref(|| None::<FileReader>);
+
+    let read_file = {
+        let draft = draft.clone();
+
+        move |file: Option<web_sys::File>| {
+            let Some(file) = file else {
+                return;
+            };
+
+            let draft = draft.clone();
+            let task =
+                gloo::file::callbacks::read_as_text(&file.into(), move |result| match result {
+                    Ok(text) => draft.set(text),
+                    Err(err) => log::error!("Failed to read input file: {err}"),
+                });
+
+            // The read gets cancelled if the reader is dropped
+            *reader.borrow_mut() = Some(task);
+        }
+    };
+
+    let oninput = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            draft.set(e.target_unchecked_into::<HtmlTextAreaElement>().value());
+        })
+    };
+
+    let onchange = {
+        let read_file = read_file.clone();
+        Callback::from(move |e: Event| {
+            let files = e.target_unchecked_into::<HtmlInputElement>().files();
+            read_file(files.and_then(|files| files.get(0)));
+        })
+    };
+
+    let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+
+    let ondrop = Callback::from(move |e: DragEvent| {
+        e.prevent_default();
+        let files = e.data_transfer().and_then(|transfer| transfer.files());
+        read_file(files.and_then(|files| files.get(0)));
+    });
+
+    let onrun = {
+        let draft = draft.clone();
+        props.onrun.reform(move |_: MouseEvent| {
+            if draft.trim().is_empty() {
+                None
+            } else {
+                Some((*draft).clone())
+            }
+        })
+    };
+
+    let onreset = {
+        let draft = draft.clone();
+        props.onrun.reform(move |_: MouseEvent| {
+            draft.set(String::new());
+            None
+        })
+    };
+
+    html! {
+        <div class="input-panel" {ondragover} {ondrop}>
+            <textarea
+                placeholder="Paste your puzzle input here or drop a file"
+                spellcheck="false"
+                value={(*draft).clone()}
+                {oninput}
+            />
+            <div class="links">
+                <button onclick={onrun}>{"[Run]"}</button>
+                <button onclick={onreset}>{"[Default input]"}</button>
+                <label>
+                    {"[Open file]"}
+                    <input type="file" {onchange} />
+                </label>
+            </div>
+        </div>
+    }
+}
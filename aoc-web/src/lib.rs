@@ -1,11 +1,15 @@
+pub mod dashboard;
 pub mod footer;
 pub mod header;
 pub mod home;
+pub mod input;
 pub mod navlink;
 pub mod router;
 pub mod runner;
+pub mod share;
 pub mod solution;
 pub mod syntax;
+pub mod visualization;
 pub mod year;
 
 pub mod y2020;
@@ -1,10 +1,13 @@
 use yew::prelude::*;
 use yew_agent::oneshot::OneshotProvider;
+use yew_agent::reactor::ReactorProvider;
 use yew_router::prelude::*;
 
+use aoc_solver::registry::{self, YEARS};
+
 use crate::{
-    header::Header, home::Home, runner::SolutionTask, solution::Solution,
-    syntax::SyntaxHighlightTask, y2022,
+    dashboard::Dashboard, header::Header, home::Home, runner::SolutionTask, solution::Solution,
+    syntax::SyntaxHighlightTask, visualization::Visualizer,
 };
 
 #[derive(Clone, Routable, PartialEq)]
@@ -13,14 +16,12 @@ pub enum Route {
     Index,
     #[at("/:year")]
     Home { year: u32 },
+    #[at("/:year/all")]
+    Dashboard { year: u32 },
     #[at("/:year/:day")]
     Solution { year: u32, day: u8 },
-    #[at("/2022/9/rope")]
-    Rope,
-    #[at("/2022/18/lava")]
-    Lava,
-    #[at("/2022/22/cube")]
-    Cube,
+    #[at("/:year/:day/:name")]
+    Visualization { year: u32, day: u8, name: String },
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -28,38 +29,43 @@ pub enum Route {
 
 pub fn switch(route: Route) -> Html {
     let year = match route {
-        Route::Index | Route::NotFound => 2023,
-        Route::Solution { year, day: _ } | Route::Home { year } => year,
-        Route::Lava | Route::Rope | Route::Cube => 2022,
+        Route::Index | Route::NotFound => YEARS[0],
+        Route::Solution { year, .. }
+        | Route::Home { year }
+        | Route::Dashboard { year }
+        | Route::Visualization { year, .. } => year,
     };
 
-    let main = match route {
-        Route::Index => html! { <Home year={2023} /> },
-        Route::Home { year } => html! { <Home year={year} /> },
+    let not_found = html! {<h1>{ "Not Found :(" }</h1>};
+
+    let main = match &route {
+        Route::Index => html! { <Home year={YEARS[0]} /> },
+        Route::Home { year } => html! { <Home year={*year} /> },
+        Route::Dashboard { year } => html! { <Dashboard year={*year} /> },
         Route::Solution { year, day } => {
-            html! { <Solution year={year} day={day} />}
-        }
-        Route::Rope => {
-            html! { <y2022::Rope/> }
-        }
-        Route::Lava => {
-            html! { <y2022::Lava/> }
+            html! { <Solution year={*year} day={*day} />}
         }
-        Route::Cube => {
-            html! { <y2022::Cube/> }
+        Route::Visualization { year, day, name }
+            if registry::find_day(*year, *day)
+                .is_some_and(|info| info.visualizations.contains(&name.as_str())) =>
+        {
+            html! {
+                <Visualizer key={format!("{year}/{day}/{name}")} year={*year} day={*day} name={name.clone()} />
+            }
         }
-        Route::NotFound => html! {<h1>{ "Not Found :(" }</h1>},
+        Route::Visualization { .. } => not_found,
+        Route::NotFound => not_found,
     };
 
     html! {
         <>
             <Header year={year} route={route} />
             <main class="fade-in">
-                <OneshotProvider<SolutionTask> path="/solution-worker.js">
+                <ReactorProvider<SolutionTask> path="/solution-worker.js">
                     <OneshotProvider<SyntaxHighlightTask> path="/syntax-worker.js">
                         { main }
                     </OneshotProvider<SyntaxHighlightTask>>
-                </OneshotProvider<SolutionTask>>
+                </ReactorProvider<SolutionTask>>
             </main>
         </>
     }
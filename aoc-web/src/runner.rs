@@ -1,23 +1,152 @@
+use std::time::Duration;
+
 use chrono::Local;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use yew::platform::time::sleep;
 use yew_agent::prelude::*;
 
-use aoc_solver::{solution::Solver, y2020::Y2020, y2021::Y2021, y2022::Y2022, y2023::Y2023};
+use aoc_solver::visualization::Frame;
+use aoc_solver::{registry, solution::Part};
+
+/// Keeps the memory use of very long visualizations in check
+const MAX_FRAMES: usize = 20_000;
+
+/// How many frames are sent to the page at once
+const FRAME_BATCH: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Request {
+    Solve(SolutionRequest),
+    Visualize(VisualizationRequest),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Output {
+    Part(PartOutput),
+    Frames(FramesOutput),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SolutionRequest {
+    /// Identifies the run the outputs belong to
+    pub id: u32,
+    pub year: u32,
+    pub day: u8,
+    pub input: Option<String>,
+    /// Runs only the given part instead of both
+    pub part: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PartOutput {
+    pub id: u32,
+    pub day: u8,
+    pub part: u8,
+    pub output: String,
+    pub duration: i64,
+    /// Whether this was the last part of the run
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VisualizationRequest {
+    pub year: u32,
+    pub day: u8,
+    pub name: String,
+    pub input: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FramesOutput {
+    /// The next frames of the visualization, or why it couldn't be made
+    pub frames: Result<Vec<Frame>, String>,
+    /// Whether this was the last batch of frames
+    pub done: bool,
+}
+
+#[reactor]
+pub async fn SolutionTask(mut scope: ReactorScope<Request, Output>) {
+    while let Some(request) = scope.next().await {
+        let sent = match request {
+            Request::Solve(request) => solve(&mut scope, request).await,
+            Request::Visualize(request) => visualize(&mut scope, request).await,
+        };
+
+        if !sent {
+            return;
+        }
+    }
+}
+
+/// Sends the outputs of the parts one at a time. Returns false if the bridge is gone.
+async fn solve(scope: &mut ReactorScope<Request, Output>, request: SolutionRequest) -> bool {
+    let parts: Vec<(u8, Part)> = (1..)
+        .zip(Part::ALL)
+        .filter(|(number, _)| request.part.map_or(true, |part| part == *number))
+        .collect();
+
+    for (index, (number, part)) in parts.iter().enumerate() {
+        let start = Local::now();
+        let output =
+            registry::run_parts(request.year, request.day, request.input.clone(), &[*part]);
+        let duration = (Local::now() - start).num_milliseconds();
+
+        let output = PartOutput {
+            id: request.id,
+            day: request.day,
+            part: *number,
+            output: output.join("\n"),
+            duration,
+            done: index == parts.len() - 1,
+        };
+
+        if scope.send(Output::Part(output)).await.is_err() {
+            return false;
+        }
+
+        // The output only gets posted to the page once the worker is idle,
+        // yield before blocking it again with the next part.
+        sleep(Duration::ZERO).await;
+    }
+
+    true
+}
 
-#[oneshot]
-pub async fn SolutionTask(input: (u32, u8)) -> (String, i64) {
-    let (year, day) = input;
+/// Streams the frames of the visualization in batches. Returns false if the bridge is gone.
+async fn visualize(
+    scope: &mut ReactorScope<Request, Output>,
+    request: VisualizationRequest,
+) -> bool {
+    let mut frames =
+        match registry::visualize(request.year, request.day, &request.name, request.input) {
+            Ok(frames) => frames.take(MAX_FRAMES).peekable(),
+            Err(err) => {
+                let output = FramesOutput {
+                    frames: Err(err.to_string()),
+                    done: true,
+                };
+                return scope.send(Output::Frames(output)).await.is_ok();
+            }
+        };
 
-    let start = Local::now();
+    loop {
+        let batch: Vec<Frame> = frames.by_ref().take(FRAME_BATCH).collect();
+        let output = FramesOutput {
+            frames: Ok(batch),
+            done: frames.peek().is_none(),
+        };
+        let done = output.done;
 
-    let output = match year {
-        2020 => Y2020::run_solution(day, None),
-        2021 => Y2021::run_solution(day, None),
-        2022 => Y2022::run_solution(day, None),
-        2023 => Y2023::run_solution(day, None),
-        _ => vec!["Missing year".to_string()],
-    };
+        if scope.send(Output::Frames(output)).await.is_err() {
+            return false;
+        }
 
-    let duration = (Local::now() - start).num_milliseconds();
+        if done {
+            return true;
+        }
 
-    (output.join("\n"), duration)
+        // Same as with the parts, let the batch get posted before computing the next one
+        sleep(Duration::ZERO).await;
+    }
 }
@@ -0,0 +1,97 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use serde::{Deserialize, Serialize};
+
+use aoc_solver::solution::Part;
+
+const COMPRESSION_LEVEL: u8 = 9;
+/// Puzzle inputs are far smaller, so a link inflating past this is not a valid link
+const MAX_INPUT_SIZE: usize = 4 * 1024 * 1024;
+
+/// Query parameters of a shared solution link, eg. `/2023/1?input=...&part=2`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct SolutionQuery {
+    /// Custom input, deflated and base64 encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    /// Only run this part of the solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<u8>,
+}
+
+impl SolutionQuery {
+    pub fn new(input: Option<&str>, part: Option<u8>) -> Self {
+        SolutionQuery {
+            input: input.map(encode_input),
+            part,
+        }
+    }
+
+    pub fn decoded_input(&self) -> Result<Option<String>, String> {
+        self.input
+            .as_ref()
+            .map(|input| decode_input(input).ok_or("The input of the link is invalid".to_string()))
+            .transpose()
+    }
+
+    /// The part to run, or `None` to run every part
+    pub fn checked_part(&self) -> Result<Option<u8>, String> {
+        match self.part {
+            Some(part) if !(1..=Part::ALL.len()).contains(&(part as usize)) => {
+                Err(format!("Part {part} of the link doesn't exist"))
+            }
+            part => Ok(part),
+        }
+    }
+}
+
+pub fn encode_input(input: &str) -> String {
+    URL_SAFE_NO_PAD.encode(compress_to_vec(input.as_bytes(), COMPRESSION_LEVEL))
+}
+
+pub fn decode_input(encoded: &str) -> Option<String> {
+    let compressed = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+    let bytes = decompress_to_vec_with_limit(&compressed, MAX_INPUT_SIZE).ok()?;
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_encoded_inputs() {
+        let input = "1abc2\npqr3stu8vwx\n";
+        assert_eq!(decode_input(&encode_input(input)).as_deref(), Some(input));
+        assert_eq!(decode_input("not base64!"), None);
+    }
+
+    #[test]
+    fn it_rejects_inputs_inflating_past_the_limit() {
+        let input = "a".repeat(MAX_INPUT_SIZE);
+        assert!(decode_input(&encode_input(&input)).is_some());
+
+        let encoded = encode_input(&"a".repeat(MAX_INPUT_SIZE + 1));
+        assert!(encoded.len() < 16 * 1024);
+        assert_eq!(decode_input(&encoded), None);
+    }
+
+    #[test]
+    fn it_rejects_invalid_links() {
+        let query = SolutionQuery::new(Some("1abc2"), Some(2));
+        assert_eq!(query.decoded_input(), Ok(Some("1abc2".to_string())));
+        assert_eq!(query.checked_part(), Ok(Some(2)));
+        assert_eq!(SolutionQuery::default().decoded_input(), Ok(None));
+
+        let query = SolutionQuery {
+            input: Some("not base64!".to_string()),
+            part: Some(3),
+        };
+        assert!(query.decoded_input().is_err());
+        assert!(query.checked_part().is_err());
+        assert!(SolutionQuery::new(None, Some(0)).checked_part().is_err());
+    }
+}
@@ -1,10 +1,17 @@
+use std::rc::Rc;
+
 use yew::platform::spawn_local;
 use yew::prelude::*;
 use yew_agent::oneshot::use_oneshot_runner;
+use yew_agent::reactor::{use_reactor_bridge, ReactorEvent};
+use yew_router::prelude::*;
 
-use aoc_solver::{solution::Solver, y2020::Y2020, y2021::Y2021, y2022::Y2022, y2023::Y2023};
+use aoc_solver::registry;
 
-use crate::runner::SolutionTask;
+use crate::input::InputPanel;
+use crate::router::Route;
+use crate::runner::{Output, PartOutput, Request, SolutionRequest, SolutionTask};
+use crate::share::SolutionQuery;
 use crate::syntax::SyntaxHighlightTask;
 
 #[derive(Properties, PartialEq)]
@@ -38,15 +45,7 @@ pub fn source_viewer(props: &Props) -> Html {
     let day = props.day;
 
     use_effect_with((props.day, props.year), move |_| {
-        let source = match year {
-            2020 => Y2020::get_source(day),
-            2021 => Y2021::get_source(day),
-            2022 => Y2022::get_source(day),
-            2023 => Y2023::get_source(day),
-            _ => "",
-        };
-
-        run_syntax_highlight(source.to_owned())
+        run_syntax_highlight(registry::get_source(year, day).to_owned())
     });
 
     let div = gloo::utils::document().create_element("div").unwrap();
@@ -56,35 +55,144 @@ pub fn source_viewer(props: &Props) -> Html {
     Html::VRef(div.into())
 }
 
+#[derive(Default, PartialEq)]
+struct RunState {
+    id: u32,
+    lines: Vec<String>,
+    running: bool,
+}
+
+enum RunAction {
+    Start,
+    Output(PartOutput),
+    /// The run couldn't be started
+    Fail(String),
+}
+
+impl Reducible for RunState {
+    type Action = RunAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            RunAction::Start => Rc::new(RunState {
+                id: self.id + 1,
+                lines: Vec::new(),
+                running: true,
+            }),
+            // Outputs of earlier runs may still arrive after a rerun
+            RunAction::Output(output) if output.id != self.id => self,
+            RunAction::Output(output) => {
+                let mut lines = self.lines.clone();
+                lines.push(format!("{} ({} ms)", output.output, output.duration));
+
+                Rc::new(RunState {
+                    id: self.id,
+                    lines,
+                    running: !output.done,
+                })
+            }
+            RunAction::Fail(err) => Rc::new(RunState {
+                id: self.id + 1,
+                lines: vec![err],
+                running: false,
+            }),
+        }
+    }
+}
+
 #[function_component(Solution)]
 pub fn solution(props: &Props) -> Html {
-    let output = use_state(|| "".to_string());
-    let solution_task = use_oneshot_runner::<SolutionTask>();
+    let state = use_reducer(RunState::default);
+
+    let bridge = {
+        let state = state.clone();
+        use_reactor_bridge::<SolutionTask, _>(move |event| {
+            if let ReactorEvent::Output(Output::Part(output)) = event {
+                state.dispatch(RunAction::Output(output));
+            }
+        })
+    };
 
-    let run_solution = {
-        let output = output.clone();
+    let (year, day) = (props.year, props.day);
 
-        move |input: (u32, u8)| {
-            let solution_agent = solution_task.clone();
-            let output = output.clone();
-            output.set("Running...".to_string());
+    let navigator = use_navigator();
+    let query = use_location()
+        .map(|location| location.query::<SolutionQuery>())
+        .unwrap_or(Ok(SolutionQuery::default()));
 
-            spawn_local(async move {
-                let (output_value, duration) = solution_agent.run(input).await;
-                output.set(format!("{output_value}\n{duration} ms"));
-            });
+    // A broken link shows an error instead of silently running something else
+    let shared = match &query {
+        Ok(query) => query
+            .decoded_input()
+            .and_then(|input| Ok((input, query.checked_part()?))),
+        Err(err) => Err(format!("The link is invalid: {err}")),
+    };
+    let (shared_input, part) = shared.clone().unwrap_or_default();
+
+    let run_solution = {
+        let state = state.clone();
+
+        move |input: Option<String>, part: Option<u8>| {
+            // The id is only bumped by the dispatch, so it is known in advance
+            let id = state.id + 1;
+            state.dispatch(RunAction::Start);
+            bridge.send(Request::Solve(SolutionRequest {
+                id,
+                year,
+                day,
+                input,
+                part,
+            }));
         }
     };
 
-    let input = (props.year, props.day);
+    let onrun = {
+        let run_solution = run_solution.clone();
+
+        // Keep the address bar in sync with the run, so that it can be shared as is
+        Callback::from(move |input: Option<String>| {
+            if let Some(navigator) = &navigator {
+                let route = Route::Solution { year, day };
+                let result = match (&input, part) {
+                    (None, None) => {
+                        navigator.replace(&route);
+                        Ok(())
+                    }
+                    (input, part) => navigator
+                        .replace_with_query(&route, &SolutionQuery::new(input.as_deref(), part)),
+                };
+
+                if let Err(err) = result {
+                    log::error!("Failed to update the link: {err}");
+                }
+            }
+
+            run_solution(input, part)
+        })
+    };
+
+    {
+        let state = state.clone();
+        use_effect_with((props.day, props.year), move |_| match shared {
+            Ok((input, part)) => run_solution(input, part),
+            Err(err) => state.dispatch(RunAction::Fail(err)),
+        });
+    }
 
-    use_effect_with((props.day, props.year), move |_| run_solution(input));
+    let mut output = state.lines.join("\n");
+    if state.running {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str("Running...");
+    }
 
     html! {
         <div class="fade-in">
             <pre>
-                <code>{ &*output }</code>
+                <code>{ output }</code>
             </pre>
+            <InputPanel key={format!("{year}/{day}")} initial={shared_input} {onrun} />
             <SourceViewer year={props.year} day={props.day} />
         </div>
     }
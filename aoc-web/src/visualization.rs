@@ -0,0 +1,441 @@
+use std::rc::Rc;
+
+use gloo::events::EventListener;
+use gloo::timers::callback::Interval;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, KeyboardEvent};
+use yew::prelude::*;
+use yew_agent::reactor::{use_reactor_bridge, ReactorEvent};
+
+use aoc_solver::visualization::{Content, Edge, Frame, Graph, Voxel};
+
+use crate::runner::{Output, Request, SolutionTask, VisualizationRequest};
+
+/// Playback speeds in frames per second
+const SPEEDS: [u32; 7] = [1, 2, 5, 10, 20, 30, 60];
+const DEFAULT_SPEED: usize = 3;
+
+/// Larger graphs only show the labels around the selected node
+const MAX_LABELS: usize = 100;
+
+#[derive(Properties, PartialEq)]
+pub struct VisualizerProps {
+    pub year: u32,
+    pub day: u8,
+    pub name: String,
+}
+
+#[derive(PartialEq)]
+struct Player {
+    index: usize,
+    frames: usize,
+    /// Whether the worker is still producing frames
+    loading: bool,
+    playing: bool,
+    speed: usize,
+}
+
+enum PlayerAction {
+    TogglePlay,
+    Step(isize),
+    Seek(usize),
+    Faster,
+    Slower,
+    Tick,
+    Loaded { frames: usize, done: bool },
+}
+
+impl Reducible for Player {
+    type Action = PlayerAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let last = self.frames.saturating_sub(1);
+
+        let player = match action {
+            // Playing from the end starts over
+            PlayerAction::TogglePlay if !self.playing && self.index == last => Player {
+                index: 0,
+                playing: true,
+                ..*self
+            },
+            PlayerAction::TogglePlay => Player {
+                playing: !self.playing,
+                ..*self
+            },
+            PlayerAction::Step(delta) => Player {
+                index: self.index.saturating_add_signed(delta).min(last),
+                playing: false,
+                ..*self
+            },
+            PlayerAction::Seek(index) => Player {
+                index: index.min(last),
+                playing: false,
+                ..*self
+            },
+            PlayerAction::Faster => Player {
+                speed: (self.speed + 1).min(SPEEDS.len() - 1),
+                ..*self
+            },
+            PlayerAction::Slower => Player {
+                speed: self.speed.saturating_sub(1),
+                ..*self
+            },
+            // Keeps playing while waiting for more frames
+            PlayerAction::Tick => {
+                let index = (self.index + 1).min(last);
+                Player {
+                    index,
+                    playing: self.playing && (index < last || self.loading),
+                    ..*self
+                }
+            }
+            PlayerAction::Loaded { frames, done } => Player {
+                frames: self.frames + frames,
+                loading: !done,
+                ..*self
+            },
+        };
+
+        Rc::new(player)
+    }
+}
+
+#[function_component(Visualizer)]
+pub fn visualizer(props: &VisualizerProps) -> Html {
+    // The frames are kept outside of the player to not copy them on every batch
+    let frames = use_mut_ref(Vec::<Frame>::new);
+    let error = use_state(|| None::<String>);
+
+    let player = use_reducer_eq(|| Player {
+        index: 0,
+        frames: 0,
+        loading: true,
+        playing: false,
+        speed: DEFAULT_SPEED,
+    });
+
+    let bridge = {
+        let frames = frames.clone();
+        let error = error.clone();
+        let dispatcher = player.dispatcher();
+        use_reactor_bridge::<SolutionTask, _>(move |event| {
+            if let ReactorEvent::Output(Output::Frames(output)) = event {
+                match output.frames {
+                    Ok(batch) => {
+                        let count = batch.len();
+                        frames.borrow_mut().extend(batch);
+                        dispatcher.dispatch(PlayerAction::Loaded {
+                            frames: count,
+                            done: output.done,
+                        });
+                    }
+                    Err(err) => error.set(Some(err)),
+                }
+            }
+        })
+    };
+
+    {
+        // The visualizer is keyed by the route, so the frames are only requested once
+        let request = VisualizationRequest {
+            year: props.year,
+            day: props.day,
+            name: props.name.clone(),
+            input: None,
+        };
+        use_effect_with((), move |_| bridge.send(Request::Visualize(request)));
+    }
+
+    {
+        let dispatcher = player.dispatcher();
+        use_effect_with((player.playing, player.speed), move |(playing, speed)| {
+            let interval = playing.then(|| {
+                Interval::new(1000 / SPEEDS[*speed], move || {
+                    dispatcher.dispatch(PlayerAction::Tick)
+                })
+            });
+
+            move || drop(interval)
+        });
+    }
+
+    {
+        let dispatcher = player.dispatcher();
+        use_effect_with((), move |_| {
+            let listener = EventListener::new(&gloo::utils::document(), "keydown", move |e| {
+                let Some(e) = e.dyn_ref::<KeyboardEvent>() else {
+                    return;
+                };
+
+                if e.ctrl_key() || e.alt_key() || e.meta_key() {
+                    return;
+                }
+
+                let action = match e.key().as_str() {
+                    " " => PlayerAction::TogglePlay,
+                    "ArrowLeft" => PlayerAction::Step(-1),
+                    "ArrowRight" => PlayerAction::Step(1),
+                    "ArrowUp" => PlayerAction::Faster,
+                    "ArrowDown" => PlayerAction::Slower,
+                    _ => return,
+                };
+
+                e.prevent_default();
+                dispatcher.dispatch(action);
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    let frames = frames.borrow();
+    let count = player.frames;
+
+    if count == 0 {
+        return match (&*error, player.loading) {
+            (Some(err), _) => html! { <p>{ err }</p> },
+            (None, true) => html! { <p>{"Computing frames..."}</p> },
+            (None, false) => html! { <p>{"Nothing to visualize"}</p> },
+        };
+    }
+
+    let frame = &frames[player.index.min(count - 1)];
+
+    let dispatch = |action: fn() -> PlayerAction| {
+        let dispatcher = player.dispatcher();
+        Callback::from(move |_: MouseEvent| dispatcher.dispatch(action()))
+    };
+
+    let onseek = {
+        let dispatcher = player.dispatcher();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(index) = input.value().parse() {
+                dispatcher.dispatch(PlayerAction::Seek(index));
+            }
+        })
+    };
+
+    html! {
+        <div class="visualization">
+            <h2>{ format!("-- Day {} --", props.day) }</h2>
+            <div class="links">
+                <button onclick={dispatch(|| PlayerAction::TogglePlay)}>
+                    { if player.playing { "[Pause]" } else { "[Play]" } }
+                </button>
+                <button onclick={dispatch(|| PlayerAction::Step(-1))}>{"[<]"}</button>
+                <button onclick={dispatch(|| PlayerAction::Step(1))}>{"[>]"}</button>
+                <button onclick={dispatch(|| PlayerAction::Slower)}>{"[-]"}</button>
+                <span>{ format!("{} fps", SPEEDS[player.speed]) }</span>
+                <button onclick={dispatch(|| PlayerAction::Faster)}>{"[+]"}</button>
+            </div>
+            <input
+                type="range"
+                min="0"
+                max={(count - 1).to_string()}
+                value={player.index.to_string()}
+                oninput={onseek}
+            />
+            <p>{ format!("{}/{count} {}", player.index + 1, frame.caption) }</p>
+            { view_content(&frame.content) }
+        </div>
+    }
+}
+
+fn view_content(content: &Content) -> Html {
+    match content {
+        Content::Grid(rows) => html! {
+            <pre>
+                <code>{ rows.join("\n") }</code>
+            </pre>
+        },
+        Content::Voxels(voxels) => view_voxels(voxels),
+        Content::Graph(graph) => html! { <GraphView graph={graph.clone()} /> },
+    }
+}
+
+/// Draws the voxels in isometric projection, back to front.
+fn view_voxels(voxels: &[Voxel]) -> Html {
+    const SIZE: f64 = 8.0;
+    let (dx, dy) = (SIZE * 0.866, SIZE * 0.5);
+
+    let project = |(x, y, z): (f64, f64, f64)| ((x - y) * dx, (x + y) * dy - z * SIZE);
+
+    let mut sorted: Vec<&Voxel> = voxels.iter().collect();
+    sorted.sort_by_key(|voxel| {
+        let (x, y, z) = voxel.position;
+        (x + y + z, z)
+    });
+
+    let faces = sorted.iter().map(|voxel| {
+        let (x, y, z) = voxel.position;
+        let (x, y, z) = (x as f64, y as f64, z as f64);
+
+        let points = |corners: [(f64, f64, f64); 4]| {
+            corners
+                .iter()
+                .map(|corner| {
+                    let (px, py) = project(*corner);
+                    format!("{px:.1},{py:.1}")
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let top = points([
+            (x, y, z + 1.0),
+            (x + 1.0, y, z + 1.0),
+            (x + 1.0, y + 1.0, z + 1.0),
+            (x, y + 1.0, z + 1.0),
+        ]);
+        let left = points([
+            (x, y + 1.0, z),
+            (x + 1.0, y + 1.0, z),
+            (x + 1.0, y + 1.0, z + 1.0),
+            (x, y + 1.0, z + 1.0),
+        ]);
+        let right = points([
+            (x + 1.0, y, z),
+            (x + 1.0, y + 1.0, z),
+            (x + 1.0, y + 1.0, z + 1.0),
+            (x + 1.0, y, z + 1.0),
+        ]);
+
+        html! {
+            <g fill={voxel.color.to_string()}>
+                <polygon points={top} />
+                <polygon points={left} style="filter: brightness(0.7)" />
+                <polygon points={right} style="filter: brightness(0.5)" />
+            </g>
+        }
+    });
+
+    let corners = voxels.iter().flat_map(|voxel| {
+        let (x, y, z) = voxel.position;
+        let (x, y, z) = (x as f64, y as f64, z as f64);
+        [
+            project((x, y + 1.0, z)),
+            project((x + 1.0, y, z)),
+            project((x, y, z + 1.0)),
+            project((x + 1.0, y + 1.0, z)),
+        ]
+    });
+    let (min_x, min_y, max_x, max_y) = corners.fold(
+        (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+        |(min_x, min_y, max_x, max_y), (x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    );
+
+    html! {
+        <svg
+            class="voxels"
+            viewBox={format!("{min_x} {min_y} {} {}", max_x - min_x, max_y - min_y)}
+        >
+            { for faces }
+        </svg>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct GraphViewProps {
+    graph: Graph,
+}
+
+/// Draws the graph at the positions of its layout. Clicking a node selects it and its edges.
+#[function_component(GraphView)]
+fn graph_view(props: &GraphViewProps) -> Html {
+    let graph = &props.graph;
+    let selected = use_state(|| None::<usize>);
+
+    // The solver lays out the graph, as that is too slow to do on the page
+    let positions = &graph.positions;
+    if positions.len() != graph.nodes.len() {
+        return html! { <p>{"The graph has no layout"}</p> };
+    }
+
+    let is_selected = |edge: &&Edge| Some(edge.from) == *selected || Some(edge.to) == *selected;
+
+    let neighbours: Vec<usize> = graph
+        .edges
+        .iter()
+        .filter(is_selected)
+        .flat_map(|edge| [edge.from, edge.to])
+        .collect();
+
+    // Edges of the selected node are drawn last to get them on top
+    let (selected_edges, edges): (Vec<&Edge>, Vec<&Edge>) =
+        graph.edges.iter().partition(is_selected);
+
+    let lines = edges
+        .iter()
+        .map(|edge| (edge, false))
+        .chain(selected_edges.iter().map(|edge| (edge, true)))
+        .map(|(edge, highlighted)| {
+            let ((x1, y1), (x2, y2)) = (positions[edge.from], positions[edge.to]);
+            let class = if highlighted { "selected" } else { "" };
+
+            html! {
+                <line
+                    class={class}
+                    x1={x1.to_string()}
+                    y1={y1.to_string()}
+                    x2={x2.to_string()}
+                    y2={y2.to_string()}
+                    stroke={edge.color.to_string()}
+                />
+            }
+        });
+
+    let show_all_labels = graph.nodes.len() <= MAX_LABELS;
+    let radius = if show_all_labels { 4.0 } else { 2.0 };
+
+    let nodes = graph.nodes.iter().enumerate().map(|(index, node)| {
+        let (x, y) = positions[index];
+        let onclick = {
+            let selected = selected.clone();
+            Callback::from(move |_: MouseEvent| {
+                selected.set((*selected != Some(index)).then_some(index))
+            })
+        };
+
+        let show_label = show_all_labels || *selected == Some(index) || neighbours.contains(&index);
+        let (cx, cy) = (x.to_string(), y.to_string());
+        let (label_x, label_y) = ((x + 6.0).to_string(), (y + 4.0).to_string());
+
+        html! {
+            <g class="node" {onclick}>
+                <circle {cx} {cy} r={radius.to_string()} fill={node.color.to_string()}>
+                    <title>{ node.label.clone() }</title>
+                </circle>
+                if show_label {
+                    <text x={label_x} y={label_y}>{ node.label.clone() }</text>
+                }
+            </g>
+        }
+    });
+
+    let (min_x, min_y, max_x, max_y) = positions.iter().fold(
+        (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+        |(min_x, min_y, max_x, max_y), (x, y)| {
+            (min_x.min(*x), min_y.min(*y), max_x.max(*x), max_y.max(*y))
+        },
+    );
+    let margin = 40.0;
+
+    html! {
+        <svg
+            class="graph"
+            viewBox={format!(
+                "{} {} {} {}",
+                min_x - margin,
+                min_y - margin,
+                max_x - min_x + margin * 2.0,
+                max_y - min_y + margin * 2.0
+            )}
+        >
+            <g class="edges">{ for lines }</g>
+            { for nodes }
+        </svg>
+    }
+}
@@ -1,7 +0,0 @@
-mod cube;
-mod lava;
-mod rope;
-
-pub use self::cube::Cube;
-pub use self::lava::Lava;
-pub use self::rope::Rope;
@@ -1,9 +1,9 @@
 use yew::prelude::*;
 use yew_router::components::Link;
 
-use crate::router::Route;
+use aoc_solver::registry::YEARS;
 
-const YEARS: &[u32] = &[2023, 2022, 2021, 2020];
+use crate::router::Route;
 
 #[derive(Properties, PartialEq)]
 pub struct YearProps {